@@ -19,7 +19,8 @@ pub enum SectionKind {
     ///
     /// Example ELF sections: `.rodata`
     ///
-    /// Example Mach-O sections: `__TEXT/__const`, `__DATA/__const`, `__TEXT/__literal4`
+    /// Example Mach-O sections: `__TEXT/__const`, `__DATA/__const`, `__DATA_CONST/__const`,
+    /// `__TEXT/__literal4`
     ReadOnlyData,
     /// A loadable string section.
     ///
@@ -86,6 +87,33 @@ impl SectionKind {
     }
 }
 
+/// The operating system platform that a file is built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// macOS.
+    MacOS,
+    /// iOS.
+    IOS,
+    /// tvOS.
+    TvOS,
+    /// watchOS.
+    WatchOS,
+    /// bridgeOS.
+    BridgeOS,
+    /// iOS apps running on macOS (Mac Catalyst).
+    MacCatalyst,
+    /// The iOS simulator.
+    IOSSimulator,
+    /// The tvOS simulator.
+    TvOSSimulator,
+    /// The watchOS simulator.
+    WatchOSSimulator,
+    /// DriverKit.
+    DriverKit,
+    /// Some other Mach-O platform. The value is the `platform` field of `LC_BUILD_VERSION`.
+    MachO(u32),
+}
+
 /// The kind of a symbol.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SymbolKind {
@@ -133,6 +161,7 @@ pub enum SymbolScope {
 /// * GotBase - The address of the global offset table.
 /// * Image - The base address of the image.
 /// * Section - The address of the section containing the symbol.
+/// * T - The address of the symbol's thread local variable descriptor.
 ///
 /// 'XxxRelative' means 'Xxx + A - P'.  'XxxOffset' means 'S + A - Xxx'.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -157,6 +186,10 @@ pub enum RelocationKind {
     SectionOffset,
     /// The index of the section containing the symbol.
     SectionIndex,
+    /// T + A
+    Tlv,
+    /// T + A - P
+    TlvRelative,
     /// Some other ELF relocation. The value is dependent on the architecture.
     Elf(u32),
     /// Some other Mach-O relocation. The value is dependent on the architecture.
@@ -195,6 +228,18 @@ pub enum RelocationEncoding {
     ///
     /// The `RelocationKind` must be PC relative.
     X86Branch,
+
+    /// AArch64 `adrp` instruction.
+    ///
+    /// The result is the difference between the 4KB pages containing the
+    /// target and the place.
+    ///
+    /// The `RelocationKind` must be PC relative.
+    AArch64Page21,
+    /// AArch64 instruction with a 12-bit immediate offset, such as `add` or `ldr`.
+    ///
+    /// The result is the offset of the target within its 4KB page.
+    AArch64PageOffset12,
 }
 
 /// File flags that are specific to each file format.
@@ -32,7 +32,7 @@ pub mod endian;
 mod pod;
 // This isn't really intended for users yet, but other traits required it.
 #[doc(hidden)]
-pub use pod::Pod;
+pub use pod::{Bytes, Pod};
 
 #[cfg(feature = "read_core")]
 pub mod read;
@@ -7,7 +7,7 @@
 
 #![allow(missing_docs)]
 
-use crate::endian::{BigEndian, Endian, U64Bytes, U16, U32, U64};
+use crate::endian::{BigEndian, Endian, U32Bytes, U64Bytes, I32, U16, U32, U64};
 use crate::pod::Pod;
 
 // Definitions from "/usr/include/mach-o/machine.h".
@@ -266,6 +266,8 @@ pub const CPU_SUBTYPE_ARM64_32_V8: u32 = 1;
 
 // Definitions from "/usr/include/mach/vm_prot.h".
 
+/// no permissions
+pub const VM_PROT_NONE: u32 = 0x00;
 /// read permission
 pub const VM_PROT_READ: u32 = 0x01;
 /// write permission
@@ -451,6 +453,8 @@ pub const MH_DYLIB_STUB: u32 = 0x9;
 pub const MH_DSYM: u32 = 0xa;
 /// x86_64 kexts
 pub const MH_KEXT_BUNDLE: u32 = 0xb;
+/// set of mach-o's
+pub const MH_FILESET: u32 = 0xc;
 
 // Values for `MachHeader*::flags`.
 /// the object file has no undefined references
@@ -667,6 +671,8 @@ pub const LC_BUILD_VERSION: u32 = 0x32;
 pub const LC_DYLD_EXPORTS_TRIE: u32 = (0x33 | LC_REQ_DYLD);
 /// used with `LinkeditDataCommand`
 pub const LC_DYLD_CHAINED_FIXUPS: u32 = (0x34 | LC_REQ_DYLD);
+/// used with `FilesetEntryCommand`
+pub const LC_FILESET_ENTRY: u32 = 0x35 | LC_REQ_DYLD;
 
 /// A variable length string in a load command.
 ///
@@ -1840,6 +1846,7 @@ pub const PLATFORM_IOSMAC: u32 = 6;
 pub const PLATFORM_IOSSIMULATOR: u32 = 7;
 pub const PLATFORM_TVOSSIMULATOR: u32 = 8;
 pub const PLATFORM_WATCHOSSIMULATOR: u32 = 9;
+pub const PLATFORM_DRIVERKIT: u32 = 10;
 
 /* Known values for the tool field above. */
 pub const TOOL_CLANG: u32 = 1;
@@ -2196,6 +2203,29 @@ pub struct NoteCommand<E: Endian> {
     pub size: U64<E>,
 }
 
+/*
+ * LC_FILESET_ENTRY commands describe constituent Mach-O files that are part
+ * of a fileset. In one implementation, entries are dylibs with individual
+ * mach headers and repositionable text and data segments. Each entry is
+ * further described by its own mach header.
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct FilesetEntryCommand<E: Endian> {
+    /// LC_FILESET_ENTRY
+    pub cmd: U32<E>,
+    /// includes entry_id string
+    pub cmdsize: U32<E>,
+    /// memory address of the entry
+    pub vmaddr: U64<E>,
+    /// file offset of the entry
+    pub fileoff: U64<E>,
+    /// contained entry id
+    pub entry_id: LcStr<E>,
+    /// reserved
+    pub reserved: U32<E>,
+}
+
 // Definitions from "/usr/include/mach-o/nlist.h".
 
 #[derive(Debug, Clone, Copy)]
@@ -2654,7 +2684,7 @@ impl RelocationInfo {
                     | u32::from(self.r_extern) << 27
                     | u32::from(self.r_type) << 28
             } else {
-                self.r_symbolnum >> 8
+                self.r_symbolnum << 8
                     | u32::from(self.r_pcrel) << 7
                     | u32::from(self.r_length & 0x3) << 5
                     | u32::from(self.r_extern) << 4
@@ -3108,7 +3138,288 @@ pub const X86_64_RELOC_SIGNED_4: u8 = 8;
 /// for thread local variables
 pub const X86_64_RELOC_TLV: u8 = 9;
 
-unsafe_impl_pod!(FatHeader, FatArch32, FatArch64,);
+// Definitions from "/usr/include/mach-o/fixup-chains.h".
+
+/// header of the LC_DYLD_CHAINED_FIXUPS payload
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedFixupsHeader<E: Endian> {
+    /// 0
+    pub fixups_version: U32<E>,
+    /// offset of dyld_chained_starts_in_image in chain_data
+    pub starts_offset: U32<E>,
+    /// offset of imports table in chain_data
+    pub imports_offset: U32<E>,
+    /// offset of symbol strings in chain_data
+    pub symbols_offset: U32<E>,
+    /// number of imported symbol names
+    pub imports_count: U32<E>,
+    /// DYLD_CHAINED_IMPORT*
+    pub imports_format: U32<E>,
+    /// 0 => uncompressed, 1 => zlib compressed
+    pub symbols_format: U32<E>,
+}
+
+/// This struct is embedded in LC_DYLD_CHAINED_FIXUPS payload
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedStartsInImage<E: Endian> {
+    pub seg_count: U32<E>,
+    // followed by `seg_count` U32 offsets of `DyldChainedStartsInSegment`
+}
+
+/// This struct is embedded in dyld_chain_starts_in_image
+/// and passed down to the kernel for page-in linking
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedStartsInSegment<E: Endian> {
+    /// size of this (amount kernel needs to copy)
+    pub size: U32<E>,
+    /// 0x1000 or 0x4000
+    pub page_size: U16<E>,
+    /// DYLD_CHAINED_PTR_*
+    pub pointer_format: U16<E>,
+    /// offset in memory to start of segment
+    pub segment_offset: U64Bytes<E>,
+    /// for 32-bit OS, any value beyond this is not a pointer
+    pub max_valid_pointer: U32<E>,
+    /// how many pages are in array
+    pub page_count: U16<E>,
+    // followed by `page_count` U16 page starts
+}
+
+// values for dyld_chained_starts_in_segment.pointer_format
+/// stride 8, unauth target is vmaddr
+pub const DYLD_CHAINED_PTR_ARM64E: u16 = 1;
+/// target is vmaddr
+pub const DYLD_CHAINED_PTR_64: u16 = 2;
+pub const DYLD_CHAINED_PTR_32: u16 = 3;
+pub const DYLD_CHAINED_PTR_32_CACHE: u16 = 4;
+pub const DYLD_CHAINED_PTR_32_FIRMWARE: u16 = 5;
+/// target is vm offset
+pub const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+/// old name
+pub const DYLD_CHAINED_PTR_ARM64E_OFFSET: u16 = 7;
+pub const DYLD_CHAINED_PTR_ARM64E_KERNEL: u16 = 7;
+pub const DYLD_CHAINED_PTR_64_KERNEL_CACHE: u16 = 8;
+/// stride 8, unauth target is vm offset
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND: u16 = 9;
+/// stride 4, unauth target is vmaddr
+pub const DYLD_CHAINED_PTR_ARM64E_FIRMWARE: u16 = 10;
+/// stride 1, x86_64 kernel caches
+pub const DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE: u16 = 11;
+/// stride 8, unauth target is vm offset, 24-bit bind
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND24: u16 = 12;
+
+// values for dyld_chained_fixups_header.imports_format
+pub const DYLD_CHAINED_IMPORT: u32 = 1;
+pub const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+pub const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+
+/// DYLD_CHAINED_IMPORT
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedImport<E: Endian> {
+    /// lib_ordinal:8, weak_import:1, name_offset:23
+    pub import: U32<E>,
+}
+
+/// DYLD_CHAINED_IMPORT_ADDEND
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedImportAddend<E: Endian> {
+    /// lib_ordinal:8, weak_import:1, name_offset:23
+    pub import: U32<E>,
+    pub addend: I32<E>,
+}
+
+/// DYLD_CHAINED_IMPORT_ADDEND64
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedImportAddend64<E: Endian> {
+    /// lib_ordinal:16, weak_import:1, reserved:15, name_offset:32
+    pub import: U64Bytes<E>,
+    pub addend: U64Bytes<E>,
+}
+
+// Definitions from "osfmk/kern/cs_blobs.h" in xnu.
+
+/*
+ * Code signing data is always big endian, regardless of the endianness
+ * of the file that contains it.
+ */
+
+/*
+ * Magic numbers used by code signing.
+ */
+/// single Requirement blob
+pub const CSMAGIC_REQUIREMENT: u32 = 0xfade_0c00;
+/// Requirements vector (internal requirements)
+pub const CSMAGIC_REQUIREMENTS: u32 = 0xfade_0c01;
+/// CodeDirectory blob
+pub const CSMAGIC_CODEDIRECTORY: u32 = 0xfade_0c02;
+/// embedded form of signature data
+pub const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade_0cc0;
+/// multi-arch collection of embedded signatures
+pub const CSMAGIC_DETACHED_SIGNATURE: u32 = 0xfade_0cc1;
+/// CMS signature, among other things
+pub const CSMAGIC_BLOBWRAPPER: u32 = 0xfade_0b01;
+/// embedded entitlements
+pub const CSMAGIC_EMBEDDED_ENTITLEMENTS: u32 = 0xfade_7171;
+/// embedded DER encoded entitlements
+pub const CSMAGIC_EMBEDDED_DER_ENTITLEMENTS: u32 = 0xfade_7172;
+
+/*
+ * Slot types in the index of a SuperBlob.
+ */
+/// slot index for CodeDirectory
+pub const CSSLOT_CODEDIRECTORY: u32 = 0;
+pub const CSSLOT_INFOSLOT: u32 = 1;
+pub const CSSLOT_REQUIREMENTS: u32 = 2;
+pub const CSSLOT_RESOURCEDIR: u32 = 3;
+pub const CSSLOT_APPLICATION: u32 = 4;
+pub const CSSLOT_ENTITLEMENTS: u32 = 5;
+pub const CSSLOT_DER_ENTITLEMENTS: u32 = 7;
+/// first alternate CodeDirectory, if any
+pub const CSSLOT_ALTERNATE_CODEDIRECTORIES: u32 = 0x1000;
+/// CMS Signature
+pub const CSSLOT_SIGNATURESLOT: u32 = 0x10000;
+
+/*
+ * Structure of an embedded-signature SuperBlob
+ *
+ * Blobs are not padded within a SuperBlob, so these use unaligned fields.
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CsBlobIndex {
+    /// type of entry
+    pub typ: U32Bytes<BigEndian>,
+    /// offset of entry
+    pub offset: U32Bytes<BigEndian>,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CsSuperBlob {
+    /// magic number
+    pub magic: U32Bytes<BigEndian>,
+    /// total length of SuperBlob
+    pub length: U32Bytes<BigEndian>,
+    /// number of index entries following
+    pub count: U32Bytes<BigEndian>,
+    // followed by `count` CsBlobIndex entries
+}
+
+/*
+ * C form of a CodeDirectory.
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CsCodeDirectory {
+    /// magic number (CSMAGIC_CODEDIRECTORY)
+    pub magic: U32Bytes<BigEndian>,
+    /// total length of CodeDirectory blob
+    pub length: U32Bytes<BigEndian>,
+    /// compatibility version
+    pub version: U32Bytes<BigEndian>,
+    /// setup and mode flags
+    pub flags: U32Bytes<BigEndian>,
+    /// offset of hash slot element at index zero
+    pub hash_offset: U32Bytes<BigEndian>,
+    /// offset of identifier string
+    pub ident_offset: U32Bytes<BigEndian>,
+    /// number of special hash slots
+    pub n_special_slots: U32Bytes<BigEndian>,
+    /// number of ordinary (code) hash slots
+    pub n_code_slots: U32Bytes<BigEndian>,
+    /// limit to main image signature range
+    pub code_limit: U32Bytes<BigEndian>,
+    /// size of each hash in bytes
+    pub hash_size: u8,
+    /// type of hash (cdHashType* constants)
+    pub hash_type: u8,
+    /// platform identifier; zero if not platform binary
+    pub platform: u8,
+    /// log2(page size in bytes); 0 => infinite
+    pub page_size: u8,
+    /// unused (must be zero)
+    pub spare2: U32Bytes<BigEndian>,
+    // Version 0x20100 adds `scatterOffset`.
+    // Version 0x20200 adds `teamOffset`.
+    // Version 0x20300 adds `spare3` and `codeLimit64`.
+    // Version 0x20400 adds `execSegBase`, `execSegLimit` and `execSegFlags`.
+}
+
+/// offset of optional `scatterOffset` field in a CodeDirectory
+pub const CS_SCATTER_OFFSET: usize = 44;
+/// offset of optional `teamOffset` field in a CodeDirectory
+pub const CS_TEAM_OFFSET: usize = 48;
+
+/// CodeDirectory version that supports `scatterOffset`
+pub const CS_SUPPORTSSCATTER: u32 = 0x20100;
+/// CodeDirectory version that supports `teamOffset`
+pub const CS_SUPPORTSTEAMID: u32 = 0x20200;
+/// CodeDirectory version that supports `codeLimit64`
+pub const CS_SUPPORTSCODELIMIT64: u32 = 0x20300;
+/// CodeDirectory version that supports the executable segment fields
+pub const CS_SUPPORTSEXECSEG: u32 = 0x20400;
+
+/*
+ * Code signing attributes of a process, stored in the `flags` field of a CodeDirectory.
+ */
+/// dynamically valid
+pub const CS_VALID: u32 = 0x0000_0001;
+/// ad hoc signed
+pub const CS_ADHOC: u32 = 0x0000_0002;
+/// has get-task-allow entitlement
+pub const CS_GET_TASK_ALLOW: u32 = 0x0000_0004;
+/// has installer entitlement
+pub const CS_INSTALLER: u32 = 0x0000_0008;
+/// Library Validation required by Hardened System Policy
+pub const CS_FORCED_LV: u32 = 0x0000_0010;
+/// (macOS Only) Page invalidation allowed by task port policy
+pub const CS_INVALID_ALLOWED: u32 = 0x0000_0020;
+/// don't load invalid pages
+pub const CS_HARD: u32 = 0x0000_0100;
+/// kill process if it becomes invalid
+pub const CS_KILL: u32 = 0x0000_0200;
+/// force expiration checking
+pub const CS_CHECK_EXPIRATION: u32 = 0x0000_0400;
+/// tell dyld to treat restricted
+pub const CS_RESTRICT: u32 = 0x0000_0800;
+/// require enforcement
+pub const CS_ENFORCEMENT: u32 = 0x0000_1000;
+/// require library validation
+pub const CS_REQUIRE_LV: u32 = 0x0000_2000;
+/// code signature permits restricted entitlements
+pub const CS_ENTITLEMENTS_VALIDATED: u32 = 0x0000_4000;
+/// has com.apple.rootless.restricted-nvram-variables.heritable entitlement
+pub const CS_NVRAM_UNRESTRICTED: u32 = 0x0000_8000;
+/// Apply hardened runtime policies
+pub const CS_RUNTIME: u32 = 0x0001_0000;
+/// Automatically signed by the linker
+pub const CS_LINKER_SIGNED: u32 = 0x0002_0000;
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CsGenericBlob {
+    /// magic number
+    pub magic: U32Bytes<BigEndian>,
+    /// total length of blob
+    pub length: U32Bytes<BigEndian>,
+    // followed by the blob data
+}
+
+unsafe_impl_pod!(
+    FatHeader,
+    FatArch32,
+    FatArch64,
+    CsBlobIndex,
+    CsSuperBlob,
+    CsCodeDirectory,
+    CsGenericBlob,
+);
 unsafe_impl_endian_pod!(
     MachHeader32,
     MachHeader64,
@@ -3158,6 +3469,13 @@ unsafe_impl_endian_pod!(
     DataInCodeEntry,
     //TlvDescriptor,
     NoteCommand,
+    FilesetEntryCommand,
+    DyldChainedFixupsHeader,
+    DyldChainedStartsInImage,
+    DyldChainedStartsInSegment,
+    DyldChainedImport,
+    DyldChainedImportAddend,
+    DyldChainedImportAddend64,
     Nlist32,
     Nlist64,
     Relocation,
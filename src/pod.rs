@@ -73,17 +73,24 @@ impl<'data> fmt::Debug for Bytes<'data> {
     }
 }
 
+// The errors are converted to `read::Error` by the callers.
+#[allow(clippy::result_unit_err)]
 impl<'data> Bytes<'data> {
+    /// Return the length of the byte slice.
     #[inline]
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    /// Return true if the byte slice is empty.
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
+    /// Skip over the given number of bytes.
+    ///
+    /// Returns an error and empties the slice if there are not enough bytes.
     #[inline]
     pub fn skip(&mut self, offset: usize) -> Result<()> {
         match self.0.get(offset..) {
@@ -98,6 +105,7 @@ impl<'data> Bytes<'data> {
         }
     }
 
+    /// Read the given number of bytes.
     #[inline]
     pub fn read_bytes(&mut self, count: usize) -> Result<Bytes<'data>> {
         match (self.0.get(..count), self.0.get(count..)) {
@@ -112,12 +120,16 @@ impl<'data> Bytes<'data> {
         }
     }
 
+    /// Read the given number of bytes at an offset.
     #[inline]
     pub fn read_bytes_at(mut self, offset: usize, count: usize) -> Result<Bytes<'data>> {
         self.skip(offset)?;
         self.read_bytes(count)
     }
 
+    /// Read a `Pod` type.
+    ///
+    /// Returns an error if there are not enough bytes or the data is not aligned.
     #[inline]
     pub fn read<T: Pod>(&mut self) -> Result<&'data T> {
         match from_bytes(self.0) {
@@ -132,12 +144,14 @@ impl<'data> Bytes<'data> {
         }
     }
 
+    /// Read a `Pod` type at an offset.
     #[inline]
     pub fn read_at<T: Pod>(mut self, offset: usize) -> Result<&'data T> {
         self.skip(offset)?;
         self.read()
     }
 
+    /// Read a slice of a `Pod` type.
     #[inline]
     pub fn read_slice<T: Pod>(&mut self, count: usize) -> Result<&'data [T]> {
         match slice_from_bytes(self.0, count) {
@@ -152,6 +166,7 @@ impl<'data> Bytes<'data> {
         }
     }
 
+    /// Read a slice of a `Pod` type at an offset.
     #[inline]
     pub fn read_slice_at<T: Pod>(mut self, offset: usize, count: usize) -> Result<&'data [T]> {
         self.skip(offset)?;
@@ -186,6 +201,50 @@ impl<'data> Bytes<'data> {
         self.skip(offset)?;
         self.read_string()
     }
+
+    /// Read an unsigned LEB128 number.
+    ///
+    /// Returns an error if the number is truncated or does not fit in a `u64`.
+    pub fn read_uleb128(&mut self) -> Result<u64> {
+        let mut result = 0;
+        let mut shift = 0;
+        loop {
+            let byte = *self.read::<u8>()?;
+            if shift == 63 && byte > 1 {
+                self.0 = &[];
+                return Err(());
+            }
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Read a signed LEB128 number.
+    ///
+    /// Returns an error if the number is truncated or does not fit in an `i64`.
+    pub fn read_sleb128(&mut self) -> Result<i64> {
+        let mut result = 0;
+        let mut shift = 0;
+        loop {
+            let byte = *self.read::<u8>()?;
+            if shift == 63 && byte != 0 && byte != 0x7f {
+                self.0 = &[];
+                return Err(());
+            }
+            result |= i64::from(byte & 0x7f) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    // Sign extend.
+                    result |= !0 << shift;
+                }
+                return Ok(result);
+            }
+        }
+    }
 }
 
 /// A newtype for byte vectors.
@@ -421,6 +480,84 @@ mod tests {
         assert_eq!(data.read_string_at(3), Err(()));
     }
 
+    #[test]
+    fn uleb128() {
+        let tests: &[(&[u8], u64)] = &[
+            (&[0x00], 0),
+            (&[0x7f], 0x7f),
+            (&[0x80, 0x01], 0x80),
+            (&[0xe5, 0x8e, 0x26], 624_485),
+            (&[0x80, 0x80, 0x00], 0),
+            (
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+                u64::MAX,
+            ),
+        ];
+        for &(data, value) in tests {
+            let mut bytes = Bytes(data);
+            assert_eq!(bytes.read_uleb128(), Ok(value));
+            assert_eq!(bytes, Bytes(&[]));
+        }
+
+        let mut bytes = Bytes(&[0x7f, 0x01]);
+        assert_eq!(bytes.read_uleb128(), Ok(0x7f));
+        assert_eq!(bytes, Bytes(&[0x01]));
+
+        // Truncated.
+        let mut bytes = Bytes(&[]);
+        assert_eq!(bytes.read_uleb128(), Err(()));
+        let mut bytes = Bytes(&[0x80, 0x80]);
+        assert_eq!(bytes.read_uleb128(), Err(()));
+        assert_eq!(bytes, Bytes(&[]));
+
+        // Overflow.
+        let mut bytes = Bytes(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]);
+        assert_eq!(bytes.read_uleb128(), Err(()));
+        assert_eq!(bytes, Bytes(&[]));
+        let mut bytes = Bytes(&[
+            0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00,
+        ]);
+        assert_eq!(bytes.read_uleb128(), Err(()));
+    }
+
+    #[test]
+    fn sleb128() {
+        let tests: &[(&[u8], i64)] = &[
+            (&[0x00], 0),
+            (&[0x3f], 0x3f),
+            (&[0x40], -0x40),
+            (&[0x7f], -1),
+            (&[0x80, 0x01], 0x80),
+            (&[0x80, 0x7f], -0x80),
+            (&[0xc0, 0xbb, 0x78], -123_456),
+            (
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00],
+                i64::MAX,
+            ),
+            (
+                &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f],
+                i64::MIN,
+            ),
+        ];
+        for &(data, value) in tests {
+            let mut bytes = Bytes(data);
+            assert_eq!(bytes.read_sleb128(), Ok(value));
+            assert_eq!(bytes, Bytes(&[]));
+        }
+
+        // Truncated.
+        let mut bytes = Bytes(&[]);
+        assert_eq!(bytes.read_sleb128(), Err(()));
+        let mut bytes = Bytes(&[0xff]);
+        assert_eq!(bytes.read_sleb128(), Err(()));
+        assert_eq!(bytes, Bytes(&[]));
+
+        // Overflow.
+        let mut bytes = Bytes(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+        assert_eq!(bytes.read_sleb128(), Err(()));
+        assert_eq!(bytes, Bytes(&[]));
+    }
+
     #[test]
     fn bytes_mut() {
         let data = BytesMut(vec![0x01, 0x23, 0x45, 0x67]);
@@ -1,6 +1,7 @@
 #[cfg(feature = "compression")]
 use alloc::borrow::Cow;
 use alloc::fmt;
+use alloc::string::String;
 use target_lexicon::{Architecture, BinaryFormat};
 
 #[cfg(feature = "coff")]
@@ -14,8 +15,8 @@ use crate::read::pe;
 #[cfg(feature = "wasm")]
 use crate::read::wasm;
 use crate::read::{
-    self, Error, FileFlags, Object, ObjectSection, ObjectSegment, Relocation, Result, SectionFlags,
-    SectionIndex, SectionKind, Symbol, SymbolIndex, SymbolMap,
+    self, Error, FileFlags, Object, ObjectSection, ObjectSegment, Platform, Relocation, Result,
+    SectionFlags, SectionIndex, SectionKind, Symbol, SymbolIndex, SymbolMap,
 };
 
 /// Evaluate an expression on the contents of a file format enum.
@@ -326,10 +327,20 @@ where
         with_inner!(self.inner, FileInternal, |x| x.gnu_debuglink())
     }
 
+    #[inline]
+    fn code_id(&self) -> Option<String> {
+        with_inner!(self.inner, FileInternal, |x| x.code_id())
+    }
+
     fn entry(&self) -> u64 {
         with_inner!(self.inner, FileInternal, |x| x.entry())
     }
 
+    #[inline]
+    fn platform(&self) -> Option<Platform> {
+        with_inner!(self.inner, FileInternal, |x| x.platform())
+    }
+
     fn flags(&self) -> FileFlags {
         with_inner!(self.inner, FileInternal, |x| x.flags())
     }
@@ -8,6 +8,7 @@
 #[cfg(feature = "compression")]
 use alloc::borrow::Cow;
 use alloc::fmt;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
@@ -334,6 +335,10 @@ where
         false
     }
 
+    fn code_id(&self) -> Option<String> {
+        self.build_id().ok()?.map(util::hex)
+    }
+
     fn build_id(&self) -> read::Result<Option<&'data [u8]>> {
         let endian = self.endian;
         // Use section headers if present, otherwise use program headers.
@@ -5,20 +5,25 @@
 //!
 //! Also provides `MachOFile` and related types which implement the `Object` trait.
 
-#[cfg(feature = "compression")]
 use alloc::borrow::Cow;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
 use alloc::vec::Vec;
+use core::convert::TryFrom;
 use core::fmt::Debug;
 use core::marker::PhantomData;
-use core::{fmt, mem, result, slice, str};
-use target_lexicon::{Aarch64Architecture, Architecture, ArmArchitecture};
+use core::{cmp, fmt, mem, result, slice, str};
+use target_lexicon::{
+    Aarch64Architecture, Architecture, ArmArchitecture, BinaryFormat, Environment, OperatingSystem,
+    Triple, Vendor,
+};
 
 use crate::endian::{self, BigEndian, Endian, RunTimeEndian};
 use crate::macho;
 use crate::pod::{Bytes, Pod};
-use crate::read::util::StringTable;
+use crate::read::util::{self, StringTable};
 use crate::read::{
-    self, Error, FileFlags, Object, ObjectSection, ObjectSegment, ReadError, Relocation,
+    self, Error, FileFlags, Object, ObjectSection, ObjectSegment, Platform, ReadError, Relocation,
     RelocationEncoding, RelocationKind, RelocationTarget, Result, SectionFlags, SectionIndex,
     SectionKind, Symbol, SymbolFlags, SymbolIndex, SymbolKind, SymbolMap, SymbolScope,
     SymbolSection,
@@ -38,32 +43,78 @@ pub struct MachOFile<'data, Mach: MachHeader> {
     header: &'data Mach,
     sections: Vec<MachOSectionInternal<'data, Mach>>,
     symbols: SymbolTable<'data, Mach>,
+    truncated: bool,
     data: Bytes<'data>,
+    header_offset: u64,
 }
 
 impl<'data, Mach: MachHeader> MachOFile<'data, Mach> {
     /// Parse the raw Mach-O file data.
+    ///
+    /// Returns an error if the header has load commands (`ncmds` is non-zero)
+    /// but `sizeofcmds` is zero.
     pub fn parse(data: &'data [u8]) -> Result<Self> {
+        Self::parse_with_options(data, ParseOptions::default())
+    }
+
+    /// Parse the raw Mach-O file data, with limits on the amount of parsing.
+    ///
+    /// Returns an error if the file exceeds any of the limits in `options`, or if
+    /// the header has load commands (`ncmds` is non-zero) but `sizeofcmds` is zero.
+    pub fn parse_with_options(data: &'data [u8], options: ParseOptions) -> Result<Self> {
+        Self::parse_at_with_options(data, 0, options)
+    }
+
+    /// Parse a Mach-O image whose header is at the given offset within the file data.
+    ///
+    /// This is used for the entries of an `MH_FILESET` file, such as a kernel collection.
+    /// The file offsets in the image's load commands, including those for the
+    /// symbol table, are relative to the start of `data` rather than to the header.
+    /// Use `fileset_entries` to find the header offset of each entry.
+    pub fn parse_at(data: &'data [u8], header_offset: u64) -> Result<Self> {
+        Self::parse_at_with_options(data, header_offset, ParseOptions::default())
+    }
+
+    fn parse_at_with_options(
+        data: &'data [u8],
+        header_offset: u64,
+        options: ParseOptions,
+    ) -> Result<Self> {
         let data = Bytes(data);
         let header = data
-            .read_at::<Mach>(0)
+            .read_at::<Mach>(header_offset as usize)
             .read_error("Invalid Mach-O header size or alignment")?;
         if !header.is_supported() {
             return Err(Error("Unsupported Mach-O header"));
         }
 
         let endian = header.endian().read_error("Unsupported Mach-O endian")?;
+        if header.ncmds(endian) != 0 && header.sizeofcmds(endian) == 0 {
+            return Err(Error("Invalid Mach-O load command table size"));
+        }
+        if let Some(max_commands) = options.max_commands {
+            if header.ncmds(endian) > max_commands {
+                return Err(Error("Too many Mach-O load commands"));
+            }
+        }
 
-        let mut symbols = &[][..];
+        let mut symbols: &[Mach::Nlist] = &[];
         let mut strings = Bytes(&[]);
         // Build a list of sections to make some operations more efficient.
         let mut sections = Vec::new();
-        if let Ok(mut commands) = header.load_commands(endian, data) {
+        let mut truncated = false;
+        if let Ok(mut commands) = header.load_commands_at(endian, data, header_offset) {
             while let Ok(Some(command)) = commands.next() {
                 if let Some((segment, section_data)) = Mach::Segment::from_command(command)? {
+                    if let Some(max_sections) = options.max_sections {
+                        let nsects = segment.nsects(endian) as usize;
+                        if nsects > max_sections.saturating_sub(sections.len()) {
+                            return Err(Error("Too many Mach-O sections"));
+                        }
+                    }
                     for section in segment.sections(endian, section_data)? {
                         let index = SectionIndex(sections.len() + 1);
-                        sections.push(MachOSectionInternal::parse(index, section));
+                        sections.push(MachOSectionInternal::parse(endian, index, section));
                     }
                 } else if let Some(symtab) = command.symtab()? {
                     symbols = data
@@ -80,6 +131,7 @@ impl<'data, Mach: MachHeader> MachOFile<'data, Mach> {
                         .read_error("Invalid Mach-O string table offset or size")?;
                 }
             }
+            truncated = commands.truncated();
         }
 
         let strings = StringTable { data: strings };
@@ -90,709 +142,4752 @@ impl<'data, Mach: MachHeader> MachOFile<'data, Mach> {
             header,
             sections,
             symbols,
+            truncated,
             data,
+            header_offset,
         })
     }
 
-    /// Return the section at the given index.
+    /// Return true if the `MH_NOUNDEFS` header flag is set.
+    ///
+    /// This flag indicates that the image has no undefined references.
     #[inline]
-    fn section_internal(&self, index: SectionIndex) -> Result<&MachOSectionInternal<'data, Mach>> {
-        index
-            .0
-            .checked_sub(1)
-            .and_then(|index| self.sections.get(index))
-            .read_error("Invalid Mach-O section index")
+    pub fn no_undefined_symbols(&self) -> bool {
+        self.header.flags(self.endian) & macho::MH_NOUNDEFS != 0
     }
-}
-
-impl<'data, Mach: MachHeader> read::private::Sealed for MachOFile<'data, Mach> {}
 
-impl<'data, 'file, Mach> Object<'data, 'file> for MachOFile<'data, Mach>
-where
-    'data: 'file,
-    Mach: MachHeader,
-{
-    type Segment = MachOSegment<'data, 'file, Mach>;
-    type SegmentIterator = MachOSegmentIterator<'data, 'file, Mach>;
-    type Section = MachOSection<'data, 'file, Mach>;
-    type SectionIterator = MachOSectionIterator<'data, 'file, Mach>;
-    type SymbolIterator = MachOSymbolIterator<'data, 'file, Mach>;
+    /// Return true if the `MH_TWOLEVEL` header flag is set.
+    ///
+    /// Images that use the two-level namespace record the library that provides
+    /// each undefined symbol, using the library ordinal in the high byte of `n_desc`.
+    /// Images that use the flat namespace search all libraries for each symbol, and
+    /// that byte of `n_desc` has no meaning. `symbol_library` and `imports` only use
+    /// the library ordinal if this flag is set.
+    #[inline]
+    pub fn is_two_level(&self) -> bool {
+        self.header.flags(self.endian) & macho::MH_TWOLEVEL != 0
+    }
 
-    fn architecture(&self) -> Architecture {
-        match self.header.cputype(self.endian) {
-            macho::CPU_TYPE_ARM => Architecture::Arm(ArmArchitecture::Arm),
-            macho::CPU_TYPE_ARM64 => Architecture::Aarch64(Aarch64Architecture::Aarch64),
-            macho::CPU_TYPE_X86 => Architecture::I386,
-            macho::CPU_TYPE_X86_64 => Architecture::X86_64,
-            macho::CPU_TYPE_MIPS => Architecture::Mips,
-            _ => Architecture::Unknown,
-        }
+    /// Return the magic number of the header.
+    ///
+    /// The value is read as big endian, so this is one of `MH_MAGIC`, `MH_CIGAM`,
+    /// `MH_MAGIC_64` or `MH_CIGAM_64`, and the `CIGAM` variants indicate a little
+    /// endian file.
+    #[inline]
+    pub fn magic(&self) -> u32 {
+        self.header.magic()
     }
 
+    /// Return the `reserved` field of the header.
+    ///
+    /// This is always 0 for 32-bit files, which have no such field.
     #[inline]
-    fn is_little_endian(&self) -> bool {
-        self.header.is_little_endian()
+    pub fn reserved(&self) -> u32 {
+        self.header.reserved(self.endian)
     }
 
+    /// Return true if the load commands were truncated.
+    ///
+    /// This occurs when a load command extends past the end of the file.
+    /// The commands before the truncated command are still parsed.
+    /// Other invalid load commands are an error instead.
     #[inline]
-    fn is_64(&self) -> bool {
-        self.header.is_type_64()
+    pub fn load_commands_truncated(&self) -> bool {
+        self.truncated
     }
 
-    fn segments(&'file self) -> MachOSegmentIterator<'data, 'file, Mach> {
-        MachOSegmentIterator {
-            file: self,
-            commands: self
-                .header
-                .load_commands(self.endian, self.data)
-                .ok()
-                .unwrap_or_else(Default::default),
+    /// Perform additional checks on the file structure.
+    ///
+    /// `parse` only checks what is needed to read the file. This is an opt-in
+    /// check for inconsistencies that a loader would reject:
+    ///
+    /// - segments with overlapping file ranges or address ranges
+    pub fn validate(&self) -> Result<()> {
+        if self.overlapping_segments().is_some() {
+            return Err(Error("Overlapping Mach-O segments"));
         }
+        Ok(())
     }
 
-    fn section_by_name(
-        &'file self,
-        section_name: &str,
-    ) -> Option<MachOSection<'data, 'file, Mach>> {
-        // Translate the "." prefix to the "__" prefix used by OSX/Mach-O, eg
-        // ".debug_info" to "__debug_info".
-        let system_section = section_name.starts_with('.');
-        let cmp_section_name = |section: &MachOSection<Mach>| {
-            section
-                .name()
-                .map(|name| {
-                    section_name == name
-                        || (system_section
-                            && name.starts_with("__")
-                            && section_name[1..] == name[2..])
+    /// Return true if the file declares everything that a loader needs.
+    ///
+    /// This is a heuristic check of the load commands. The criteria are:
+    ///
+    /// - the file type is `MH_EXECUTE`, `MH_DYLIB` or `MH_BUNDLE`
+    /// - executables have an entry point (`LC_MAIN` or `LC_UNIXTHREAD`) and an
+    ///   `LC_LOAD_DYLINKER` command
+    /// - at least one segment maps data from the file
+    /// - there is an `LC_SYMTAB` command
+    ///
+    /// The symbol table offsets are already checked by `parse`. Use `validate`
+    /// to check the segment layout.
+    pub fn is_loadable(&self) -> Result<bool> {
+        let filetype = self.header.filetype(self.endian);
+        if !matches!(
+            filetype,
+            macho::MH_EXECUTE | macho::MH_DYLIB | macho::MH_BUNDLE
+        ) {
+            return Ok(false);
+        }
+        let mut has_dylinker = false;
+        let mut has_segment = false;
+        let mut has_symtab = false;
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if command.cmd() == macho::LC_LOAD_DYLINKER {
+                has_dylinker = true;
+            } else if let Some((segment, _)) = Mach::Segment::from_command(command)? {
+                has_segment |= segment.file_range(self.endian).1 != 0;
+            } else if command.symtab()?.is_some() {
+                has_symtab = true;
+            }
+        }
+        if filetype == macho::MH_EXECUTE && (self.entry_info().is_none() || !has_dylinker) {
+            return Ok(false);
+        }
+        Ok(has_segment && has_symtab)
+    }
+
+    /// Return the names of a pair of segments that overlap.
+    ///
+    /// Segments overlap if their file ranges or their address ranges intersect.
+    /// Empty ranges never overlap. The names are in load command order.
+    pub fn overlapping_segments(&self) -> Option<(&'data [u8], &'data [u8])> {
+        // If any ranges overlap, then the first overlap in order of start address
+        // is between neighbours, so sorting avoids comparing every pair.
+        fn overlap(mut ranges: Vec<(u64, u64, usize)>) -> Option<(usize, usize)> {
+            ranges.sort_unstable();
+            ranges
+                .windows(2)
+                .find(|pair| pair[1].0 < pair[0].1)
+                .map(|pair| {
+                    (
+                        cmp::min(pair[0].2, pair[1].2),
+                        cmp::max(pair[0].2, pair[1].2),
+                    )
                 })
-                .unwrap_or(false)
+        }
+
+        let segments: Vec<_> = self.segments().collect();
+        let mut file_ranges = Vec::new();
+        let mut address_ranges = Vec::new();
+        for (index, segment) in segments.iter().enumerate() {
+            let (offset, size) = segment.file_range();
+            if size != 0 {
+                file_ranges.push((offset, offset.saturating_add(size), index));
+            }
+            let (address, size) = (segment.address(), segment.size());
+            if size != 0 {
+                address_ranges.push((address, address.saturating_add(size), index));
+            }
+        }
+        let (first, second) = overlap(file_ranges).or_else(|| overlap(address_ranges))?;
+        Some((segments[first].name_bytes(), segments[second].name_bytes()))
+    }
+
+    /// Return the bytes of the header and load commands.
+    ///
+    /// This is the range from the start of the header to the end of the load
+    /// command table, as given by `sizeofcmds`. It is truncated at the end of
+    /// the file if `sizeofcmds` is too large.
+    pub fn header_region(&self) -> &'data [u8] {
+        let offset = self.header_offset as usize;
+        let size = mem::size_of::<Mach>() + self.header.sizeofcmds(self.endian) as usize;
+        let size = cmp::min(size, self.data.len().saturating_sub(offset));
+        self.data.read_bytes_at(offset, size).unwrap_or_default().0
+    }
+
+    /// Return the size of the file that is described by the header and load commands.
+    ///
+    /// This is the maximum end offset of the header and load commands, the segments,
+    /// and the tables referenced by the `LC_SYMTAB`, `LC_DYSYMTAB`, `LC_DYLD_INFO`
+    /// and `linkedit_data` commands. If this is less than the length of the file
+    /// then the file has trailing data, and if it is greater then the file is
+    /// truncated. Load commands that fail to parse are ignored.
+    pub fn mapped_file_size(&self) -> u64 {
+        let endian = self.endian;
+        let mut size = self.header_offset
+            + mem::size_of::<Mach>() as u64
+            + u64::from(self.header.sizeofcmds(endian));
+        let mut add = |(offset, len): (u64, u64)| {
+            if len != 0 {
+                size = cmp::max(size, offset.saturating_add(len));
+            }
+        };
+        let table = |offset: &endian::U32<Mach::Endian>,
+                     count: &endian::U32<Mach::Endian>,
+                     entry_size: usize| {
+            (
+                u64::from(offset.get(endian)),
+                u64::from(count.get(endian)) * entry_size as u64,
+            )
+        };
+        let mut commands = match self.load_commands() {
+            Ok(commands) => commands,
+            Err(_) => return size,
         };
+        while let Ok(Some(command)) = commands.next() {
+            if let Ok(Some((segment, _))) = Mach::Segment::from_command(command) {
+                add(segment.file_range(endian));
+            } else if let Ok(Some(symtab)) = command.symtab() {
+                add(table(
+                    &symtab.symoff,
+                    &symtab.nsyms,
+                    mem::size_of::<Mach::Nlist>(),
+                ));
+                add(table(&symtab.stroff, &symtab.strsize, 1));
+            } else if let Ok(Some(dysymtab)) = command.dysymtab() {
+                add(table(&dysymtab.indirectsymoff, &dysymtab.nindirectsyms, 4));
+                add(table(&dysymtab.extreloff, &dysymtab.nextrel, 8));
+                add(table(&dysymtab.locreloff, &dysymtab.nlocrel, 8));
+            } else if let Ok(Some(dyld_info)) = command.dyld_info() {
+                add(table(&dyld_info.rebase_off, &dyld_info.rebase_size, 1));
+                add(table(&dyld_info.bind_off, &dyld_info.bind_size, 1));
+                add(table(
+                    &dyld_info.weak_bind_off,
+                    &dyld_info.weak_bind_size,
+                    1,
+                ));
+                add(table(
+                    &dyld_info.lazy_bind_off,
+                    &dyld_info.lazy_bind_size,
+                    1,
+                ));
+                add(table(&dyld_info.export_off, &dyld_info.export_size, 1));
+            } else if let Ok(Some(linkedit_data)) = command.linkedit_data() {
+                add(table(&linkedit_data.dataoff, &linkedit_data.datasize, 1));
+            }
+        }
+        size
+    }
 
-        self.sections().find(cmp_section_name)
+    /// Return the file data of each segment, in load command order.
+    ///
+    /// Segments that have no file data, such as `__PAGEZERO`, are skipped.
+    pub fn segment_data<'file>(&'file self) -> impl Iterator<Item = Result<&'data [u8]>> + 'file
+    where
+        'data: 'file,
+    {
+        self.segments()
+            .filter(|segment| segment.file_range().1 != 0)
+            .map(|segment| segment.data())
     }
 
-    fn section_by_index(
+    /// Return an iterator over the segments, each with its section headers.
+    ///
+    /// The segments are in load command order, and the section headers are read
+    /// lazily from the segment load command. Segments with an invalid number of
+    /// sections are skipped, as for `segments`.
+    pub fn segments_with_sections<'file>(
         &'file self,
-        index: SectionIndex,
-    ) -> Result<MachOSection<'data, 'file, Mach>> {
-        let internal = *self.section_internal(index)?;
-        Ok(MachOSection {
-            file: self,
-            internal,
+    ) -> impl Iterator<Item = (MachOSegment<'data, 'file, Mach>, &'data [Mach::Section])> + 'file
+    where
+        'data: 'file,
+    {
+        let endian = self.endian;
+        let mut commands = self.load_commands().unwrap_or_default();
+        core::iter::from_fn(move || loop {
+            let command = commands.next().ok()??;
+            if let Ok(Some((segment, section_data))) = Mach::Segment::from_command(command) {
+                if let Ok(sections) = segment.sections(endian, section_data) {
+                    return Some((
+                        MachOSegment {
+                            file: self,
+                            segment,
+                        },
+                        sections,
+                    ));
+                }
+            }
         })
     }
 
-    fn sections(&'file self) -> MachOSectionIterator<'data, 'file, Mach> {
-        MachOSectionIterator {
-            file: self,
-            iter: self.sections.iter(),
+    /// Return the raw data of the symbol string table.
+    ///
+    /// This is the `stroff`/`strsize` range given by the `LC_SYMTAB` command,
+    /// or an empty slice if there is no symbol table.
+    #[inline]
+    pub fn string_table_data(&self) -> &'data [u8] {
+        self.symbols.strings.data.0
+    }
+
+    /// Return an iterator over the strings in the symbol string table.
+    ///
+    /// The strings are in table order, and are not required to be referenced
+    /// by a symbol. Empty strings, such as the padding at the start and end of
+    /// the table, and strings that are not valid UTF-8 are skipped.
+    pub fn strings(&self) -> impl Iterator<Item = &'data str> + 'data {
+        self.string_table_data()
+            .split(|&byte| byte == 0)
+            .filter(|string| !string.is_empty())
+            .filter_map(|string| str::from_utf8(string).ok())
+    }
+
+    /// Return an iterator over the symbols that reports invalid entries.
+    ///
+    /// Unlike `symbols`, which skips any entry that it can't parse, this
+    /// returns an error for entries with an invalid name offset, a non UTF-8
+    /// name, or an invalid section index. Debugging entries (stabs) are
+    /// still skipped.
+    pub fn try_symbols<'file>(
+        &'file self,
+    ) -> impl Iterator<Item = Result<(SymbolIndex, Symbol<'data>)>> + 'file
+    where
+        'data: 'file,
+    {
+        self.symbols
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, nlist)| nlist.n_type() & macho::N_STAB == 0)
+            .map(move |(index, nlist)| {
+                self.check_symbol(nlist)?;
+                let symbol = parse_symbol(self, nlist, self.symbols.strings)
+                    .read_error("Unsupported Mach-O symbol")?;
+                Ok((SymbolIndex(index), symbol))
+            })
+    }
+
+    /// Check the fields of a symbol that `parse_symbol` ignores if invalid.
+    fn check_symbol(&self, nlist: &Mach::Nlist) -> Result<()> {
+        let n_strx = nlist.n_strx(self.endian);
+        if n_strx != 0 {
+            let name = self
+                .symbols
+                .strings
+                .get(n_strx)
+                .read_error("Invalid Mach-O symbol name offset")?;
+            str::from_utf8(name)
+                .ok()
+                .read_error("Non UTF-8 Mach-O symbol name")?;
+        }
+        if nlist.n_type() & macho::N_TYPE == macho::N_SECT {
+            let n_sect = nlist.n_sect();
+            if n_sect == 0 || n_sect as usize > self.sections.len() {
+                return Err(Error("Invalid Mach-O symbol section index"));
+            }
         }
+        Ok(())
     }
 
-    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Symbol<'data>> {
+    /// Return the section containing the symbol at the given index.
+    ///
+    /// Returns `Ok(None)` if the symbol is not defined in a section, such as
+    /// undefined or absolute symbols.
+    pub fn symbol_section<'file>(
+        &'file self,
+        index: SymbolIndex,
+    ) -> Result<Option<MachOSection<'data, 'file, Mach>>> {
         let nlist = self
             .symbols
             .symbols
             .get(index.0)
             .read_error("Invalid Mach-O symbol index")?;
-        parse_symbol(self, nlist, self.symbols.strings)
-            .read_error("Unsupported Mach-O symbol index")
-    }
-
-    fn symbols(&'file self) -> MachOSymbolIterator<'data, 'file, Mach> {
-        MachOSymbolIterator {
-            file: self,
-            symbols: self.symbols,
-            index: 0,
+        if nlist.n_type() & macho::N_STAB != 0
+            || nlist.n_type() & macho::N_TYPE != macho::N_SECT
+            || nlist.n_sect() == 0
+        {
+            return Ok(None);
         }
+        self.section_by_index(SectionIndex(nlist.n_sect() as usize))
+            .map(Some)
     }
 
-    fn dynamic_symbols(&'file self) -> MachOSymbolIterator<'data, 'file, Mach> {
-        // The LC_DYSYMTAB command contains indices into the same symbol
-        // table as the LC_SYMTAB command, so return all of them.
-        self.symbols()
+    /// Return the install names of the libraries that are loaded by this file.
+    ///
+    /// These are in load command order, so library ordinal `n` refers to the
+    /// library at index `n - 1`.
+    pub fn imported_libraries(&self) -> Result<Vec<&'data str>> {
+        let mut libraries = Vec::new();
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if command.cmd == macho::LC_ID_DYLIB {
+                continue;
+            }
+            if let Some(dylib) = command.dylib()? {
+                let name = command.string(self.endian, dylib.dylib.name)?;
+                libraries.push(
+                    str::from_utf8(name)
+                        .ok()
+                        .read_error("Non UTF-8 Mach-O dylib name")?,
+                );
+            }
+        }
+        Ok(libraries)
     }
 
-    fn symbol_map(&self) -> SymbolMap<'data> {
-        let mut symbols: Vec<_> = self.symbols().map(|(_, s)| s).collect();
-
-        // Add symbols for the end of each section.
-        for section in self.sections() {
-            symbols.push(Symbol {
-                name: None,
-                address: section.address() + section.size(),
-                size: 0,
-                kind: SymbolKind::Section,
-                section: SymbolSection::Undefined,
-                weak: false,
-                scope: SymbolScope::Compilation,
-                flags: SymbolFlags::None,
-            });
+    /// Return the install name of the library that provides an undefined symbol.
+    ///
+    /// The library is determined by the library ordinal in the symbol's `n_desc`.
+    /// Returns `Ok(None)` if the symbol is not undefined, or if it uses one of the
+    /// special ordinals `SELF_LIBRARY_ORDINAL`, `DYNAMIC_LOOKUP_ORDINAL` or
+    /// `EXECUTABLE_ORDINAL`. Also returns `Ok(None)` for images that don't use the
+    /// two-level namespace, since they don't record the library.
+    pub fn symbol_library(&self, index: SymbolIndex) -> Result<Option<&'data str>> {
+        let nlist = self
+            .symbols
+            .symbols
+            .get(index.0)
+            .read_error("Invalid Mach-O symbol index")?;
+        let n_type = nlist.n_type();
+        if n_type & macho::N_STAB != 0
+            || (n_type & macho::N_TYPE != macho::N_UNDF && n_type & macho::N_TYPE != macho::N_PBUD)
+        {
+            return Ok(None);
         }
+        if !self.is_two_level() {
+            return Ok(None);
+        }
+        let libraries = self.imported_libraries()?;
+        symbol_library(&libraries, nlist.n_desc(self.endian))
+    }
 
-        // Calculate symbol sizes by sorting and finding the next symbol.
-        symbols.sort_by(|a, b| {
-            a.address.cmp(&b.address).then_with(|| {
-                // Place the end of section symbols last.
-                (a.kind == SymbolKind::Section).cmp(&(b.kind == SymbolKind::Section))
-            })
-        });
-
-        for i in 0..symbols.len() {
-            let (before, after) = symbols.split_at_mut(i + 1);
-            let symbol = &mut before[i];
-            if symbol.kind != SymbolKind::Section {
-                if let Some(next) = after
-                    .iter()
-                    .skip_while(|x| x.kind != SymbolKind::Section && x.address == symbol.address)
-                    .next()
+    /// Return the symbols imported by this file, and the libraries that provide them.
+    ///
+    /// The imports are the undefined symbols in the symbol table, with the library
+    /// determined as for `symbol_library`. If there is no symbol table, then the
+    /// imports are read from the bind and lazy bind information of the `LC_DYLD_INFO`
+    /// command instead, and each symbol is only listed once per library.
+    pub fn imports(&self) -> Result<Vec<MachOImport<'data>>> {
+        let libraries = self.imported_libraries()?;
+        let two_level = self.is_two_level();
+        let mut imports = Vec::new();
+        if !self.symbols.symbols.is_empty() {
+            for nlist in self.symbols.symbols {
+                let n_type = nlist.n_type();
+                if n_type & macho::N_STAB != 0
+                    || (n_type & macho::N_TYPE != macho::N_UNDF
+                        && n_type & macho::N_TYPE != macho::N_PBUD)
                 {
-                    symbol.size = next.address - symbol.address;
+                    continue;
+                }
+                let value: u64 = nlist.n_value(self.endian).into();
+                if n_type & macho::N_TYPE == macho::N_UNDF && value != 0 {
+                    // A common symbol.
+                    continue;
                 }
+                imports.push(MachOImport {
+                    name: self
+                        .symbols
+                        .strings
+                        .get(nlist.n_strx(self.endian))
+                        .read_error("Invalid Mach-O symbol name offset")?,
+                    library: if two_level {
+                        symbol_library(&libraries, nlist.n_desc(self.endian))?
+                    } else {
+                        None
+                    },
+                });
             }
+            return Ok(imports);
         }
 
-        symbols.retain(SymbolMap::filter);
-        SymbolMap { symbols }
+        let dyld_info = match self.dyld_info()? {
+            Some(dyld_info) => dyld_info,
+            None => return Ok(imports),
+        };
+        let pointer_size = self.pointer_size();
+        let bind = self
+            .data
+            .read_bytes_at(
+                dyld_info.bind_off.get(self.endian) as usize,
+                dyld_info.bind_size.get(self.endian) as usize,
+            )
+            .read_error("Invalid Mach-O bind offset or size")?;
+        let mut lazy_bind = self
+            .data
+            .read_bytes_at(
+                dyld_info.lazy_bind_off.get(self.endian) as usize,
+                dyld_info.lazy_bind_size.get(self.endian) as usize,
+            )
+            .read_error("Invalid Mach-O lazy bind offset or size")?;
+        let segment_sizes = self.segment_sizes();
+        let mut seen = BTreeSet::new();
+        let mut add_import = |bind: MachOBind<'data>| -> Result<()> {
+            if !seen.insert((bind.name_bytes(), bind.library_ordinal())) {
+                return Ok(());
+            }
+            let library = match usize::try_from(bind.library_ordinal()) {
+                Ok(ordinal) if ordinal > 0 => Some(
+                    libraries
+                        .get(ordinal - 1)
+                        .cloned()
+                        .read_error("Invalid Mach-O bind library ordinal")?,
+                ),
+                _ => None,
+            };
+            imports.push(MachOImport {
+                name: bind.name_bytes(),
+                library,
+            });
+            Ok(())
+        };
+        let mut binds = MachOBindIterator::new(bind, pointer_size, &segment_sizes);
+        while let Some(bind) = binds.next()? {
+            // Repeated bindings have the same symbol and library.
+            binds.repeat = 0;
+            add_import(bind)?;
+        }
+        // Each lazy binding is a separate opcode stream ending in `BIND_OPCODE_DONE`.
+        while !lazy_bind.is_empty() {
+            let mut binds = MachOBindIterator::new(lazy_bind, pointer_size, &segment_sizes);
+            while let Some(bind) = binds.next()? {
+                binds.repeat = 0;
+                add_import(bind)?;
+            }
+            lazy_bind = binds.data;
+        }
+        Ok(imports)
     }
 
-    fn has_debug_symbols(&self) -> bool {
-        self.section_by_name(".debug_info").is_some()
+    /// Return the `n_type` field of the symbol at the given index.
+    ///
+    /// Use `N_TYPE` to extract the type, such as `N_SECT`, `N_ABS` or `N_UNDF`.
+    /// The index is the same as for `symbol_by_index`, and includes stabs.
+    pub fn n_type(&self, index: SymbolIndex) -> Result<u8> {
+        self.symbols
+            .symbols
+            .get(index.0)
+            .map(|nlist| nlist.n_type())
+            .read_error("Invalid Mach-O symbol index")
     }
 
-    fn mach_uuid(&self) -> Result<Option<[u8; 16]>> {
-        // Return the UUID from the `LC_UUID` load command, if one is present.
-        let mut commands = self.header.load_commands(self.endian, self.data)?;
-        while let Some(command) = commands.next()? {
-            if let Some(uuid) = command.uuid()? {
-                return Ok(Some(uuid.uuid));
+    /// Return the debug map given by the debugging entries (stabs) in the symbol table.
+    ///
+    /// The linker records an `N_OSO` entry for each object file that contributed to
+    /// a linked image, followed by entries for the functions and variables that the
+    /// object file defined. Tools such as `dsymutil` use this to find the debugging
+    /// information in the object files.
+    ///
+    /// Functions are given by `N_FUN` entries, static variables by `N_STSYM` entries,
+    /// and global variables by `N_GSYM` entries. The address of a global variable is
+    /// found from the symbol of the same name in the symbol table. Entries that are
+    /// not preceded by an `N_OSO` entry are ignored.
+    pub fn debug_map(&self) -> Result<MachODebugMap<'data>> {
+        let endian = self.endian;
+        let strings = self.symbols.strings;
+        let name = |nlist: &Mach::Nlist| {
+            strings
+                .get(nlist.n_strx(endian))
+                .read_error("Invalid Mach-O stab name offset")
+        };
+
+        // Addresses of global symbols, sorted by name.
+        let mut globals = Vec::new();
+        for nlist in self.symbols.symbols {
+            if nlist.n_type() & (macho::N_STAB | macho::N_TYPE | macho::N_EXT)
+                == macho::N_SECT | macho::N_EXT
+            {
+                if let Ok(name) = strings.get(nlist.n_strx(endian)) {
+                    globals.push((name, nlist.n_value(endian).into()));
+                }
             }
         }
-        Ok(None)
-    }
+        globals.sort_by_key(|&(name, _)| name);
 
-    fn entry(&self) -> u64 {
-        if let Ok(mut commands) = self.header.load_commands(self.endian, self.data) {
-            while let Ok(Some(command)) = commands.next() {
-                if let Ok(Some(command)) = command.entry_point() {
-                    return command.entryoff.get(self.endian);
+        let mut objects = Vec::new();
+        let mut object: Option<MachODebugMapObject<'data>> = None;
+        for nlist in self.symbols.symbols {
+            let n_type = nlist.n_type();
+            if n_type & macho::N_STAB == 0 {
+                continue;
+            }
+            let n_value = nlist.n_value(endian).into();
+            match n_type {
+                macho::N_OSO => {
+                    objects.extend(object.take());
+                    object = Some(MachODebugMapObject {
+                        path: name(nlist)?,
+                        timestamp: n_value,
+                        symbols: Vec::new(),
+                    });
+                }
+                // An empty name ends the compilation unit.
+                macho::N_SO if name(nlist)?.is_empty() => {
+                    objects.extend(object.take());
                 }
+                macho::N_FUN | macho::N_STSYM | macho::N_GSYM => {
+                    let object = match object.as_mut() {
+                        Some(object) => object,
+                        None => continue,
+                    };
+                    let name = name(nlist)?;
+                    if name.is_empty() {
+                        // An `N_FUN` with an empty name gives the size of the function.
+                        if n_type == macho::N_FUN {
+                            if let Some(symbol) = object.symbols.last_mut() {
+                                symbol.size = n_value;
+                            }
+                        }
+                        continue;
+                    }
+                    let address = if n_type == macho::N_GSYM {
+                        match globals.binary_search_by_key(&name, |&(name, _)| name) {
+                            Ok(index) => globals[index].1,
+                            Err(_) => continue,
+                        }
+                    } else {
+                        n_value
+                    };
+                    object.symbols.push(MachODebugMapSymbol {
+                        name,
+                        address,
+                        size: 0,
+                    });
+                }
+                _ => {}
             }
         }
-        0
+        objects.extend(object);
+        Ok(MachODebugMap { objects })
     }
 
-    fn flags(&self) -> FileFlags {
-        FileFlags::MachO {
-            flags: self.header.flags(self.endian),
+    /// Return the name of the target of an indirect symbol.
+    ///
+    /// For `N_INDR` symbols, `n_value` is the string table offset of the name
+    /// of the symbol that this symbol is an alias for.
+    /// Returns `Ok(None)` if the symbol at the given index is not an `N_INDR` symbol.
+    pub fn indirect_name(&self, index: SymbolIndex) -> Result<Option<&'data str>> {
+        let nlist = self
+            .symbols
+            .symbols
+            .get(index.0)
+            .read_error("Invalid Mach-O symbol index")?;
+        if nlist.n_type() & macho::N_STAB != 0 || nlist.n_type() & macho::N_TYPE != macho::N_INDR {
+            return Ok(None);
         }
+        let offset: u64 = nlist.n_value(self.endian).into();
+        let offset = u32::try_from(offset)
+            .ok()
+            .read_error("Invalid Mach-O indirect symbol name offset")?;
+        let name = self
+            .symbols
+            .strings
+            .get(offset)
+            .read_error("Invalid Mach-O indirect symbol name offset")?;
+        str::from_utf8(name)
+            .ok()
+            .read_error("Non UTF-8 Mach-O indirect symbol name")
+            .map(Some)
     }
-}
-
-/// An iterator over the segments of a `MachOFile32`.
-pub type MachOSegmentIterator32<'data, 'file, Endian = RunTimeEndian> =
-    MachOSegmentIterator<'data, 'file, macho::MachHeader32<Endian>>;
-/// An iterator over the segments of a `MachOFile64`.
-pub type MachOSegmentIterator64<'data, 'file, Endian = RunTimeEndian> =
-    MachOSegmentIterator<'data, 'file, macho::MachHeader64<Endian>>;
 
-/// An iterator over the segments of a `MachOFile`.
-#[derive(Debug)]
-pub struct MachOSegmentIterator<'data, 'file, Mach>
-where
-    'data: 'file,
-    Mach: MachHeader,
-{
-    file: &'file MachOFile<'data, Mach>,
-    commands: MachOLoadCommandIterator<'data, Mach::Endian>,
-}
+    /// Return the Objective-C image info, if present.
+    ///
+    /// This is read from the `__objc_imageinfo` section in the `__DATA` or
+    /// `__DATA_CONST` segment, or from `__OBJC,__image_info` in older files.
+    /// Returns `Ok(None)` if there is no such section.
+    pub fn objc_image_info(&self) -> Result<Option<MachOObjcImageInfo>> {
+        let internal = match self.sections.iter().find(|internal| {
+            let section = internal.section;
+            matches!(
+                (section.segment_name(), section.name()),
+                (b"__DATA", b"__objc_imageinfo")
+                    | (b"__DATA_CONST", b"__objc_imageinfo")
+                    | (b"__OBJC", b"__image_info")
+            )
+        }) {
+            Some(internal) => *internal,
+            None => return Ok(None),
+        };
+        let section = MachOSection {
+            file: self,
+            internal,
+        };
+        let mut data = section.bytes()?;
+        let version = data
+            .read::<endian::U32Bytes<Mach::Endian>>()
+            .read_error("Invalid Mach-O Objective-C image info size")?
+            .get(self.endian);
+        let flags = data
+            .read::<endian::U32Bytes<Mach::Endian>>()
+            .read_error("Invalid Mach-O Objective-C image info size")?
+            .get(self.endian);
+        Ok(Some(MachOObjcImageInfo { version, flags }))
+    }
+
+    /// Return the contents of the embedded `Info.plist`, if present.
+    ///
+    /// This is read from the `__TEXT,__info_plist` section, which is used by
+    /// command line tools that are not part of a bundle.
+    /// Returns `Ok(None)` if there is no such section.
+    pub fn embedded_info_plist(&self) -> Result<Option<&'data [u8]>> {
+        let internal = match self.sections.iter().find(|internal| {
+            let section = internal.section;
+            section.segment_name() == b"__TEXT" && section.name() == b"__info_plist"
+        }) {
+            Some(internal) => *internal,
+            None => return Ok(None),
+        };
+        let section = MachOSection {
+            file: self,
+            internal,
+        };
+        section.bytes().map(|bytes| Some(bytes.0))
+    }
 
-impl<'data, 'file, Mach: MachHeader> Iterator for MachOSegmentIterator<'data, 'file, Mach> {
-    type Item = MachOSegment<'data, 'file, Mach>;
+    /// Return the names of the Objective-C selectors that are referenced by the file.
+    ///
+    /// These are read from the `__objc_selrefs` section in the `__DATA` or
+    /// `__DATA_CONST` segment. Each entry is a pointer to a null terminated selector
+    /// name, usually in the `__TEXT,__objc_methname` section. Pointers that are encoded as chained rebases by the
+    /// `LC_DYLD_CHAINED_FIXUPS` command are decoded. The names are in section order.
+    /// Returns an empty list if there is no such section.
+    pub fn objc_selrefs(&self) -> Result<Vec<&'data str>> {
+        let endian = self.endian;
+        let mut selectors = Vec::new();
+        let internal = match self.sections.iter().find(|internal| {
+            let section = internal.section;
+            (section.segment_name() == b"__DATA" || section.segment_name() == b"__DATA_CONST")
+                && section.name() == b"__objc_selrefs"
+        }) {
+            Some(internal) => *internal,
+            None => return Ok(selectors),
+        };
+        let section = MachOSection {
+            file: self,
+            internal,
+        };
+        let pointer_format = match self
+            .segments()
+            .position(|segment| segment.name_bytes() == section.segment_name_bytes())
+        {
+            Some(segment_index) => self.chained_pointer_format(segment_index)?,
+            None => None,
+        };
+        let image_base = self.image_base();
+        let mut data = section.bytes()?;
+        while !data.is_empty() {
+            let value = if self.header.is_type_64() {
+                data.read::<endian::U64Bytes<Mach::Endian>>()
+                    .map(|value| value.get(endian))
+            } else {
+                data.read::<endian::U32Bytes<Mach::Endian>>()
+                    .map(|value| value.get(endian).into())
+            }
+            .read_error("Invalid Mach-O selector reference section size")?;
+            let address = match pointer_format {
+                Some(pointer_format) => chained_rebase_target(pointer_format, value, image_base)?,
+                None => value,
+            };
+            let name = self
+                .string_at(address)
+                .read_error("Invalid Mach-O selector reference address")?;
+            selectors.push(
+                str::from_utf8(name)
+                    .ok()
+                    .read_error("Non UTF-8 Mach-O selector name")?,
+            );
+        }
+        Ok(selectors)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Return the names of the Objective-C classes that are defined by the file.
+    ///
+    /// These are found using the `__objc_classlist` section in the `__DATA` or
+    /// `__DATA_CONST` segment. Each entry is a pointer to a `class_t`, whose `data`
+    /// field points to a `class_ro_t` that contains a pointer to the class name.
+    /// Pointers that are encoded as chained rebases by the `LC_DYLD_CHAINED_FIXUPS`
+    /// command are decoded. The names are in section order. Returns an empty list
+    /// if there is no such section.
+    pub fn objc_classes(&self) -> Result<Vec<&'data str>> {
+        let mut classes = Vec::new();
+        let internal = match self.sections.iter().find(|internal| {
+            let section = internal.section;
+            (section.segment_name() == b"__DATA" || section.segment_name() == b"__DATA_CONST")
+                && section.name() == b"__objc_classlist"
+        }) {
+            Some(internal) => *internal,
+            None => return Ok(classes),
+        };
+        let section = MachOSection {
+            file: self,
+            internal,
+        };
+        // The offsets of `class_t::data` and `class_ro_t::name`, and the mask for the
+        // flags in the low bits of `class_t::data`.
+        let pointer_size = self.pointer_size();
+        let (data_offset, name_offset, data_mask) = if pointer_size == 8 {
+            (32, 24, 0x0000_7fff_ffff_fff8)
+        } else {
+            (16, 16, 0xffff_fffc)
+        };
+        if section.size() % pointer_size != 0 {
+            return Err(Error("Invalid Mach-O class list section size"));
+        }
+        for index in 0..section.size() / pointer_size {
+            let class = self.pointer_at(section.address() + index * pointer_size)?;
+            let class_ro = self.pointer_at(class.wrapping_add(data_offset))? & data_mask;
+            let name = self.pointer_at(class_ro.wrapping_add(name_offset))?;
+            let name = self
+                .string_at(name)
+                .read_error("Invalid Mach-O class name address")?;
+            classes.push(
+                str::from_utf8(name)
+                    .ok()
+                    .read_error("Non UTF-8 Mach-O class name")?,
+            );
+        }
+        Ok(classes)
+    }
+
+    /// Read a pointer at the given address.
+    ///
+    /// If the pointer is encoded as a chained rebase, then its target address is returned.
+    fn pointer_at(&self, address: u64) -> Result<u64> {
+        let endian = self.endian;
+        let (segment_index, segment) = self
+            .segments()
+            .enumerate()
+            .find(|(_, segment)| address.wrapping_sub(segment.address()) < segment.size())
+            .read_error("Invalid Mach-O pointer address")?;
+        let offset = (address - segment.address()) as usize;
+        let data = segment.bytes()?;
+        let value = if self.header.is_type_64() {
+            data.read_at::<endian::U64Bytes<Mach::Endian>>(offset)
+                .map(|value| value.get(endian))
+        } else {
+            data.read_at::<endian::U32Bytes<Mach::Endian>>(offset)
+                .map(|value| value.get(endian).into())
+        }
+        .read_error("Invalid Mach-O pointer address")?;
+        match self.chained_pointer_format(segment_index)? {
+            Some(pointer_format) => chained_rebase_target(pointer_format, value, self.image_base()),
+            None => Ok(value),
+        }
+    }
+
+    /// Read a null terminated string at the given address.
+    ///
+    /// Returns `None` if the address is not within a section that has data.
+    fn string_at(&self, address: u64) -> Option<&'data [u8]> {
+        self.sections
+            .iter()
+            .map(|internal| MachOSection {
+                file: self,
+                internal: *internal,
+            })
+            .find(|section| {
+                address >= section.address()
+                    && address - section.address() < section.size()
+                    && !section.kind().is_bss()
+            })
+            .and_then(|section| {
+                section
+                    .bytes()
+                    .ok()?
+                    .read_string_at((address - section.address()) as usize)
+                    .ok()
+            })
+    }
+
+    /// Return the compact unwind entries of a relocatable object, if present.
+    ///
+    /// These are read from the `__LD,__compact_unwind` section. Relocations in the
+    /// section are applied, so the addresses are those of the symbols or sections
+    /// that the fields refer to. The addresses of undefined symbols are zero, and the
+    /// personality symbol is given by `MachOCompactUnwindEntry::personality_symbol`.
+    /// Returns `Ok(None)` if there is no such section.
+    pub fn compact_unwind(&self) -> Result<Option<impl Iterator<Item = MachOCompactUnwindEntry>>> {
+        let internal = match self.sections.iter().find(|internal| {
+            let section = internal.section;
+            section.segment_name() == b"__LD" && section.name() == b"__compact_unwind"
+        }) {
+            Some(internal) => *internal,
+            None => return Ok(None),
+        };
+        let section = MachOSection {
+            file: self,
+            internal,
+        };
+        let endian = self.endian;
+        let pointer_size = self.pointer_size() as usize;
+        // functionStart, length, encoding, personality, lsda
+        let entry_size = 3 * pointer_size + 8;
+        let data = section.relocated_data(|symbol| {
+            self.symbol_by_index(symbol)
+                .ok()
+                .map(|symbol| symbol.address())
+        })?;
+        if data.len() % entry_size != 0 {
+            return Err(Error("Invalid Mach-O compact unwind section size"));
+        }
+        let personality_symbols: Vec<_> = section
+            .relocations()
+            .filter_map(|(offset, relocation)| match relocation.target() {
+                RelocationTarget::Symbol(symbol) => Some((offset as usize, symbol)),
+                RelocationTarget::Section(_) => None,
+            })
+            .filter(|&(offset, _)| offset % entry_size == pointer_size + 8)
+            .collect();
+        let count = data.len() / entry_size;
+        Ok(Some((0..count).map(move |i| {
+            let offset = i * entry_size;
+            let data = Bytes(&data[offset..][..entry_size]);
+            let pointer = |offset| -> u64 {
+                if pointer_size == 8 {
+                    data.read_at::<endian::U64Bytes<Mach::Endian>>(offset)
+                        .map(|value| value.get(endian))
+                        .unwrap_or(0)
+                } else {
+                    data.read_at::<endian::U32Bytes<Mach::Endian>>(offset)
+                        .map(|value| value.get(endian).into())
+                        .unwrap_or(0)
+                }
+            };
+            let word = |offset| -> u32 {
+                data.read_at::<endian::U32Bytes<Mach::Endian>>(offset)
+                    .map(|value| value.get(endian))
+                    .unwrap_or(0)
+            };
+            let personality_offset = offset + pointer_size + 8;
+            MachOCompactUnwindEntry {
+                function_address: pointer(0),
+                function_length: word(pointer_size),
+                encoding: word(pointer_size + 4),
+                personality: pointer(pointer_size + 8),
+                personality_symbol: personality_symbols
+                    .iter()
+                    .find(|&&(offset, _)| offset == personality_offset)
+                    .map(|&(_, symbol)| symbol),
+                lsda: pointer(2 * pointer_size + 8),
+            }
+        })))
+    }
+
+    /// Return true if the symbol table has been stripped.
+    ///
+    /// This is true if there are no symbol table entries, or if all of the
+    /// entries are debugging entries (stabs).
+    pub fn is_stripped(&self) -> bool {
+        self.symbols
+            .symbols
+            .iter()
+            .all(|nlist| nlist.n_type() & macho::N_STAB != 0)
+    }
+
+    /// Return the number of symbol table entries declared by the `LC_SYMTAB` command.
+    ///
+    /// This includes entries that are skipped by the symbol iterator, such as stabs.
+    #[inline]
+    pub fn nsyms(&self) -> u32 {
+        // `parse` ensures the symbol slice has exactly the declared length.
+        self.symbols.symbols.len() as u32
+    }
+
+    /// Return the number of entries in the symbol table.
+    ///
+    /// This is the same as `nsyms`, but doesn't require a conversion for use as a count.
+    /// It includes stabs, which are skipped by `symbols`, so it may be larger than
+    /// the number of symbols returned by that iterator.
+    #[inline]
+    pub fn symbol_count(&self) -> usize {
+        self.symbols.symbols.len()
+    }
+
+    /// Return the size in bytes of the string table declared by the `LC_SYMTAB` command.
+    #[inline]
+    pub fn strsize(&self) -> u32 {
+        self.symbols.strings.data.len() as u32
+    }
+
+    /// Return the total number of relocations declared by the file.
+    ///
+    /// This is the sum of the `nreloc` field of each section and the external and
+    /// local relocation counts of the `LC_DYSYMTAB` command. It includes scattered
+    /// relocations and relocation pairs, which are not returned separately by the
+    /// relocation iterators.
+    pub fn relocation_count(&self) -> u32 {
+        let endian = self.endian;
+        let mut count = self.sections.iter().fold(0u32, |count, internal| {
+            count.saturating_add(internal.section.nreloc(endian))
+        });
+        if let Ok(Some(dysymtab)) = self.dysymtab() {
+            count = count
+                .saturating_add(dysymtab.nextrel.get(endian))
+                .saturating_add(dysymtab.nlocrel.get(endian));
+        }
+        count
+    }
+
+    /// Return the locations of the section relocations that refer to each symbol.
+    ///
+    /// Each location is the index of the section containing the relocation, and the
+    /// offset of the relocation within that section. The locations for each symbol
+    /// are in section order, and then relocation order. Relocations that refer to a
+    /// section rather than a symbol are not included, nor are the external relocations
+    /// of the `LC_DYSYMTAB` command.
+    pub fn xrefs_to_symbols(&self) -> Result<BTreeMap<SymbolIndex, Vec<(SectionIndex, u64)>>> {
+        let mut xrefs = BTreeMap::new();
+        for section in self.sections() {
+            for (offset, relocation) in section.try_relocations()? {
+                if let RelocationTarget::Symbol(symbol) = relocation.target() {
+                    xrefs
+                        .entry(symbol)
+                        .or_insert_with(Vec::new)
+                        .push((section.index(), offset));
+                }
+            }
+        }
+        Ok(xrefs)
+    }
+
+    /// Return the address of the target of a relocation.
+    ///
+    /// This is the address of the symbol for `RelocationTarget::Symbol`, and the
+    /// address of the section for `RelocationTarget::Section`. The relocation addend
+    /// is not included. Returns `Ok(None)` for undefined symbols, and an error if
+    /// the index is invalid.
+    ///
+    /// For relocations against sections, the implicit addend stored in the section
+    /// data is the target address rather than an offset from the section, so it must
+    /// not be added to this address. Use `MachOSection::resolved_relocations` to get
+    /// addends that are relative to this address.
+    pub fn relocation_target_address(&self, target: &RelocationTarget) -> Result<Option<u64>> {
+        match *target {
+            RelocationTarget::Symbol(index) => {
+                let symbol = self.symbol_by_index(index)?;
+                if symbol.is_undefined() {
+                    Ok(None)
+                } else {
+                    Ok(Some(symbol.address()))
+                }
+            }
+            RelocationTarget::Section(index) => Ok(Some(
+                self.section_internal(index)?
+                    .section
+                    .addr(self.endian)
+                    .into(),
+            )),
+        }
+    }
+
+    /// Return true if this file and `other` have the same `LC_UUID`.
+    ///
+    /// This can be used to match a binary with its dSYM. Returns false if either
+    /// file has no `LC_UUID` command.
+    pub fn uuid_matches(&self, other: &MachOFile<Mach>) -> Result<bool> {
+        match (self.mach_uuid()?, other.mach_uuid()?) {
+            (Some(uuid), Some(other_uuid)) => Ok(uuid == other_uuid),
+            _ => Ok(false),
+        }
+    }
+
+    /// Return the sections sorted by address.
+    ///
+    /// Sections with the same address are in load command order, which is the
+    /// order used by `sections`.
+    pub fn sections_by_address<'file>(&'file self) -> Vec<MachOSection<'data, 'file, Mach>> {
+        let mut sections: Vec<_> = self.sections().collect();
+        sections.sort_by_key(|section| section.address());
+        sections
+    }
+
+    /// Return an iterator over the contents of each section, with the section names.
+    ///
+    /// Each item is the segment name, the section name, and the section data.
+    /// Names that are not valid UTF-8 are converted lossily. The sections are in
+    /// the order used by `sections`.
+    pub fn section_contents<'file>(
+        &'file self,
+    ) -> impl Iterator<Item = (Cow<'data, str>, Cow<'data, str>, Result<&'data [u8]>)> + 'file
+    where
+        'data: 'file,
+    {
+        self.sections().map(|section| {
+            (
+                String::from_utf8_lossy(section.segment_name_bytes()),
+                String::from_utf8_lossy(section.name_bytes()),
+                section.data(),
+            )
+        })
+    }
+
+    /// Return all of the symbols that are defined at the given address.
+    ///
+    /// Multiple symbols may share an address, such as a function and its aliases.
+    /// The symbols are returned in symbol table order.
+    ///
+    /// This searches the whole symbol table. Use `Object::symbol_map` instead for
+    /// many lookups.
+    pub fn symbols_at_address(&self, address: u64) -> Vec<Symbol<'data>> {
+        let endian = self.endian;
+        self.symbols
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, nlist)| {
+                let n_type = nlist.n_type();
+                n_type & macho::N_STAB == 0
+                    && n_type & macho::N_TYPE == macho::N_SECT
+                    && nlist.n_value(endian).into() == address
+            })
+            .filter_map(|(index, _)| self.symbol_by_index(SymbolIndex(index)).ok())
+            .collect()
+    }
+
+    /// Return the Swift metadata sections that are present in the file.
+    ///
+    /// These are the `__TEXT,__swift5_*` sections, such as `__swift5_types`
+    /// and `__swift5_fieldmd`. Each section is paired with its section name,
+    /// and they are returned in section table order.
+    pub fn swift_sections<'file>(
+        &'file self,
+    ) -> Vec<(&'data str, MachOSection<'data, 'file, Mach>)> {
+        self.sections
+            .iter()
+            .filter_map(|internal| {
+                let section = internal.section;
+                if section.segment_name() != b"__TEXT" {
+                    return None;
+                }
+                let name = section.name();
+                if !name.starts_with(b"__swift5_") {
+                    return None;
+                }
+                let name = str::from_utf8(name).ok()?;
+                Some((
+                    name,
+                    MachOSection {
+                        file: self,
+                        internal: *internal,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Return the slide of the image, given the runtime address of its Mach header.
+    ///
+    /// The slide is the difference between the runtime address and the image's
+    /// preferred base, which is the address of the segment that maps the start
+    /// of the file (normally `__TEXT`).
+    pub fn slide(&self, runtime_addr: u64) -> u64 {
+        runtime_addr.wrapping_sub(self.image_base())
+    }
+
+    /// Convert a runtime address to the corresponding address in the file.
+    ///
+    /// `slide` is the difference between the image's runtime base and its
+    /// preferred base, as returned by `slide`.
+    #[inline]
+    pub fn unslide(&self, runtime_addr: u64, slide: u64) -> u64 {
+        runtime_addr.wrapping_sub(slide)
+    }
+
+    /// Return the preferred base address of the image.
+    ///
+    /// This is the address of the segment that maps the start of the file,
+    /// or 0 if there is no such segment. This is normally `__TEXT`, which is at
+    /// 0x100000000 for 64-bit executables and 0 for dylibs.
+    ///
+    /// The slide of a loaded image is its runtime base minus this address.
+    pub fn image_base(&self) -> u64 {
+        self.segments()
+            .find(|segment| {
+                let (offset, size) = segment.file_range();
+                offset == 0 && size != 0
+            })
+            .map(|segment| segment.address())
+            .unwrap_or(0)
+    }
+
+    /// Return the `__TEXT` segment, if present.
+    ///
+    /// The segment's `file_range` gives the location of the code in the file.
+    pub fn text_segment<'file>(&'file self) -> Result<Option<MachOSegment<'data, 'file, Mach>>>
+    where
+        'data: 'file,
+    {
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some((segment, _)) = Mach::Segment::from_command(command)? {
+                if segment.name() == b"__TEXT" {
+                    return Ok(Some(MachOSegment {
+                        file: self,
+                        segment,
+                    }));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the file data of the segment with the given name.
+    ///
+    /// This is the range given by the segment's `fileoff` and `filesize`, so it
+    /// includes any padding between sections, and it can be used for segments that
+    /// have no sections, such as a custom segment containing a single blob of data.
+    /// Returns `Ok(None)` if there is no segment with this name, and an error if the
+    /// file range is invalid.
+    pub fn segment_data_by_name(&self, name: &str) -> Result<Option<&'data [u8]>> {
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some((segment, _)) = Mach::Segment::from_command(command)? {
+                if segment.name() == name.as_bytes() {
+                    return segment
+                        .data(self.endian, self.data)
+                        .read_error("Invalid Mach-O segment size or offset")
+                        .map(|data| Some(data.0));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decode the lazy binding at the given offset in the lazy binding information.
+    ///
+    /// `lazy_bind_offset` is relative to the start of the lazy binding information
+    /// given by the `LC_DYLD_INFO` command. This is the value that the stub helper
+    /// passes to dyld for a lazy symbol pointer.
+    ///
+    /// Only the opcodes for this one binding are decoded. Returns `Ok(None)` if
+    /// there is no `LC_DYLD_INFO` command, or if the entry ends without a binding.
+    pub fn lazy_bind_at(&self, lazy_bind_offset: u32) -> Result<Option<MachOBind<'data>>> {
+        let dyld_info = match self.dyld_info()? {
+            Some(dyld_info) => dyld_info,
+            None => return Ok(None),
+        };
+        let mut data = self
+            .data
+            .read_bytes_at(
+                dyld_info.lazy_bind_off.get(self.endian) as usize,
+                dyld_info.lazy_bind_size.get(self.endian) as usize,
+            )
+            .read_error("Invalid Mach-O lazy bind offset or size")?;
+        data.skip(lazy_bind_offset as usize)
+            .read_error("Invalid Mach-O lazy bind entry offset")?;
+        MachOBindIterator::new(data, self.pointer_size(), &self.segment_sizes()).next()
+    }
+
+    /// Return the names of the symbols in the weak binding information.
+    ///
+    /// The weak binding information of the `LC_DYLD_INFO` command lists the weak
+    /// definitions that dyld coalesces across images, such as C++ inline functions
+    /// and template instantiations. It also lists strong definitions that override
+    /// weak definitions, which have the `BIND_SYMBOL_FLAGS_NON_WEAK_DEFINITION` flag.
+    /// These strong definitions are not included.
+    ///
+    /// Each name is returned once, in the order of the weak binding information.
+    /// Returns an empty list if there is no `LC_DYLD_INFO` command.
+    pub fn weak_definitions(&self) -> Result<Vec<&'data str>> {
+        let mut names = Vec::new();
+        let mut seen = BTreeSet::new();
+        let dyld_info = match self.dyld_info()? {
+            Some(dyld_info) => dyld_info,
+            None => return Ok(names),
+        };
+        let data = self
+            .data
+            .read_bytes_at(
+                dyld_info.weak_bind_off.get(self.endian) as usize,
+                dyld_info.weak_bind_size.get(self.endian) as usize,
+            )
+            .read_error("Invalid Mach-O weak bind offset or size")?;
+        let segment_sizes = self.segment_sizes();
+        let mut binds = MachOBindIterator::new(data, self.pointer_size(), &segment_sizes);
+        while let Some((name, flags)) = binds.next_symbol()? {
+            if flags & macho::BIND_SYMBOL_FLAGS_NON_WEAK_DEFINITION != 0 {
+                continue;
+            }
+            let name = str::from_utf8(name)
+                .ok()
+                .read_error("Non UTF-8 Mach-O bind symbol name")?;
+            if seen.insert(name) {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+
+    /// Look up an exported symbol in the export trie.
+    ///
+    /// Only the trie edges that match `name` are followed, so this is much cheaper
+    /// than decoding every export. The export trie is read from the `LC_DYLD_INFO`
+    /// or `LC_DYLD_EXPORTS_TRIE` command.
+    ///
+    /// Returns `Ok(None)` if there is no export trie, or if `name` is not exported.
+    pub fn export(&self, name: &str) -> Result<Option<MachOExport<'data>>> {
+        let trie = match self.export_trie()? {
+            Some(trie) => trie,
+            None => return Ok(None),
+        };
+        let mut name = name.as_bytes();
+        let mut offset = 0;
         loop {
-            let command = self.commands.next().ok()??;
-            if let Ok(Some((segment, _))) = Mach::Segment::from_command(command) {
-                return Some(MachOSegment {
-                    file: self.file,
-                    segment,
-                });
+            let mut node = trie;
+            node.skip(offset)
+                .read_error("Invalid Mach-O export trie node offset")?;
+            let terminal_size = node.read_uleb128().read_error("Invalid ULEB128 value")? as usize;
+            if name.is_empty() {
+                if terminal_size == 0 {
+                    return Ok(None);
+                }
+                let terminal = node
+                    .read_bytes(terminal_size)
+                    .read_error("Invalid Mach-O export trie terminal size")?;
+                return MachOExport::parse(terminal).map(Some);
+            }
+            node.skip(terminal_size)
+                .read_error("Invalid Mach-O export trie terminal size")?;
+            let child_count = *node
+                .read::<u8>()
+                .read_error("Invalid Mach-O export trie child count")?;
+            let mut next = None;
+            for _ in 0..child_count {
+                let edge = node
+                    .read_string()
+                    .read_error("Invalid Mach-O export trie edge")?;
+                let child = node.read_uleb128().read_error("Invalid ULEB128 value")?;
+                // Empty edges are ignored so that every step consumes part of the name.
+                if !edge.is_empty() && name.starts_with(edge) {
+                    next = Some((edge.len(), child));
+                    break;
+                }
+            }
+            match next {
+                Some((len, child)) => {
+                    name = &name[len..];
+                    offset = child as usize;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Return all exported symbols, sorted by name.
+    ///
+    /// This merges the exports in the export trie with the external defined symbols
+    /// in the symbol table, since a file may have either or both. If a symbol is
+    /// in both, then the export trie entry is used. Private extern (`N_PEXT`)
+    /// symbols are not exported. For symbol table entries, the flags are derived
+    /// from the symbol type and `N_WEAK_DEF`, and the address is made relative to
+    /// `image_base`, in the same way as for the export trie.
+    ///
+    /// Returns an error if the total size of the names in the export trie is more
+    /// than 16 times the size of the trie.
+    pub fn all_exports(&self) -> Result<Vec<MachOExportedSymbol<'data>>> {
+        let endian = self.endian;
+        let mut exports: Vec<_> = self
+            .trie_exports()?
+            .into_iter()
+            .map(|(name, export)| MachOExportedSymbol {
+                name: Cow::Owned(name),
+                export,
+            })
+            .collect();
+
+        let image_base = self.image_base();
+        for nlist in self.symbols.symbols {
+            let n_type = nlist.n_type();
+            if n_type & (macho::N_STAB | macho::N_EXT) != macho::N_EXT
+                || n_type & macho::N_PEXT != 0
+            {
+                continue;
+            }
+            let n_value = nlist.n_value(endian).into();
+            let (mut flags, address) = match n_type & macho::N_TYPE {
+                macho::N_SECT => {
+                    let section_type = (nlist.n_sect() as usize)
+                        .checked_sub(1)
+                        .and_then(|index| self.sections.get(index))
+                        .map(|internal| internal.section.flags(endian) & macho::SECTION_TYPE);
+                    let kind = if section_type == Some(macho::S_THREAD_LOCAL_VARIABLES) {
+                        macho::EXPORT_SYMBOL_FLAGS_KIND_THREAD_LOCAL
+                    } else {
+                        macho::EXPORT_SYMBOL_FLAGS_KIND_REGULAR
+                    };
+                    (kind, n_value.wrapping_sub(image_base))
+                }
+                macho::N_ABS => (macho::EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE, n_value),
+                _ => continue,
+            };
+            if nlist.n_desc(endian) & macho::N_WEAK_DEF != 0 {
+                flags |= macho::EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION;
+            }
+            let name = self
+                .symbols
+                .strings
+                .get(nlist.n_strx(endian))
+                .read_error("Invalid Mach-O symbol name offset")?;
+            exports.push(MachOExportedSymbol {
+                name: Cow::Borrowed(name),
+                export: MachOExport {
+                    flags,
+                    address,
+                    library_ordinal: 0,
+                    import_name: &[],
+                    resolver: None,
+                },
+            });
+        }
+
+        // The sort is stable, so the trie entry for each name is kept by `dedup_by`.
+        exports.sort_by(|a, b| a.name.cmp(&b.name));
+        exports.dedup_by(|a, b| a.name == b.name);
+        Ok(exports)
+    }
+
+    /// Decode every export in the export trie, in trie order.
+    ///
+    /// Returns an empty list if there is no export trie. Returns an error if the
+    /// total size of the export names is more than 16 times the size of the trie.
+    fn trie_exports(&self) -> Result<Vec<(Vec<u8>, MachOExport<'data>)>> {
+        let trie = match self.export_trie()? {
+            Some(trie) => trie,
+            None => return Ok(Vec::new()),
+        };
+        let mut exports = Vec::new();
+        // Names share prefixes in the trie, so their total size can exceed the size
+        // of the trie, but a malformed trie could otherwise need memory quadratic in
+        // its size.
+        let mut names_size = trie.len().saturating_mul(16);
+        // Each node may only be visited once, so that a malformed trie can't loop.
+        let mut visited = vec![false; trie.len()];
+        // The nodes are visited depth first, so the name of each node is built in a
+        // single buffer from the name length of its parent and its edge.
+        let mut name = Vec::new();
+        let mut nodes = vec![(0, 0, &[][..])];
+        while let Some((offset, parent_len, edge)) = nodes.pop() {
+            name.truncate(parent_len);
+            name.extend_from_slice(edge);
+            match visited.get_mut(offset) {
+                Some(visited) if !*visited => *visited = true,
+                Some(_) => return Err(Error("Invalid Mach-O export trie cycle")),
+                None => return Err(Error("Invalid Mach-O export trie node offset")),
+            }
+            let mut node = trie;
+            node.skip(offset)
+                .read_error("Invalid Mach-O export trie node offset")?;
+            let terminal_size = node.read_uleb128().read_error("Invalid ULEB128 value")? as usize;
+            let terminal = node
+                .read_bytes(terminal_size)
+                .read_error("Invalid Mach-O export trie terminal size")?;
+            let child_count = *node
+                .read::<u8>()
+                .read_error("Invalid Mach-O export trie child count")?;
+            let children = nodes.len();
+            for _ in 0..child_count {
+                let edge = node
+                    .read_string()
+                    .read_error("Invalid Mach-O export trie edge")?;
+                let child = node.read_uleb128().read_error("Invalid ULEB128 value")?;
+                nodes.push((child as usize, name.len(), edge));
+            }
+            // Reverse so that children are visited in edge order.
+            nodes[children..].reverse();
+            if terminal_size != 0 {
+                names_size = names_size
+                    .checked_sub(name.len())
+                    .read_error("Mach-O export trie names are too large")?;
+                exports.push((name.clone(), MachOExport::parse(terminal)?));
+            }
+        }
+        Ok(exports)
+    }
+
+    /// Return the format of the dynamic linking information in `__LINKEDIT`.
+    ///
+    /// This determines which of the dynamic linking parsers apply to the file.
+    /// The symbol table is available in all formats.
+    pub fn linkedit_format(&self) -> Result<MachOLinkeditFormat> {
+        let mut format = MachOLinkeditFormat::Classic;
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            match command.cmd {
+                macho::LC_DYLD_CHAINED_FIXUPS => return Ok(MachOLinkeditFormat::ChainedFixups),
+                macho::LC_DYLD_INFO | macho::LC_DYLD_INFO_ONLY => {
+                    format = MachOLinkeditFormat::DyldInfo;
+                }
+                _ => {}
+            }
+        }
+        Ok(format)
+    }
+
+    /// Return the export trie data, if present.
+    fn export_trie(&self) -> Result<Option<Bytes<'data>>> {
+        let (offset, size) = if let Some(dyld_info) = self.dyld_info()? {
+            (
+                dyld_info.export_off.get(self.endian),
+                dyld_info.export_size.get(self.endian),
+            )
+        } else if let Some(command) = self.linkedit_data(macho::LC_DYLD_EXPORTS_TRIE)? {
+            (
+                command.dataoff.get(self.endian),
+                command.datasize.get(self.endian),
+            )
+        } else {
+            return Ok(None);
+        };
+        if size == 0 {
+            return Ok(None);
+        }
+        self.data
+            .read_bytes_at(offset as usize, size as usize)
+            .read_error("Invalid Mach-O export trie offset or size")
+            .map(Some)
+    }
+
+    /// Return the defined function symbols, with their sizes calculated.
+    ///
+    /// The size of each symbol is calculated in the same way as for `symbol_map`,
+    /// using the address of the next symbol or the end of the section.
+    /// Symbols with a size of zero are omitted. The symbols are sorted by address.
+    pub fn defined_functions(&self) -> Vec<Symbol<'data>> {
+        let mut symbols: Vec<_> = self.sized_symbols().into_iter().map(|(_, s)| s).collect();
+        symbols.retain(|symbol| {
+            symbol.kind() == SymbolKind::Text
+                && !symbol.is_undefined()
+                && !symbol.is_common()
+                && symbol.size() > 0
+        });
+        symbols
+    }
+
+    /// Return all symbols in symbol table order, with their sizes calculated.
+    ///
+    /// The size of each symbol is calculated in the same way as for `symbol_map`,
+    /// using the address of the next symbol or the end of the section. This
+    /// requires sorting a copy of the symbols by address.
+    pub fn symbols_with_sizes(&self) -> Vec<(SymbolIndex, Symbol<'data>)> {
+        let mut symbols: Vec<_> = self
+            .sized_symbols()
+            .into_iter()
+            .filter_map(|(index, symbol)| Some((index?, symbol)))
+            .collect();
+        symbols.sort_by_key(|(index, _)| index.0);
+        symbols
+    }
+
+    /// Return the symbols that are defined in the given section, in symbol table order.
+    ///
+    /// This uses the `n_sect` field of `N_SECT` symbols. Debugging symbols are
+    /// not included. The sizes of the symbols are not calculated.
+    pub fn symbols_in_section(&self, index: SectionIndex) -> Vec<(SymbolIndex, Symbol<'data>)> {
+        self.symbols()
+            .filter(|(_, symbol)| symbol.section_index() == Some(index))
+            .collect()
+    }
+
+    /// Return all symbols sorted by address, with their sizes calculated.
+    ///
+    /// Symbols with the same address are sorted by name. Includes a `SymbolKind::Section`
+    /// symbol for the end of each section, which has no symbol index.
+    fn sized_symbols(&self) -> Vec<(Option<SymbolIndex>, Symbol<'data>)> {
+        let mut symbols: Vec<_> = self
+            .symbols()
+            .map(|(index, symbol)| (Some(index), symbol))
+            .collect();
+
+        // Add symbols for the end of each section.
+        for section in self.sections() {
+            symbols.push((
+                None,
+                Symbol {
+                    name: None,
+                    address: section.address() + section.size(),
+                    size: 0,
+                    kind: SymbolKind::Section,
+                    section: SymbolSection::Undefined,
+                    weak: false,
+                    scope: SymbolScope::Compilation,
+                    flags: SymbolFlags::None,
+                },
+            ));
+        }
+
+        // Calculate symbol sizes by sorting and finding the next symbol.
+        // Symbols at the same address are sorted by name and then by index, so that
+        // the order doesn't depend on the order of the symbol table.
+        symbols.sort_by(|(a_index, a), (b_index, b)| {
+            a.address
+                .cmp(&b.address)
+                .then_with(|| {
+                    // Place the end of section symbols last.
+                    (a.kind == SymbolKind::Section).cmp(&(b.kind == SymbolKind::Section))
+                })
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a_index.map(|i| i.0).cmp(&b_index.map(|i| i.0)))
+        });
+
+        for i in 0..symbols.len() {
+            let (before, after) = symbols.split_at_mut(i + 1);
+            let symbol = &mut before[i].1;
+            if symbol.kind != SymbolKind::Section {
+                if let Some(next) = after
+                    .iter()
+                    .map(|(_, x)| x)
+                    .skip_while(|x| x.kind != SymbolKind::Section && x.address == symbol.address)
+                    .next()
+                {
+                    symbol.size = next.address - symbol.address;
+                }
+            }
+        }
+        symbols
+    }
+
+    /// Return an iterator over the load commands.
+    ///
+    /// Each command includes its offset within the file data.
+    pub fn load_commands(&self) -> Result<MachOLoadCommandIterator<'data, Mach::Endian>> {
+        self.header
+            .load_commands_at(self.endian, self.data, self.header_offset)
+    }
+
+    /// Return the raw data of the first load command with the given `cmd` value.
+    ///
+    /// The data includes the `cmd` and `cmdsize` fields. This can be used to
+    /// read load commands that don't have a typed accessor.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), object::read::Error> {
+    /// use object::endian::LittleEndian;
+    /// use object::macho;
+    /// use object::read::macho::MachOFile64;
+    ///
+    /// # let mut data = vec![
+    /// #     0xcf, 0xfa, 0xed, 0xfe, 0x07, 0x00, 0x00, 0x01, 0x03, 0x00, 0x00, 0x00,
+    /// #     0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1b, 0x00, 0x00, 0x00,
+    /// #     0x18, 0x00, 0x00, 0x00,
+    /// # ];
+    /// # data.extend_from_slice(&[0xab; 16]);
+    /// let file = MachOFile64::<LittleEndian>::parse(&data)?;
+    /// let command = file.load_command(macho::LC_UUID)?.unwrap();
+    /// let uuid = &command[8..24];
+    /// assert_eq!(uuid, &[0xab; 16]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_command(&self, cmd: u32) -> Result<Option<&'data [u8]>> {
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if command.cmd == cmd {
+                return Ok(Some(command.data.0));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the raw data of all load commands with the given `cmd` value.
+    ///
+    /// The data includes the `cmd` and `cmdsize` fields.
+    pub fn load_commands_matching(&self, cmd: u32) -> Result<Vec<&'data [u8]>> {
+        let mut matching = Vec::new();
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if command.cmd == cmd {
+                matching.push(command.data.0);
+            }
+        }
+        Ok(matching)
+    }
+
+    /// Read a `Pod` type at the given address.
+    ///
+    /// The address is mapped to a file offset using the segment containing it.
+    /// Returns `Ok(None)` if the address is not within the file data of any segment,
+    /// such as for zero-fill data. Returns an error if the type extends past the end
+    /// of the segment's file data, or if the data is not aligned for the type.
+    ///
+    /// Pointers are returned as stored in the file. If `linkedit_format` is
+    /// `MachOLinkeditFormat::ChainedFixups`, then these are encoded chained fixups.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), object::read::Error> {
+    /// use object::endian::{LittleEndian as LE, U32, U64};
+    /// use object::read::macho::MachOFile64;
+    ///
+    /// /// The Objective-C `class_ro_t` structure for 64-bit targets.
+    /// #[derive(Debug, Clone, Copy)]
+    /// #[repr(C)]
+    /// struct ClassRo64 {
+    ///     flags: U32<LE>,
+    ///     instance_start: U32<LE>,
+    ///     instance_size: U32<LE>,
+    ///     reserved: U32<LE>,
+    ///     ivar_layout: U64<LE>,
+    ///     name: U64<LE>,
+    ///     base_methods: U64<LE>,
+    ///     base_protocols: U64<LE>,
+    ///     ivars: U64<LE>,
+    ///     weak_ivar_layout: U64<LE>,
+    ///     base_properties: U64<LE>,
+    /// }
+    /// unsafe impl object::Pod for ClassRo64 {}
+    ///
+    /// # let mut data = vec![
+    /// #     0xcf, 0xfa, 0xed, 0xfe, 0x07, 0x00, 0x00, 0x01, 0x03, 0x00, 0x00, 0x00,
+    /// #     0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x48, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00,
+    /// #     0x48, 0x00, 0x00, 0x00, 0x5f, 0x5f, 0x44, 0x41, 0x54, 0x41, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x48, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x68, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x48, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// # ];
+    /// # let mut class_ro = [0; 72];
+    /// # class_ro[24..32].copy_from_slice(&0x2000u64.to_le_bytes());
+    /// # data.extend_from_slice(&class_ro);
+    /// let file = MachOFile64::<LE>::parse(&data)?;
+    /// // `__DATA` contains a `class_ro_t` at 0x1000.
+    /// let class_ro = file.read_at_address::<ClassRo64>(0x1000)?.unwrap();
+    /// assert_eq!(class_ro.name.get(LE), 0x2000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_at_address<T: Pod>(&self, address: u64) -> Result<Option<&'data T>> {
+        for segment in self.segments() {
+            let (_, file_size) = segment.file_range();
+            let offset = match address.checked_sub(segment.address()) {
+                Some(offset) if offset < file_size => offset,
+                _ => continue,
+            };
+            return segment
+                .bytes_checked()?
+                .read_at(offset as usize)
+                .read_error("Invalid Mach-O data size or alignment at address")
+                .map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Find the segment and section containing the given address.
+    ///
+    /// Returns `None` if the address is not within any section.
+    pub fn resolve_address(&self, address: u64) -> Option<MachOAddressInfo<'data>> {
+        let endian = self.endian;
+        let internal = self.sections.iter().find(|internal| {
+            let section_address = internal.section.addr(endian).into();
+            let size = internal.section.size(endian).into();
+            address >= section_address && address - section_address < size
+        })?;
+        let section = MachOSection {
+            file: self,
+            internal: *internal,
+        };
+        let segment_name = match section.segment() {
+            Some(segment) => segment.name_bytes(),
+            None => section.segment_name_bytes(),
+        };
+        Some(MachOAddressInfo {
+            segment_name,
+            section_name: section.name_bytes(),
+            section_index: internal.index,
+            offset: address - section.address(),
+        })
+    }
+
+    /// Return the segments and their sections as a tree.
+    ///
+    /// Segments are in load command order, and sections are in the order
+    /// they follow their segment command.
+    pub fn layout(&self) -> MachOLayout<'data> {
+        let endian = self.endian;
+        let mut segments = Vec::new();
+        if let Ok(mut commands) = self.load_commands() {
+            while let Ok(Some(command)) = commands.next() {
+                if let Ok(Some((segment, section_data))) = Mach::Segment::from_command(command) {
+                    let sections = segment
+                        .sections(endian, section_data)
+                        .unwrap_or(&[])
+                        .iter()
+                        .map(|section| MachOSectionLayout {
+                            name: section.name(),
+                            segment_name: section.segment_name(),
+                            address: section.addr(endian).into(),
+                            size: section.size(endian).into(),
+                            file_range: section.file_range(endian),
+                            align: 1 << section.align(endian),
+                            flags: section.flags(endian),
+                        })
+                        .collect();
+                    segments.push(MachOSegmentLayout {
+                        name: segment.name(),
+                        address: segment.vmaddr(endian).into(),
+                        size: segment.vmsize(endian).into(),
+                        file_range: segment.file_range(endian),
+                        maxprot: segment.maxprot(endian),
+                        initprot: segment.initprot(endian),
+                        sections,
+                    });
+                }
+            }
+        }
+        MachOLayout { segments }
+    }
+
+    /// Return the target platform and versions.
+    ///
+    /// This uses the `LC_BUILD_VERSION` command if present, otherwise one of
+    /// the `LC_VERSION_MIN_*` commands.
+    pub fn build_version(&self) -> Result<Option<MachOBuildVersion>> {
+        let mut version_min = None;
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some(build_version) = command.build_version()? {
+                return Ok(Some(MachOBuildVersion {
+                    platform: build_version.platform.get(self.endian),
+                    minos: build_version.minos.get(self.endian),
+                    sdk: build_version.sdk.get(self.endian),
+                }));
+            }
+            if version_min.is_none() {
+                if let Some(command) = command.version_min()? {
+                    let platform = match command.cmd.get(self.endian) {
+                        macho::LC_VERSION_MIN_MACOSX => macho::PLATFORM_MACOS,
+                        macho::LC_VERSION_MIN_IPHONEOS => macho::PLATFORM_IOS,
+                        macho::LC_VERSION_MIN_TVOS => macho::PLATFORM_TVOS,
+                        _ => macho::PLATFORM_WATCHOS,
+                    };
+                    version_min = Some(MachOBuildVersion {
+                        platform,
+                        minos: command.version.get(self.endian),
+                        sdk: command.sdk.get(self.endian),
+                    });
+                }
+            }
+        }
+        Ok(version_min)
+    }
+
+    /// Return true if this file targets a simulator platform.
+    ///
+    /// This is determined from the platform given by `build_version`. Files without
+    /// a platform are not simulator files.
+    pub fn is_simulator(&self) -> Result<bool> {
+        Ok(match self.build_version()? {
+            Some(version) => matches!(
+                version.platform,
+                macho::PLATFORM_IOSSIMULATOR
+                    | macho::PLATFORM_TVOSSIMULATOR
+                    | macho::PLATFORM_WATCHOSSIMULATOR
+            ),
+            None => false,
+        })
+    }
+
+    /// Return the target triple for this file.
+    ///
+    /// The architecture is refined using the CPU subtype, and the operating system
+    /// is determined from the platform given by `build_version`. Platforms that
+    /// `target_lexicon` has no operating system for, or files without a platform,
+    /// use `OperatingSystem::Darwin`.
+    ///
+    /// `target_lexicon` has no environment for Mac Catalyst or the simulators, so
+    /// these platforms also use `OperatingSystem::Darwin` rather than reporting a
+    /// device triple. Use `Object::platform` or `is_simulator` to distinguish them.
+    pub fn triple(&self) -> Triple {
+        let architecture = cpu_architecture(
+            self.header.cputype(self.endian),
+            self.header.cpusubtype(self.endian),
+        );
+        let operating_system = match self.build_version() {
+            Ok(Some(version)) => match version.platform {
+                macho::PLATFORM_MACOS => OperatingSystem::MacOSX {
+                    major: (version.minos >> 16) as u16,
+                    minor: (version.minos >> 8) as u8 as u16,
+                    patch: version.minos as u8 as u16,
+                },
+                macho::PLATFORM_IOS => OperatingSystem::Ios,
+                _ => OperatingSystem::Darwin,
+            },
+            _ => OperatingSystem::Darwin,
+        };
+        Triple {
+            architecture,
+            vendor: Vendor::Apple,
+            operating_system,
+            environment: Environment::Unknown,
+            binary_format: BinaryFormat::Macho,
+        }
+    }
+
+    /// Return the indirect symbol table given by the `LC_DYSYMTAB` command.
+    ///
+    /// Returns an empty table if there is no `LC_DYSYMTAB` command.
+    fn indirect_symbol_table(&self) -> Result<&'data [endian::U32<Mach::Endian>]> {
+        match self.dysymtab()? {
+            Some(dysymtab) => self
+                .data
+                .read_slice_at(
+                    dysymtab.indirectsymoff.get(self.endian) as usize,
+                    dysymtab.nindirectsyms.get(self.endian) as usize,
+                )
+                .read_error("Invalid Mach-O indirect symbol table offset or size"),
+            None => Ok(&[]),
+        }
+    }
+
+    /// Return the external relocations given by the `LC_DYSYMTAB` command.
+    ///
+    /// The offset of each relocation is the `r_address` field. Unlike section
+    /// relocations, this is relative to the address of the first segment, or
+    /// the first writable segment for x86-64.
+    ///
+    /// Returns an empty iterator if there is no `LC_DYSYMTAB` command.
+    pub fn external_relocations<'file>(
+        &'file self,
+    ) -> Result<MachORelocationIterator<'data, 'file, Mach>> {
+        let relocations = match self.dysymtab()? {
+            Some(dysymtab) => self
+                .data
+                .read_slice_at(
+                    dysymtab.extreloff.get(self.endian) as usize,
+                    dysymtab.nextrel.get(self.endian) as usize,
+                )
+                .read_error("Invalid Mach-O external relocation offset or size")?,
+            None => &[],
+        };
+        Ok(MachORelocationIterator {
+            file: self,
+            relocations: relocations.iter(),
+        })
+    }
+
+    /// Return the local relocations given by the `LC_DYSYMTAB` command.
+    ///
+    /// The offset of each relocation is relative to the same base as for
+    /// `external_relocations`.
+    ///
+    /// Returns an empty iterator if there is no `LC_DYSYMTAB` command.
+    pub fn local_relocations<'file>(
+        &'file self,
+    ) -> Result<MachORelocationIterator<'data, 'file, Mach>> {
+        let relocations = match self.dysymtab()? {
+            Some(dysymtab) => self
+                .data
+                .read_slice_at(
+                    dysymtab.locreloff.get(self.endian) as usize,
+                    dysymtab.nlocrel.get(self.endian) as usize,
+                )
+                .read_error("Invalid Mach-O local relocation offset or size")?,
+            None => &[],
+        };
+        Ok(MachORelocationIterator {
+            file: self,
+            relocations: relocations.iter(),
+        })
+    }
+
+    /// Return the offset and size of the symbol segment given by the `LC_SYMSEG` command.
+    ///
+    /// This is an obsolete command that is only found in very old files.
+    /// Returns `Ok(None)` if there is no such command, and an error if the
+    /// symbol segment is outside the file.
+    pub fn symseg(&self) -> Result<Option<(u32, u32)>> {
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some(symseg) = command.symseg()? {
+                let offset = symseg.offset.get(self.endian);
+                let size = symseg.size.get(self.endian);
+                self.data
+                    .read_bytes_at(offset as usize, size as usize)
+                    .read_error("Invalid Mach-O symbol segment offset or size")?;
+                return Ok(Some((offset, size)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the `LC_DYSYMTAB` command, if present.
+    ///
+    /// This gives access to all of the fields of the command. The external and local
+    /// relocations are more conveniently read using `external_relocations` and
+    /// `local_relocations`, and the indirect symbols using `MachOSection::indirect_symbols`.
+    pub fn dysymtab(&self) -> Result<Option<&'data macho::DysymtabCommand<Mach::Endian>>> {
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some(dysymtab) = command.dysymtab()? {
+                return Ok(Some(dysymtab));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the load command that provides the entry point.
+    ///
+    /// `LC_MAIN` takes precedence over `LC_UNIXTHREAD` if both are present.
+    /// Returns `None` if there is no entry point.
+    pub fn entry_source(&self) -> Option<MachOEntrySource> {
+        self.entry_info().map(|(source, _)| source)
+    }
+
+    /// Return the virtual address of the entry point.
+    ///
+    /// Unlike `entry`, this converts the file offset given by `LC_MAIN` to an
+    /// address, using the segment that contains the offset.
+    /// Returns `None` if there is no entry point, or if the offset is not
+    /// within a segment.
+    pub fn entry_address(&self) -> Option<u64> {
+        match self.entry_info()? {
+            (MachOEntrySource::Main, offset) => self.segments().find_map(|segment| {
+                let (file_offset, file_size) = segment.file_range();
+                if offset >= file_offset && offset - file_offset < file_size {
+                    Some(segment.address() + (offset - file_offset))
+                } else {
+                    None
+                }
+            }),
+            (MachOEntrySource::UnixThread, address) => Some(address),
+        }
+    }
+
+    /// Return the section containing the entry point.
+    ///
+    /// Returns `Ok(None)` if there is no entry point, if the entry point is zero,
+    /// or if it is not within a section.
+    pub fn entry_section<'file>(&'file self) -> Result<Option<MachOSection<'data, 'file, Mach>>> {
+        let address = match self.entry_address() {
+            Some(address) if address != 0 => address,
+            _ => return Ok(None),
+        };
+        match self.resolve_address(address) {
+            Some(info) => self.section_by_index(info.section_index).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the entry point and the load command that provides it.
+    fn entry_info(&self) -> Option<(MachOEntrySource, u64)> {
+        let mut thread_entry = None;
+        let mut commands = self.load_commands().ok()?;
+        while let Ok(Some(command)) = commands.next() {
+            if let Ok(Some(command)) = command.entry_point() {
+                return Some((MachOEntrySource::Main, command.entryoff.get(self.endian)));
+            }
+            if command.cmd == macho::LC_UNIXTHREAD && thread_entry.is_none() {
+                thread_entry = self.thread_pc(command.data);
+            }
+        }
+        thread_entry.map(|entry| (MachOEntrySource::UnixThread, entry))
+    }
+
+    /// Return the program counter from the thread state of a `LC_UNIXTHREAD` command.
+    ///
+    /// Only the first thread state is checked, and it must be the general
+    /// purpose register state for the file's CPU type.
+    fn thread_pc(&self, mut data: Bytes<'data>) -> Option<u64> {
+        let endian = self.endian;
+        data.skip(mem::size_of::<macho::ThreadCommand<Mach::Endian>>())
+            .ok()?;
+        let flavor = data.read::<endian::U32<Mach::Endian>>().ok()?.get(endian);
+        let count = data.read::<endian::U32<Mach::Endian>>().ok()?.get(endian);
+        let state = data.read_bytes(count as usize * 4).ok()?;
+        // (flavor, offset of the program counter, whether the registers are 64-bit)
+        let (expected_flavor, offset, is_64) = match self.header.cputype(endian) {
+            // x86_THREAD_STATE32: eax, ebx, ecx, edx, edi, esi, ebp, esp, ss, eflags, eip
+            macho::CPU_TYPE_X86 => (1, 40, false),
+            // x86_THREAD_STATE64: rax, rbx, rcx, rdx, rdi, rsi, rbp, rsp, r8-r15, rip
+            macho::CPU_TYPE_X86_64 => (4, 128, true),
+            // ARM_THREAD_STATE: r0-r12, sp, lr, pc
+            macho::CPU_TYPE_ARM => (1, 60, false),
+            // ARM_THREAD_STATE64: x0-x28, fp, lr, sp, pc
+            macho::CPU_TYPE_ARM64 => (6, 256, true),
+            _ => return None,
+        };
+        if flavor != expected_flavor {
+            return None;
+        }
+        if is_64 {
+            state
+                .read_at::<endian::U64Bytes<Mach::Endian>>(offset)
+                .ok()
+                .map(|pc| pc.get(endian))
+        } else {
+            state
+                .read_at::<endian::U32Bytes<Mach::Endian>>(offset)
+                .ok()
+                .map(|pc| pc.get(endian).into())
+        }
+    }
+
+    /// Return the entries of an `MH_FILESET` file.
+    ///
+    /// Each entry is a Mach-O image that can be parsed with `parse_at`, using
+    /// the entry's `fileoff` as the header offset.
+    pub fn fileset_entries(&self) -> Result<Vec<MachOFilesetEntry<'data>>> {
+        let mut entries = Vec::new();
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some(entry) = command.fileset_entry()? {
+                entries.push(MachOFilesetEntry {
+                    entry_id: command.string(self.endian, entry.entry_id)?,
+                    vmaddr: entry.vmaddr.get(self.endian),
+                    fileoff: entry.fileoff.get(self.endian),
+                });
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Return the strings of the `LC_DYLD_ENVIRONMENT` commands.
+    ///
+    /// Each string sets a dyld environment variable, such as
+    /// `DYLD_FRAMEWORK_PATH=/path`.
+    pub fn dyld_environment(&self) -> Result<Vec<&'data str>> {
+        let mut environment = Vec::new();
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if command.cmd != macho::LC_DYLD_ENVIRONMENT {
+                continue;
+            }
+            if let Some(dylinker) = command.dylinker()? {
+                let string = command.string(self.endian, dylinker.name)?;
+                environment.push(
+                    str::from_utf8(string)
+                        .ok()
+                        .read_error("Non UTF-8 Mach-O LC_DYLD_ENVIRONMENT string")?,
+                );
+            }
+        }
+        Ok(environment)
+    }
+
+    /// Return true if the file contains an `LC_CODE_SIGNATURE` command.
+    ///
+    /// This does not parse or validate the signature. Use `code_signature`
+    /// for that.
+    pub fn is_signed(&self) -> bool {
+        let mut commands = match self.load_commands() {
+            Ok(commands) => commands,
+            Err(_) => return false,
+        };
+        while let Ok(Some(command)) = commands.next() {
+            if command.cmd == macho::LC_CODE_SIGNATURE {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Return the embedded code signature, if present.
+    ///
+    /// Returns `Ok(None)` if there is no `LC_CODE_SIGNATURE` command.
+    pub fn code_signature(&self) -> Result<Option<MachOCodeSignature<'data>>> {
+        let command = match self.linkedit_data(macho::LC_CODE_SIGNATURE)? {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+        let data = self
+            .data
+            .read_bytes_at(
+                command.dataoff.get(self.endian) as usize,
+                command.datasize.get(self.endian) as usize,
+            )
+            .read_error("Invalid Mach-O code signature offset or size")?;
+        MachOCodeSignature::parse(data).map(Some)
+    }
+
+    /// Return the imports table from the `LC_DYLD_CHAINED_FIXUPS` command.
+    ///
+    /// The fixup chains refer to imports by their index in this table.
+    /// Returns an empty table if there is no `LC_DYLD_CHAINED_FIXUPS` command.
+    pub fn chained_imports(&self) -> Result<Vec<MachOChainedImport<'data>>> {
+        let endian = self.endian;
+        let mut imports = Vec::new();
+        let data = match self.chained_fixups()? {
+            Some(data) => data,
+            None => return Ok(imports),
+        };
+        let header = chained_fixups_header::<Mach::Endian>(data)?;
+        if header.symbols_format.get(endian) != 0 {
+            return Err(Error("Unsupported Mach-O chained fixups symbols format"));
+        }
+        let mut symbols = data;
+        symbols
+            .skip(header.symbols_offset.get(endian) as usize)
+            .read_error("Invalid Mach-O chained fixups symbols offset")?;
+        let import = |lib_ordinal: i32, weak_import: bool, name_offset: u32, addend: i64| {
+            let name = symbols
+                .read_string_at(name_offset as usize)
+                .read_error("Invalid Mach-O chained import name offset")?;
+            Ok(MachOChainedImport {
+                name,
+                library_ordinal: lib_ordinal,
+                weak_import,
+                addend,
+            })
+        };
+
+        let imports_offset = header.imports_offset.get(endian) as usize;
+        let imports_count = header.imports_count.get(endian) as usize;
+        match header.imports_format.get(endian) {
+            macho::DYLD_CHAINED_IMPORT => {
+                let table = data
+                    .read_slice_at::<macho::DyldChainedImport<Mach::Endian>>(
+                        imports_offset,
+                        imports_count,
+                    )
+                    .read_error("Invalid Mach-O chained imports offset or count")?;
+                for entry in table {
+                    let raw = entry.import.get(endian);
+                    imports.push(import(
+                        lib_ordinal_8(raw as u8),
+                        raw & 0x100 != 0,
+                        raw >> 9,
+                        0,
+                    )?);
+                }
+            }
+            macho::DYLD_CHAINED_IMPORT_ADDEND => {
+                let table = data
+                    .read_slice_at::<macho::DyldChainedImportAddend<Mach::Endian>>(
+                        imports_offset,
+                        imports_count,
+                    )
+                    .read_error("Invalid Mach-O chained imports offset or count")?;
+                for entry in table {
+                    let raw = entry.import.get(endian);
+                    imports.push(import(
+                        lib_ordinal_8(raw as u8),
+                        raw & 0x100 != 0,
+                        raw >> 9,
+                        entry.addend.get(endian).into(),
+                    )?);
+                }
+            }
+            macho::DYLD_CHAINED_IMPORT_ADDEND64 => {
+                let table = data
+                    .read_slice_at::<macho::DyldChainedImportAddend64<Mach::Endian>>(
+                        imports_offset,
+                        imports_count,
+                    )
+                    .read_error("Invalid Mach-O chained imports offset or count")?;
+                for entry in table {
+                    let raw = entry.import.get(endian);
+                    let lib_ordinal = raw as u16;
+                    // Values above `0xfff0` are the sign extended special ordinals.
+                    let lib_ordinal = if lib_ordinal > 0xfff0 {
+                        i32::from(lib_ordinal as i16)
+                    } else {
+                        i32::from(lib_ordinal)
+                    };
+                    imports.push(import(
+                        lib_ordinal,
+                        raw & 0x1_0000 != 0,
+                        (raw >> 32) as u32,
+                        entry.addend.get(endian) as i64,
+                    )?);
+                }
+            }
+            _ => return Err(Error("Unsupported Mach-O chained imports format")),
+        }
+        Ok(imports)
+    }
+
+    /// Return the data of the `LC_DYLD_CHAINED_FIXUPS` command, if present.
+    fn chained_fixups(&self) -> Result<Option<Bytes<'data>>> {
+        let endian = self.endian;
+        let command = match self.linkedit_data(macho::LC_DYLD_CHAINED_FIXUPS)? {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+        self.data
+            .read_bytes_at(
+                command.dataoff.get(endian) as usize,
+                command.datasize.get(endian) as usize,
+            )
+            .read_error("Invalid Mach-O chained fixups offset or size")
+            .map(Some)
+    }
+
+    /// Return the chained pointer format of the segment with the given index.
+    ///
+    /// The index is the position of the segment in load command order.
+    /// Returns `Ok(None)` if there is no `LC_DYLD_CHAINED_FIXUPS` command, or if the
+    /// segment has no fixups. Returns an error if the pointer format is not supported
+    /// by `chained_rebase_target`.
+    fn chained_pointer_format(&self, segment_index: usize) -> Result<Option<u16>> {
+        let endian = self.endian;
+        let data = match self.chained_fixups()? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        let header = chained_fixups_header::<Mach::Endian>(data)?;
+        let starts_offset = header.starts_offset.get(endian) as usize;
+        let seg_count = data
+            .read_at::<macho::DyldChainedStartsInImage<Mach::Endian>>(starts_offset)
+            .read_error("Invalid Mach-O chained fixups starts offset")?
+            .seg_count
+            .get(endian);
+        if segment_index >= seg_count as usize {
+            return Ok(None);
+        }
+        let seg_info_offset = data
+            .read_at::<endian::U32<Mach::Endian>>(starts_offset + 4 + segment_index * 4)
+            .read_error("Invalid Mach-O chained fixups segment count")?
+            .get(endian);
+        if seg_info_offset == 0 {
+            return Ok(None);
+        }
+        let starts = data
+            .read_at::<macho::DyldChainedStartsInSegment<Mach::Endian>>(
+                starts_offset + seg_info_offset as usize,
+            )
+            .read_error("Invalid Mach-O chained fixups segment offset")?;
+        match starts.pointer_format.get(endian) {
+            pointer_format @ macho::DYLD_CHAINED_PTR_64
+            | pointer_format @ macho::DYLD_CHAINED_PTR_64_OFFSET
+            | pointer_format @ macho::DYLD_CHAINED_PTR_ARM64E
+            | pointer_format @ macho::DYLD_CHAINED_PTR_ARM64E_USERLAND
+            | pointer_format @ macho::DYLD_CHAINED_PTR_ARM64E_USERLAND24
+            | pointer_format @ macho::DYLD_CHAINED_PTR_32 => Ok(Some(pointer_format)),
+            _ => Err(Error("Unsupported Mach-O chained pointer format")),
+        }
+    }
+
+    /// Return the identifier from the code signature, if present.
+    ///
+    /// Returns `Ok(None)` if the file is not signed or has no `CodeDirectory`.
+    pub fn signing_identifier(&self) -> Result<Option<&'data str>> {
+        match self.code_signature()? {
+            Some(signature) => signature.identifier(),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the team identifier from the code signature, if present.
+    ///
+    /// Returns `Ok(None)` if the file is not signed or the signature has no team identifier.
+    pub fn team_id(&self) -> Result<Option<&'data str>> {
+        match self.code_signature()? {
+            Some(signature) => signature.team_id(),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the flags from the code signature, if present.
+    ///
+    /// Returns `Ok(None)` if the file is not signed or has no `CodeDirectory`.
+    pub fn code_signature_flags(&self) -> Result<Option<MachOCodeSignatureFlags>> {
+        match self.code_signature()? {
+            Some(signature) => signature.flags(),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the entitlements plist from the code signature, if present.
+    ///
+    /// Returns `Ok(None)` if the file is not signed or the signature has no entitlements.
+    pub fn entitlements(&self) -> Result<Option<&'data [u8]>> {
+        match self.code_signature()? {
+            Some(signature) => signature.entitlements(),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the first `LinkeditDataCommand` with the given command type, if present.
+    fn linkedit_data(
+        &self,
+        cmd: u32,
+    ) -> Result<Option<&'data macho::LinkeditDataCommand<Mach::Endian>>> {
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if command.cmd == cmd {
+                return command.linkedit_data();
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the `LC_DYLD_INFO` or `LC_DYLD_INFO_ONLY` command, if present.
+    fn dyld_info(&self) -> Result<Option<&'data macho::DyldInfoCommand<Mach::Endian>>> {
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some(dyld_info) = command.dyld_info()? {
+                return Ok(Some(dyld_info));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the size in bytes of a pointer in the image.
+    ///
+    /// This is given by the header format, so it is 4 for arm64_32 files.
+    #[inline]
+    pub fn pointer_size(&self) -> u64 {
+        if self.header.is_type_64() {
+            8
+        } else {
+            4
+        }
+    }
+
+    /// Return the size in memory of each segment, in load command order.
+    fn segment_sizes(&self) -> Vec<u64> {
+        self.segments().map(|segment| segment.size()).collect()
+    }
+
+    /// Return the section at the given index.
+    #[inline]
+    fn section_internal(&self, index: SectionIndex) -> Result<&MachOSectionInternal<'data, Mach>> {
+        index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.sections.get(index))
+            .read_error("Invalid Mach-O section index")
+    }
+}
+
+impl<'data, Mach: MachHeader> read::private::Sealed for MachOFile<'data, Mach> {}
+
+impl<'data, 'file, Mach> Object<'data, 'file> for MachOFile<'data, Mach>
+where
+    'data: 'file,
+    Mach: MachHeader,
+{
+    type Segment = MachOSegment<'data, 'file, Mach>;
+    type SegmentIterator = MachOSegmentIterator<'data, 'file, Mach>;
+    type Section = MachOSection<'data, 'file, Mach>;
+    type SectionIterator = MachOSectionIterator<'data, 'file, Mach>;
+    type SymbolIterator = MachOSymbolIterator<'data, 'file, Mach>;
+
+    fn architecture(&self) -> Architecture {
+        cputype_architecture(self.header.cputype(self.endian))
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        self.header.is_little_endian()
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        self.header.is_type_64()
+    }
+
+    fn segments(&'file self) -> MachOSegmentIterator<'data, 'file, Mach> {
+        MachOSegmentIterator {
+            file: self,
+            commands: self.load_commands().ok().unwrap_or_else(Default::default),
+        }
+    }
+
+    fn section_by_name(
+        &'file self,
+        section_name: &str,
+    ) -> Option<MachOSection<'data, 'file, Mach>> {
+        // Compare the raw names to avoid UTF-8 validation. A name can only match
+        // if it is valid UTF-8, so this skips invalid names as required.
+        let section_name = section_name.as_bytes();
+        self.sections
+            .iter()
+            .find(|internal| section_name_matches(internal.section.name(), section_name))
+            .map(|internal| MachOSection {
+                file: self,
+                internal: *internal,
+            })
+    }
+
+    fn section_by_index(
+        &'file self,
+        index: SectionIndex,
+    ) -> Result<MachOSection<'data, 'file, Mach>> {
+        let internal = *self.section_internal(index)?;
+        Ok(MachOSection {
+            file: self,
+            internal,
+        })
+    }
+
+    fn sections(&'file self) -> MachOSectionIterator<'data, 'file, Mach> {
+        MachOSectionIterator {
+            file: self,
+            iter: self.sections.iter(),
+        }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Symbol<'data>> {
+        let nlist = self
+            .symbols
+            .symbols
+            .get(index.0)
+            .read_error("Invalid Mach-O symbol index")?;
+        parse_symbol(self, nlist, self.symbols.strings)
+            .read_error("Unsupported Mach-O symbol index")
+    }
+
+    fn symbols(&'file self) -> MachOSymbolIterator<'data, 'file, Mach> {
+        MachOSymbolIterator {
+            file: self,
+            symbols: self.symbols,
+            index: 0,
+        }
+    }
+
+    fn dynamic_symbols(&'file self) -> MachOSymbolIterator<'data, 'file, Mach> {
+        // The LC_DYSYMTAB command contains indices into the same symbol
+        // table as the LC_SYMTAB command, so return all of them.
+        self.symbols()
+    }
+
+    fn symbol_map(&self) -> SymbolMap<'data> {
+        let mut symbols: Vec<_> = self.sized_symbols().into_iter().map(|(_, s)| s).collect();
+        symbols.retain(SymbolMap::filter);
+        SymbolMap { symbols }
+    }
+
+    fn has_debug_symbols(&self) -> bool {
+        self.section_by_name(".debug_info").is_some()
+    }
+
+    fn mach_uuid(&self) -> Result<Option<[u8; 16]>> {
+        // Return the UUID from the `LC_UUID` load command, if one is present.
+        let mut commands = self.load_commands()?;
+        while let Some(command) = commands.next()? {
+            if let Some(uuid) = command.uuid()? {
+                return Ok(Some(uuid.uuid));
+            }
+        }
+        Ok(None)
+    }
+
+    fn code_id(&self) -> Option<String> {
+        self.mach_uuid().ok()?.map(|uuid| util::hex(&uuid))
+    }
+
+    /// Return the `entryoff` field of the `LC_MAIN` command.
+    ///
+    /// This is a file offset, not an address. Returns 0 if there is no `LC_MAIN`
+    /// command, including for files that use `LC_UNIXTHREAD` instead. Use
+    /// `MachOFile::entry_address` to get the address of the entry point from either
+    /// command, and `MachOFile::entry_source` to find which command provides it.
+    fn entry(&self) -> u64 {
+        match self.entry_info() {
+            Some((MachOEntrySource::Main, offset)) => offset,
+            _ => 0,
+        }
+    }
+
+    fn platform(&self) -> Option<Platform> {
+        let platform = match self.build_version().ok()??.platform {
+            macho::PLATFORM_MACOS => Platform::MacOS,
+            macho::PLATFORM_IOS => Platform::IOS,
+            macho::PLATFORM_TVOS => Platform::TvOS,
+            macho::PLATFORM_WATCHOS => Platform::WatchOS,
+            macho::PLATFORM_BRIDGEOS => Platform::BridgeOS,
+            macho::PLATFORM_IOSMAC => Platform::MacCatalyst,
+            macho::PLATFORM_IOSSIMULATOR => Platform::IOSSimulator,
+            macho::PLATFORM_TVOSSIMULATOR => Platform::TvOSSimulator,
+            macho::PLATFORM_WATCHOSSIMULATOR => Platform::WatchOSSimulator,
+            macho::PLATFORM_DRIVERKIT => Platform::DriverKit,
+            platform => Platform::MachO(platform),
+        };
+        Some(platform)
+    }
+
+    fn flags(&self) -> FileFlags {
+        FileFlags::MachO {
+            flags: self.header.flags(self.endian),
+        }
+    }
+}
+
+/// The location of an address within a `MachOFile`.
+///
+/// Returned by `MachOFile::resolve_address`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOAddressInfo<'data> {
+    /// The name of the segment containing the address.
+    ///
+    /// This is empty for the unnamed segment in an `MH_OBJECT` file.
+    pub segment_name: &'data [u8],
+    /// The name of the section containing the address.
+    pub section_name: &'data [u8],
+    /// The index of the section containing the address.
+    pub section_index: SectionIndex,
+    /// The offset of the address within the section.
+    pub offset: u64,
+}
+
+/// The segments and sections of a `MachOFile`.
+///
+/// Returned by `MachOFile::layout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachOLayout<'data> {
+    /// The segments, in load command order.
+    pub segments: Vec<MachOSegmentLayout<'data>>,
+}
+
+/// A segment in a `MachOLayout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachOSegmentLayout<'data> {
+    /// The segment name, up until the null terminator.
+    pub name: &'data [u8],
+    /// The virtual memory address of the segment.
+    pub address: u64,
+    /// The virtual memory size of the segment.
+    pub size: u64,
+    /// The offset and size of the segment in the file.
+    pub file_range: (u64, u64),
+    /// The maximum VM protection.
+    pub maxprot: u32,
+    /// The initial VM protection.
+    pub initprot: u32,
+    /// The sections in the segment.
+    pub sections: Vec<MachOSectionLayout<'data>>,
+}
+
+/// A section in a `MachOLayout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachOSectionLayout<'data> {
+    /// The section name, up until the null terminator.
+    pub name: &'data [u8],
+    /// The segment name in the section header, up until the null terminator.
+    pub segment_name: &'data [u8],
+    /// The virtual memory address of the section.
+    pub address: u64,
+    /// The size of the section.
+    pub size: u64,
+    /// The offset and size of the section in the file.
+    ///
+    /// This is `None` for sections that have no data in the file.
+    pub file_range: Option<(u64, u64)>,
+    /// The alignment of the section in bytes.
+    pub align: u64,
+    /// The `flags` field in the section header.
+    pub flags: u32,
+}
+
+fn cputype_architecture(cputype: u32) -> Architecture {
+    match cputype {
+        macho::CPU_TYPE_ARM => Architecture::Arm(ArmArchitecture::Arm),
+        macho::CPU_TYPE_ARM64 => Architecture::Aarch64(Aarch64Architecture::Aarch64),
+        // arm64_32 is the AArch64 instruction set with 32-bit pointers.
+        macho::CPU_TYPE_ARM64_32 => Architecture::Aarch64(Aarch64Architecture::Aarch64),
+        macho::CPU_TYPE_X86 => Architecture::I386,
+        macho::CPU_TYPE_X86_64 => Architecture::X86_64,
+        macho::CPU_TYPE_MIPS => Architecture::Mips,
+        _ => Architecture::Unknown,
+    }
+}
+
+fn section_name_matches(name: &[u8], section_name: &[u8]) -> bool {
+    // Translate the "." prefix to the "__" prefix used by OSX/Mach-O, eg
+    // ".debug_info" to "__debug_info".
+    name == section_name
+        || (section_name.first() == Some(&b'.')
+            && name.starts_with(b"__")
+            && name[2..] == section_name[1..])
+}
+
+fn cpu_architecture(cputype: u32, cpusubtype: u32) -> Architecture {
+    if cputype != macho::CPU_TYPE_ARM {
+        return cputype_architecture(cputype);
+    }
+    let arm = match cpusubtype & !macho::CPU_SUBTYPE_MASK {
+        macho::CPU_SUBTYPE_ARM_V4T => ArmArchitecture::Armv4t,
+        macho::CPU_SUBTYPE_ARM_V5TEJ => ArmArchitecture::Armv5tej,
+        macho::CPU_SUBTYPE_ARM_V6 => ArmArchitecture::Armv6,
+        macho::CPU_SUBTYPE_ARM_V6M => ArmArchitecture::Thumbv6m,
+        macho::CPU_SUBTYPE_ARM_V7 | macho::CPU_SUBTYPE_ARM_V7F | macho::CPU_SUBTYPE_ARM_V7K => {
+            ArmArchitecture::Armv7
+        }
+        macho::CPU_SUBTYPE_ARM_V7S => ArmArchitecture::Armv7s,
+        macho::CPU_SUBTYPE_ARM_V7M => ArmArchitecture::Thumbv7m,
+        macho::CPU_SUBTYPE_ARM_V7EM => ArmArchitecture::Thumbv7em,
+        macho::CPU_SUBTYPE_ARM_V8 => ArmArchitecture::Armv8,
+        _ => ArmArchitecture::Arm,
+    };
+    Architecture::Arm(arm)
+}
+
+/// Limits to apply when parsing a `MachOFile`.
+///
+/// The default has no limits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The maximum total number of sections in all segments.
+    pub max_sections: Option<usize>,
+    /// The maximum number of load commands given by `ncmds` in the header.
+    pub max_commands: Option<u32>,
+}
+
+/// A Mach-O universal binary, containing a Mach-O file for each of several architectures.
+#[derive(Debug, Clone, Copy)]
+pub struct MachOFatFile<'data> {
+    data: Bytes<'data>,
+    // Only one of these is non-empty, depending on the header magic.
+    arches32: &'data [macho::FatArch32],
+    arches64: &'data [macho::FatArch64],
+}
+
+impl<'data> MachOFatFile<'data> {
+    /// Parse the raw data of a Mach-O universal binary.
+    ///
+    /// This supports both the `FAT_MAGIC` and `FAT_MAGIC_64` formats.
+    /// Returns an error if the data for any architecture is outside the file,
+    /// or overlaps the fat header.
+    pub fn parse(data: &'data [u8]) -> Result<Self> {
+        let data = Bytes(data);
+        let mut tail = data;
+        // The fat header and arch entries are always big endian, regardless of the host
+        // or of the contained files.
+        let header = tail
+            .read::<macho::FatHeader>()
+            .read_error("Invalid fat header size or alignment")?;
+        let arch_size = match header.magic.get(BigEndian) {
+            macho::FAT_MAGIC => mem::size_of::<macho::FatArch32>(),
+            macho::FAT_MAGIC_64 => mem::size_of::<macho::FatArch64>(),
+            macho::FAT_CIGAM | macho::FAT_CIGAM_64 => {
+                return Err(Error("Unsupported little endian fat header"));
+            }
+            _ => return Err(Error("Unsupported fat header magic")),
+        };
+        // Check the count against the available space before using it, so that
+        // an absurd count from a corrupt or byte swapped header is rejected early.
+        let nfat_arch = header.nfat_arch.get(BigEndian) as usize;
+        if nfat_arch > tail.len() / arch_size {
+            return Err(Error("Invalid fat arch count"));
+        }
+        let mut file = MachOFatFile {
+            data,
+            arches32: &[],
+            arches64: &[],
+        };
+        if arch_size == mem::size_of::<macho::FatArch32>() {
+            file.arches32 = tail
+                .read_slice(nfat_arch)
+                .read_error("Invalid fat arch count")?;
+        } else {
+            file.arches64 = tail
+                .read_slice(nfat_arch)
+                .read_error("Invalid fat arch count")?;
+        }
+        let header_size = (data.len() - tail.len()) as u64;
+        for arch in file.arches() {
+            if arch.size != 0 && arch.offset < header_size {
+                return Err(Error("Invalid fat arch offset or size"));
+            }
+            match arch.offset.checked_add(arch.size) {
+                Some(end) if end <= data.len() as u64 => {}
+                _ => return Err(Error("Invalid fat arch offset or size")),
+            }
+        }
+        Ok(file)
+    }
+
+    /// Return the `fat_arch` or `fat_arch_64` entries in the header, in file order.
+    ///
+    /// The offset and size are widened to 64 bits so that both `fat_arch`
+    /// and `fat_arch_64` entries can be represented.
+    pub fn arches(&self) -> impl Iterator<Item = MachOFatArch> + 'data {
+        let arches32 = self.arches32.iter().map(|arch| MachOFatArch {
+            cputype: arch.cputype.get(BigEndian),
+            cpusubtype: arch.cpusubtype.get(BigEndian),
+            offset: arch.offset.get(BigEndian).into(),
+            size: arch.size.get(BigEndian).into(),
+            align: arch.align.get(BigEndian),
+        });
+        let arches64 = self.arches64.iter().map(|arch| MachOFatArch {
+            cputype: arch.cputype.get(BigEndian),
+            cpusubtype: arch.cpusubtype.get(BigEndian),
+            offset: arch.offset.get(BigEndian),
+            size: arch.size.get(BigEndian),
+            align: arch.align.get(BigEndian),
+        });
+        arches32.chain(arches64)
+    }
+
+    /// Return the architecture, build version and UUID of each Mach-O file.
+    ///
+    /// Each file is parsed once. The build version and UUID are `None` if
+    /// the file is missing the corresponding load command or can't be parsed.
+    pub fn build_info(&self) -> Vec<(Architecture, Option<MachOBuildVersion>, Option<[u8; 16]>)> {
+        self.arches()
+            .map(|arch| {
+                let architecture = cputype_architecture(arch.cputype);
+                // `parse` checked that the offset and size are within the file.
+                let (build_version, uuid) = self
+                    .data
+                    .read_bytes_at(arch.offset as usize, arch.size as usize)
+                    .map(|data| slice_build_info(data.0))
+                    .unwrap_or((None, None));
+                (architecture, build_version, uuid)
+            })
+            .collect()
+    }
+}
+
+/// An architecture entry in the header of a `MachOFatFile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOFatArch {
+    /// The CPU type. One of the `CPU_TYPE_*` constants.
+    pub cputype: u32,
+    /// The CPU subtype. One of the `CPU_SUBTYPE_*` constants.
+    pub cpusubtype: u32,
+    /// The file offset of the Mach-O file for this architecture.
+    pub offset: u64,
+    /// The size of the Mach-O file for this architecture.
+    pub size: u64,
+    /// The alignment of the offset, as a power of 2.
+    pub align: u32,
+}
+
+/// Return the build version and UUID of a Mach-O file in a universal binary.
+fn slice_build_info(data: &[u8]) -> (Option<MachOBuildVersion>, Option<[u8; 16]>) {
+    fn build_info<Mach: MachHeader>(data: &[u8]) -> (Option<MachOBuildVersion>, Option<[u8; 16]>) {
+        match MachOFile::<Mach>::parse(data) {
+            Ok(file) => (
+                file.build_version().unwrap_or(None),
+                file.mach_uuid().unwrap_or(None),
+            ),
+            Err(_) => (None, None),
+        }
+    }
+
+    match Bytes(data).read_at::<endian::U32<BigEndian>>(0) {
+        Ok(magic) => match magic.get(BigEndian) {
+            macho::MH_MAGIC | macho::MH_CIGAM => {
+                build_info::<macho::MachHeader32<RunTimeEndian>>(data)
+            }
+            macho::MH_MAGIC_64 | macho::MH_CIGAM_64 => {
+                build_info::<macho::MachHeader64<RunTimeEndian>>(data)
+            }
+            _ => (None, None),
+        },
+        Err(()) => (None, None),
+    }
+}
+
+/// An entry in an `MH_FILESET` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOFilesetEntry<'data> {
+    /// The identifier of the entry, such as a kext bundle identifier.
+    pub entry_id: &'data [u8],
+    /// The address of the entry.
+    pub vmaddr: u64,
+    /// The file offset of the entry's header.
+    pub fileoff: u64,
+}
+
+/// The load command that provides the entry point of a `MachOFile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachOEntrySource {
+    /// The `LC_MAIN` command. The entry point is a file offset.
+    Main,
+    /// The `LC_UNIXTHREAD` command. The entry point is the initial program counter.
+    UnixThread,
+}
+
+/// The Objective-C image info of a `MachOFile`.
+///
+/// This is read from the `__objc_imageinfo` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOObjcImageInfo {
+    /// The version of the image info. This is currently always zero.
+    pub version: u32,
+    /// Flags describing the image, such as whether it has been optimized by dyld.
+    ///
+    /// Bits 8 to 15 contain the Swift ABI version, if the image contains Swift code.
+    pub flags: u32,
+}
+
+/// An entry in the `__LD,__compact_unwind` section of a relocatable `MachOFile`.
+///
+/// The linker uses these entries to build the `__TEXT,__unwind_info` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOCompactUnwindEntry {
+    /// The address of the start of the function.
+    pub function_address: u64,
+    /// The length in bytes of the function.
+    pub function_length: u32,
+    /// The compact unwind encoding of the function.
+    pub encoding: u32,
+    /// The address of the personality function, or zero if there is none.
+    ///
+    /// This is zero if the personality function is an undefined symbol.
+    pub personality: u64,
+    /// The symbol that the personality function address is relocated against, if any.
+    pub personality_symbol: Option<SymbolIndex>,
+    /// The address of the language specific data area, or zero if there is none.
+    pub lsda: u64,
+}
+
+/// The format of the dynamic linking information in a `MachOFile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachOLinkeditFormat {
+    /// Relocations and indirect symbols only, as described by `LC_DYSYMTAB`.
+    ///
+    /// This is used by object files and by executables for older OS versions.
+    /// Use `MachOFile::external_relocations` and `MachOFile::local_relocations`.
+    Classic,
+    /// Rebase and bind opcodes and an export trie, as described by `LC_DYLD_INFO`.
+    ///
+    /// Use `MachOFile::lazy_bind_at` and `MachOFile::export`.
+    DyldInfo,
+    /// Chained fixups, as described by `LC_DYLD_CHAINED_FIXUPS`.
+    ///
+    /// Use `MachOFile::chained_imports`. Decoding of the fixup chains is not
+    /// supported. The export trie is usually present in a `LC_DYLD_EXPORTS_TRIE`
+    /// command, and can be used with `MachOFile::export`.
+    ///
+    /// Pointers in the segment data are encoded as chained fixups. Only
+    /// `MachOFile::objc_selrefs` and `MachOFile::objc_classes` decode them, and
+    /// these return an error if the pointer format of the segment is not supported.
+    /// Other methods, such as `MachOFile::read_at_address`, return the encoded values.
+    ChainedFixups,
+}
+
+/// The target platform and versions of a `MachOFile`.
+///
+/// This is read from the `LC_BUILD_VERSION` command, or from one of the
+/// `LC_VERSION_MIN_*` commands in older files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOBuildVersion {
+    /// The target platform. One of the `PLATFORM_*` constants.
+    pub platform: u32,
+    /// The minimum OS version, encoded as `xxxx.yy.zz` in nibbles.
+    pub minos: u32,
+    /// The SDK version, encoded as `xxxx.yy.zz` in nibbles.
+    pub sdk: u32,
+}
+
+/// The embedded code signature of a `MachOFile`.
+///
+/// This is a `SuperBlob` that contains an index of blobs such as the
+/// `CodeDirectory`, the requirements and the entitlements.
+/// Code signing data is always big endian.
+#[derive(Debug, Clone, Copy)]
+pub struct MachOCodeSignature<'data> {
+    data: Bytes<'data>,
+    index: &'data [macho::CsBlobIndex],
+}
+
+impl<'data> MachOCodeSignature<'data> {
+    fn parse(data: Bytes<'data>) -> Result<Self> {
+        let header = data
+            .read_at::<macho::CsSuperBlob>(0)
+            .read_error("Invalid Mach-O code signature header")?;
+        if header.magic.get(BigEndian) != macho::CSMAGIC_EMBEDDED_SIGNATURE {
+            return Err(Error("Unsupported Mach-O code signature magic"));
+        }
+        let data = data
+            .read_bytes_at(0, header.length.get(BigEndian) as usize)
+            .read_error("Invalid Mach-O code signature length")?;
+        let index = data
+            .read_slice_at(
+                mem::size_of::<macho::CsSuperBlob>(),
+                header.count.get(BigEndian) as usize,
+            )
+            .read_error("Invalid Mach-O code signature index count")?;
+        Ok(MachOCodeSignature { data, index })
+    }
+
+    /// The raw bytes of the `SuperBlob`.
+    #[inline]
+    pub fn data(&self) -> &'data [u8] {
+        self.data.0
+    }
+
+    /// The slot types in the index, in file order.
+    ///
+    /// These are usually `CSSLOT_*` constants.
+    pub fn slots(&self) -> impl Iterator<Item = u32> + 'data {
+        self.index.iter().map(|entry| entry.typ.get(BigEndian))
+    }
+
+    /// Return the blob for the given slot type, if present.
+    ///
+    /// The returned bytes include the blob's `magic` and `length` header.
+    pub fn blob(&self, slot: u32) -> Result<Option<&'data [u8]>> {
+        let entry = match self
+            .index
+            .iter()
+            .find(|entry| entry.typ.get(BigEndian) == slot)
+        {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let offset = entry.offset.get(BigEndian) as usize;
+        let header = self
+            .data
+            .read_at::<macho::CsGenericBlob>(offset)
+            .read_error("Invalid Mach-O code signature blob offset")?;
+        let blob = self
+            .data
+            .read_bytes_at(offset, header.length.get(BigEndian) as usize)
+            .read_error("Invalid Mach-O code signature blob length")?;
+        Ok(Some(blob.0))
+    }
+
+    /// The identifier in the `CodeDirectory`, if present.
+    ///
+    /// This is usually the bundle identifier of the signed code.
+    pub fn identifier(&self) -> Result<Option<&'data str>> {
+        let (code_directory, data) = match self.code_directory()? {
+            Some(code_directory) => code_directory,
+            None => return Ok(None),
+        };
+        let identifier = data
+            .read_string_at(code_directory.ident_offset.get(BigEndian) as usize)
+            .read_error("Invalid Mach-O code signing identifier offset")?;
+        str::from_utf8(identifier)
+            .ok()
+            .read_error("Non UTF-8 Mach-O code signing identifier")
+            .map(Some)
+    }
+
+    /// The team identifier in the `CodeDirectory`, if present.
+    ///
+    /// Returns `Ok(None)` if the `CodeDirectory` version predates team
+    /// identifiers, or if the team offset is zero.
+    pub fn team_id(&self) -> Result<Option<&'data str>> {
+        let (code_directory, data) = match self.code_directory()? {
+            Some(code_directory) => code_directory,
+            None => return Ok(None),
+        };
+        if code_directory.version.get(BigEndian) < macho::CS_SUPPORTSTEAMID {
+            return Ok(None);
+        }
+        let offset = data
+            .read_at::<endian::U32Bytes<BigEndian>>(macho::CS_TEAM_OFFSET)
+            .read_error("Invalid Mach-O code directory size")?
+            .get(BigEndian);
+        if offset == 0 {
+            return Ok(None);
+        }
+        let team_id = data
+            .read_string_at(offset as usize)
+            .read_error("Invalid Mach-O code signing team identifier offset")?;
+        str::from_utf8(team_id)
+            .ok()
+            .read_error("Non UTF-8 Mach-O code signing team identifier")
+            .map(Some)
+    }
+
+    /// The flags in the `CodeDirectory`, if present.
+    pub fn flags(&self) -> Result<Option<MachOCodeSignatureFlags>> {
+        let (code_directory, _) = match self.code_directory()? {
+            Some(code_directory) => code_directory,
+            None => return Ok(None),
+        };
+        Ok(Some(MachOCodeSignatureFlags::new(
+            code_directory.flags.get(BigEndian),
+        )))
+    }
+
+    /// The entitlements plist, if present.
+    ///
+    /// This is the XML data of the `CSSLOT_ENTITLEMENTS` blob, excluding the blob header.
+    pub fn entitlements(&self) -> Result<Option<&'data [u8]>> {
+        let mut data = match self.blob(macho::CSSLOT_ENTITLEMENTS)? {
+            Some(data) => Bytes(data),
+            None => return Ok(None),
+        };
+        let header = data
+            .read::<macho::CsGenericBlob>()
+            .read_error("Invalid Mach-O entitlements blob size")?;
+        if header.magic.get(BigEndian) != macho::CSMAGIC_EMBEDDED_ENTITLEMENTS {
+            return Err(Error("Invalid Mach-O entitlements blob magic"));
+        }
+        Ok(Some(data.0))
+    }
+
+    /// Return the `CodeDirectory` blob header and data, if present.
+    fn code_directory(&self) -> Result<Option<(&'data macho::CsCodeDirectory, Bytes<'data>)>> {
+        let data = match self.blob(macho::CSSLOT_CODEDIRECTORY)? {
+            Some(data) => Bytes(data),
+            None => return Ok(None),
+        };
+        let code_directory = data
+            .read_at::<macho::CsCodeDirectory>(0)
+            .read_error("Invalid Mach-O code directory size")?;
+        if code_directory.magic.get(BigEndian) != macho::CSMAGIC_CODEDIRECTORY {
+            return Err(Error("Invalid Mach-O code directory magic"));
+        }
+        Ok(Some((code_directory, data)))
+    }
+}
+
+/// The flags in the `CodeDirectory` of a `MachOCodeSignature`.
+///
+/// These are the code signing attributes that the kernel applies to the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOCodeSignatureFlags {
+    /// The raw flags. A combination of the `CS_*` constants.
+    pub flags: u32,
+    /// The hardened runtime is enabled. This is the `CS_RUNTIME` flag.
+    pub hardened_runtime: bool,
+    /// Library validation is required. This is the `CS_REQUIRE_LV` flag.
+    pub library_validation: bool,
+    /// dyld treats the process as restricted. This is the `CS_RESTRICT` flag.
+    pub restrict: bool,
+    /// Invalid pages are not loaded. This is the `CS_HARD` flag.
+    pub hard: bool,
+    /// The process is killed if it becomes invalid. This is the `CS_KILL` flag.
+    pub kill: bool,
+}
+
+impl MachOCodeSignatureFlags {
+    fn new(flags: u32) -> Self {
+        MachOCodeSignatureFlags {
+            flags,
+            hardened_runtime: flags & macho::CS_RUNTIME != 0,
+            library_validation: flags & macho::CS_REQUIRE_LV != 0,
+            restrict: flags & macho::CS_RESTRICT != 0,
+            hard: flags & macho::CS_HARD != 0,
+            kill: flags & macho::CS_KILL != 0,
+        }
+    }
+}
+
+/// An iterator over the segments of a `MachOFile32`.
+pub type MachOSegmentIterator32<'data, 'file, Endian = RunTimeEndian> =
+    MachOSegmentIterator<'data, 'file, macho::MachHeader32<Endian>>;
+/// An iterator over the segments of a `MachOFile64`.
+pub type MachOSegmentIterator64<'data, 'file, Endian = RunTimeEndian> =
+    MachOSegmentIterator<'data, 'file, macho::MachHeader64<Endian>>;
+
+/// An iterator over the segments of a `MachOFile`.
+#[derive(Debug)]
+pub struct MachOSegmentIterator<'data, 'file, Mach>
+where
+    'data: 'file,
+    Mach: MachHeader,
+{
+    file: &'file MachOFile<'data, Mach>,
+    commands: MachOLoadCommandIterator<'data, Mach::Endian>,
+}
+
+impl<'data, 'file, Mach: MachHeader> Iterator for MachOSegmentIterator<'data, 'file, Mach> {
+    type Item = MachOSegment<'data, 'file, Mach>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let command = self.commands.next().ok()??;
+            if let Ok(Some((segment, _))) = Mach::Segment::from_command(command) {
+                return Some(MachOSegment {
+                    file: self.file,
+                    segment,
+                });
+            }
+        }
+    }
+}
+
+/// A segment of a `MachOFile32`.
+pub type MachOSegment32<'data, 'file, Endian = RunTimeEndian> =
+    MachOSegment<'data, 'file, macho::MachHeader32<Endian>>;
+/// A segment of a `MachOFile64`.
+pub type MachOSegment64<'data, 'file, Endian = RunTimeEndian> =
+    MachOSegment<'data, 'file, macho::MachHeader64<Endian>>;
+
+/// A segment of a `MachOFile`.
+#[derive(Debug)]
+pub struct MachOSegment<'data, 'file, Mach>
+where
+    'data: 'file,
+    Mach: MachHeader,
+{
+    file: &'file MachOFile<'data, Mach>,
+    segment: &'data Mach::Segment,
+}
+
+impl<'data, 'file, Mach: MachHeader> MachOSegment<'data, 'file, Mach> {
+    fn bytes(&self) -> Result<Bytes<'data>> {
+        self.segment
+            .data(self.file.endian, self.file.data)
+            .read_error("Invalid Mach-O segment size or offset")
+    }
+
+    /// Return the segment data as `Bytes`.
+    ///
+    /// This is the same as `data`, but allows reading `Pod` types at offsets
+    /// within the data.
+    #[inline]
+    pub fn bytes_checked(&self) -> Result<Bytes<'data>> {
+        self.bytes()
+    }
+
+    /// Return the raw bytes of the segment name, up until the null terminator.
+    ///
+    /// Unlike `name`, this does not require the name to be UTF-8.
+    #[inline]
+    pub fn name_bytes(&self) -> &'data [u8] {
+        self.segment.name()
+    }
+
+    /// Return true if this is the `__PAGEZERO` guard segment.
+    ///
+    /// The segment must be named `__PAGEZERO`, have no file data, and have
+    /// no initial protection.
+    pub fn is_pagezero(&self) -> bool {
+        let endian = self.file.endian;
+        self.segment.name() == b"__PAGEZERO"
+            && self.segment.filesize(endian).into() == 0
+            && self.segment.initprot(endian) == macho::VM_PROT_NONE
+    }
+}
+
+impl<'data, 'file, Mach: MachHeader> read::private::Sealed for MachOSegment<'data, 'file, Mach> {}
+
+impl<'data, 'file, Mach: MachHeader> ObjectSegment<'data> for MachOSegment<'data, 'file, Mach> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.segment.vmaddr(self.file.endian).into()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.segment.vmsize(self.file.endian).into()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        // Page size.
+        0x1000
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.segment.file_range(self.file.endian)
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        Ok(self.bytes()?.0)
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::data_range(
+            self.bytes()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        let name = self.segment.name();
+        if name.is_empty() {
+            // The single segment in an `MH_OBJECT` file has no name.
+            return Ok(None);
+        }
+        Ok(Some(
+            str::from_utf8(name)
+                .ok()
+                .read_error("Non UTF-8 Mach-O segment name")?,
+        ))
+    }
+}
+
+/// An iterator over the sections of a `MachOFile32`.
+pub type MachOSectionIterator32<'data, 'file, Endian = RunTimeEndian> =
+    MachOSectionIterator<'data, 'file, macho::MachHeader32<Endian>>;
+/// An iterator over the sections of a `MachOFile64`.
+pub type MachOSectionIterator64<'data, 'file, Endian = RunTimeEndian> =
+    MachOSectionIterator<'data, 'file, macho::MachHeader64<Endian>>;
+
+/// An iterator over the sections of a `MachOFile`.
+pub struct MachOSectionIterator<'data, 'file, Mach>
+where
+    'data: 'file,
+    Mach: MachHeader,
+{
+    file: &'file MachOFile<'data, Mach>,
+    iter: slice::Iter<'file, MachOSectionInternal<'data, Mach>>,
+}
+
+impl<'data, 'file, Mach: MachHeader> fmt::Debug for MachOSectionIterator<'data, 'file, Mach> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // It's painful to do much better than this
+        f.debug_struct("MachOSectionIterator").finish()
+    }
+}
+
+impl<'data, 'file, Mach: MachHeader> Iterator for MachOSectionIterator<'data, 'file, Mach> {
+    type Item = MachOSection<'data, 'file, Mach>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|&internal| MachOSection {
+            file: self.file,
+            internal,
+        })
+    }
+}
+
+/// A section of a `MachOFile32`.
+pub type MachOSection32<'data, 'file, Endian = RunTimeEndian> =
+    MachOSection<'data, 'file, macho::MachHeader32<Endian>>;
+/// A section of a `MachOFile64`.
+pub type MachOSection64<'data, 'file, Endian = RunTimeEndian> =
+    MachOSection<'data, 'file, macho::MachHeader64<Endian>>;
+
+/// A section of a `MachOFile`.
+#[derive(Debug)]
+pub struct MachOSection<'data, 'file, Mach>
+where
+    'data: 'file,
+    Mach: MachHeader,
+{
+    file: &'file MachOFile<'data, Mach>,
+    internal: MachOSectionInternal<'data, Mach>,
+}
+
+impl<'data, 'file, Mach: MachHeader> MachOSection<'data, 'file, Mach> {
+    fn bytes(&self) -> Result<Bytes<'data>> {
+        self.internal
+            .section
+            .data(self.file.endian, self.file.data)
+            .read_error("Invalid Mach-O section size or offset")
+    }
+
+    /// Return the section data as `Bytes`.
+    ///
+    /// This is the same as `data`, but allows reading `Pod` types at offsets
+    /// within the data.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), object::read::Error> {
+    /// use object::endian::{LittleEndian, U32};
+    /// use object::read::macho::MachOFile64;
+    /// use object::read::Object;
+    ///
+    /// # let mut data = vec![
+    /// #     0xcf, 0xfa, 0xed, 0xfe, 0x07, 0x00, 0x00, 0x01, 0x03, 0x00, 0x00, 0x00,
+    /// #     0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x98, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00,
+    /// #     0x98, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00,
+    /// #     0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5f, 0x5f, 0x74, 0x65,
+    /// #     0x78, 0x74, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x5f, 0x5f, 0x54, 0x45, 0x58, 0x54, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xb8, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x04, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// #     0x00, 0x00, 0x00, 0x00,
+    /// # ];
+    /// # data.extend_from_slice(&0x1234_5678u32.to_le_bytes());
+    /// let file = MachOFile64::<LittleEndian>::parse(&data)?;
+    /// let section = file.section_by_name("__text").unwrap();
+    /// let bytes = section.bytes_checked()?;
+    /// let word = bytes.read_at::<U32<LittleEndian>>(0).unwrap();
+    /// assert_eq!(word.get(LittleEndian), 0x1234_5678);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn bytes_checked(&self) -> Result<Bytes<'data>> {
+        self.bytes()
+    }
+
+    /// Return the section contents as a loader would map them.
+    ///
+    /// This allocates a buffer of the section's size. The section must lie within the
+    /// `vmsize` of a segment, and an error is returned if it doesn't, so the size of
+    /// the buffer is limited by the size of the segment. The file data of the section
+    /// is copied into the start of the buffer, and the rest is zero-filled. The file
+    /// data is limited to the `filesize` of the segment, so a section that extends past
+    /// the file data of its segment has a zero-filled tail. The buffer of a zero-fill
+    /// section is entirely zero.
+    ///
+    /// The section and segment sizes are not limited by the size of the file, and
+    /// failing to allocate the buffer aborts the process. For untrusted input, check
+    /// that `size` is acceptable before calling this.
+    pub fn data_owned(&self) -> Result<Vec<u8>> {
+        let address = self.address();
+        let size = self.size();
+        let segment = self
+            .file
+            .segments()
+            .find(|segment| {
+                let offset = address.wrapping_sub(segment.address());
+                offset < segment.size() && size <= segment.size() - offset
+            })
+            .read_error("Mach-O section is not within a segment")?;
+        let size = usize::try_from(size)
+            .ok()
+            .read_error("Invalid Mach-O section size")?;
+        let mut data = vec![0; size];
+        if let Some((offset, _)) = self.file_range() {
+            let segment_file_end = segment.address().saturating_add(segment.file_range().1);
+            let file_size = cmp::min(segment_file_end.saturating_sub(address), size as u64);
+            let file_data = self
+                .file
+                .data
+                .read_bytes_at(offset as usize, file_size as usize)
+                .read_error("Invalid Mach-O section size or offset")?;
+            data[..file_size as usize].copy_from_slice(file_data.0);
+        }
+        Ok(data)
+    }
+
+    /// Return the raw bytes of the section name, up until the null terminator.
+    ///
+    /// Unlike `name`, this does not require the name to be UTF-8.
+    #[inline]
+    pub fn name_bytes(&self) -> &'data [u8] {
+        self.internal.section.name()
+    }
+
+    /// Return the raw bytes of the segment name, up until the null terminator.
+    ///
+    /// Unlike `segment_name`, this does not require the name to be UTF-8.
+    #[inline]
+    pub fn segment_name_bytes(&self) -> &'data [u8] {
+        self.internal.section.segment_name()
+    }
+
+    /// Return the `offset` field of the section header.
+    ///
+    /// Unlike `file_range`, this is returned for zero-fill sections too, for which it
+    /// is conventionally the file offset where the data of the following section begins.
+    #[inline]
+    pub fn raw_file_offset(&self) -> u32 {
+        self.internal.section.offset(self.file.endian)
+    }
+
+    /// Return the file offset and count of the relocation entries for this section.
+    ///
+    /// These are the `reloff` and `nreloc` fields in the section header.
+    /// Returns `None` if the section has no relocations.
+    pub fn relocation_range(&self) -> Option<(u64, u32)> {
+        let endian = self.file.endian;
+        let nreloc = self.internal.section.nreloc(endian);
+        if nreloc == 0 {
+            return None;
+        }
+        Some((self.internal.section.reloff(endian).into(), nreloc))
+    }
+
+    /// Return the indirect symbol for each entry in this section.
+    ///
+    /// This applies to symbol stub sections such as `__stubs`, and symbol pointer
+    /// sections such as `__got` and `__la_symbol_ptr`. Each item is the address of
+    /// an entry and the index of its target symbol, or `None` for entries marked
+    /// `INDIRECT_SYMBOL_LOCAL` or `INDIRECT_SYMBOL_ABS`.
+    ///
+    /// Other sections have no entries.
+    pub fn indirect_symbols(
+        &self,
+    ) -> Result<impl Iterator<Item = (u64, Option<SymbolIndex>)> + 'file> {
+        let endian = self.file.endian;
+        let section = self.internal.section;
+        let entry_size = match section.flags(endian) & macho::SECTION_TYPE {
+            macho::S_SYMBOL_STUBS => u64::from(section.reserved2(endian)),
+            macho::S_NON_LAZY_SYMBOL_POINTERS
+            | macho::S_LAZY_SYMBOL_POINTERS
+            | macho::S_LAZY_DYLIB_SYMBOL_POINTERS
+            | macho::S_THREAD_LOCAL_VARIABLE_POINTERS => self.file.pointer_size(),
+            _ => 0,
+        };
+        let entries = match self.size().checked_div(entry_size) {
+            Some(count) => {
+                let start = section.reserved1(endian) as usize;
+                self.file
+                    .indirect_symbol_table()?
+                    .get(start..)
+                    .and_then(|entries| entries.get(..count as usize))
+                    .read_error("Invalid Mach-O indirect symbol index or count")?
+            }
+            None => &[],
+        };
+        let address = self.address();
+        Ok(entries.iter().enumerate().map(move |(i, entry)| {
+            let entry = entry.get(endian);
+            let symbol = if entry & (macho::INDIRECT_SYMBOL_LOCAL | macho::INDIRECT_SYMBOL_ABS) != 0
+            {
+                None
+            } else {
+                Some(SymbolIndex(entry as usize))
+            };
+            (address + i as u64 * entry_size, symbol)
+        }))
+    }
+
+    /// Return the segment containing this section.
+    ///
+    /// This is the segment whose name matches the section's segment name.
+    /// In an `MH_OBJECT` file, this is the single unnamed segment instead.
+    pub fn segment(&self) -> Option<MachOSegment<'data, 'file, Mach>> {
+        let segment_name = self.internal.section.segment_name();
+        let mut unnamed = None;
+        for segment in self.file.segments() {
+            let name = segment.segment.name();
+            if name == segment_name {
+                return Some(segment);
+            }
+            if name.is_empty() && unnamed.is_none() {
+                unnamed = Some(segment);
+            }
+        }
+        unnamed
+    }
+
+    /// Get the relocations for this section, or an error if the relocation entries
+    /// are invalid.
+    ///
+    /// `relocations` returns no relocations for an invalid `reloff` or `nreloc`.
+    pub fn try_relocations(&self) -> Result<MachORelocationIterator<'data, 'file, Mach>> {
+        let relocations = self
+            .internal
+            .section
+            .relocations(self.file.endian, self.file.data)?;
+        Ok(MachORelocationIterator {
+            file: self.file,
+            relocations: relocations.iter(),
+        })
+    }
+
+    /// Get the relocations for this section, with offsets converted to addresses.
+    ///
+    /// The offsets returned by `relocations` are relative to the start of the
+    /// section. This adds the section address to each offset.
+    pub fn relocations_with_addresses(&self) -> impl Iterator<Item = (u64, Relocation)> + 'file {
+        let address = self.address();
+        self.relocations()
+            .map(move |(offset, relocation)| (address.wrapping_add(offset), relocation))
+    }
+
+    /// Get the relocations for this section, with offsets converted to file offsets.
+    ///
+    /// The offsets returned by `relocations` are relative to the start of the
+    /// section. This adds the section file offset to each offset.
+    pub fn relocations_with_file_offsets(&self) -> impl Iterator<Item = (u64, Relocation)> + 'file {
+        let file_offset = u64::from(self.internal.section.offset(self.file.endian));
+        self.relocations()
+            .map(move |(offset, relocation)| (file_offset.wrapping_add(offset), relocation))
+    }
+
+    /// Get the relocations for this section, with implicit addends applied.
+    ///
+    /// For relocations that have an implicit addend, the value stored at the
+    /// place is read from the section data and added to the relocation's addend.
+    ///
+    /// Relocations whose place is an instruction encoding rather than a plain
+    /// integer, such as most ARM and ARM64 relocations, are returned unchanged
+    /// and still report `has_implicit_addend`. So are relocations with a
+    /// `RelocationKind::MachO` kind, such as an `X86_64_RELOC_SUBTRACTOR` and the
+    /// `X86_64_RELOC_UNSIGNED` that it is paired with, which receives the stored value.
+    ///
+    /// For relocations against sections, the place holds the address of the target
+    /// rather than an offset. The addend is adjusted to be relative to the address of
+    /// the target section, so that adding the address returned by
+    /// `MachOFile::relocation_target_address` gives the target address.
+    pub fn resolved_relocations(&self) -> Result<impl Iterator<Item = (u64, Relocation)> + 'file> {
+        let file = self.file;
+        let endian = file.endian;
+        let cputype = file.header.cputype(endian);
+        let address = self.address();
+        let data = self.bytes()?;
+        Ok(self.relocations().map(move |(offset, mut relocation)| {
+            if !relocation.implicit_addend {
+                return (offset, relocation);
+            }
+            let addend =
+                match relocation_implicit_addend(endian, cputype, data, offset, &relocation) {
+                    Some(addend) => addend,
+                    None => return (offset, relocation),
+                };
+            if let RelocationTarget::Section(index) = relocation.target {
+                let section_address: u64 = match file.section_internal(index) {
+                    Ok(section) => section.section.addr(endian).into(),
+                    Err(_) => return (offset, relocation),
+                };
+                // For relative relocations, the stored value is relative to the end
+                // of the place, which is the place address minus the initial addend.
+                let mut target = addend as u64;
+                if relocation.kind != RelocationKind::Absolute {
+                    target = target
+                        .wrapping_add(address.wrapping_add(offset))
+                        .wrapping_sub(relocation.addend as u64);
+                }
+                relocation.addend = relocation
+                    .addend
+                    .wrapping_add(target.wrapping_sub(section_address) as i64);
+            } else {
+                relocation.addend = relocation.addend.wrapping_add(addend);
+            }
+            relocation.implicit_addend = false;
+            (offset, relocation)
+        }))
+    }
+
+    /// Return a copy of the section data with relocations applied.
+    ///
+    /// `resolver` returns the address of the symbol with the given index.
+    ///
+    /// Only `RelocationKind::Absolute` (S + A) and `RelocationKind::Relative` (S + A - P)
+    /// relocations against symbols are supported, where P is the address of the place
+    /// in this section. The place must be a plain integer of 8, 16, 32 or 64 bits.
+    /// Relocations against sections are left unchanged, since the data already
+    /// contains the target address.
+    ///
+    /// Returns an error for unsupported relocations, for unresolved symbols,
+    /// and for values that don't fit in the place.
+    pub fn relocated_data<F>(&self, resolver: F) -> Result<Vec<u8>>
+    where
+        F: Fn(SymbolIndex) -> Option<u64>,
+    {
+        let endian = self.file.endian;
+        let mut data = self.bytes()?.0.to_vec();
+        for (offset, relocation) in self.resolved_relocations()? {
+            let symbol = match relocation.target {
+                RelocationTarget::Symbol(symbol) => symbol,
+                RelocationTarget::Section(_) => continue,
+            };
+            if relocation.implicit_addend {
+                return Err(Error("Unsupported Mach-O relocation place"));
+            }
+            let value = resolver(symbol)
+                .read_error("Unresolved Mach-O relocation symbol")?
+                .wrapping_add(relocation.addend as u64);
+            let value = match relocation.kind {
+                RelocationKind::Absolute => value,
+                RelocationKind::Relative => value.wrapping_sub(self.address().wrapping_add(offset)),
+                _ => return Err(Error("Unsupported Mach-O relocation kind")),
+            };
+            let relative = relocation.kind == RelocationKind::Relative;
+            let mut bytes = [0; 8];
+            let size = match relocation.size {
+                8 => {
+                    bytes[0] = relocation_value(value, 8, relative)? as u8;
+                    1
+                }
+                16 => {
+                    let value = relocation_value(value, 16, relative)? as u16;
+                    bytes[..2].copy_from_slice(&endian.write_u16_bytes(value));
+                    2
+                }
+                32 => {
+                    let value = relocation_value(value, 32, relative)? as u32;
+                    bytes[..4].copy_from_slice(&endian.write_u32_bytes(value));
+                    4
+                }
+                64 => {
+                    bytes.copy_from_slice(&endian.write_u64_bytes(value));
+                    8
+                }
+                _ => return Err(Error("Unsupported Mach-O relocation size")),
+            };
+            let place = usize::try_from(offset)
+                .ok()
+                .and_then(|offset| data.get_mut(offset..)?.get_mut(..size))
+                .read_error("Invalid Mach-O relocation offset")?;
+            place.copy_from_slice(&bytes[..size]);
+        }
+        Ok(data)
+    }
+}
+
+/// Check that a relocation value fits in a place of the given number of bits.
+///
+/// Relative values must fit as signed integers, and absolute values must fit as
+/// either signed or unsigned integers.
+fn relocation_value(value: u64, bits: u32, relative: bool) -> Result<u64> {
+    let signed = value as i64;
+    let min = -(1i64 << (bits - 1));
+    let max = (1i64 << (bits - 1)) - 1;
+    if (signed >= min && signed <= max) || (!relative && value >> bits == 0) {
+        Ok(value)
+    } else {
+        Err(Error("Mach-O relocation value out of range"))
+    }
+}
+
+/// Read the implicit addend stored at the place of a relocation.
+///
+/// Returns `None` if the place is not a plain integer or is out of bounds.
+///
+/// Only relocation kinds with a known meaning are handled. Relocations with a
+/// `RelocationKind::MachO` kind, such as `X86_64_RELOC_SUBTRACTOR` pairs,
+/// `GENERIC_RELOC_SECTDIFF` pairs and `X86_64_RELOC_SIGNED_1`, are not, since
+/// the stored value applies to the pair as a whole or includes an extra bias.
+fn relocation_implicit_addend<E: Endian>(
+    endian: E,
+    cputype: u32,
+    data: Bytes,
+    offset: u64,
+    relocation: &Relocation,
+) -> Option<i64> {
+    let x86 = cputype == macho::CPU_TYPE_X86 || cputype == macho::CPU_TYPE_X86_64;
+    let relative = match relocation.kind {
+        RelocationKind::Absolute => false,
+        RelocationKind::Relative
+        | RelocationKind::GotRelative
+        | RelocationKind::PltRelative
+        | RelocationKind::TlvRelative
+            if x86 =>
+        {
+            true
+        }
+        _ => return None,
+    };
+    let offset = offset as usize;
+    let addend = match relocation.size {
+        8 => {
+            let value = *data.read_at::<u8>(offset).ok()?;
+            if relative {
+                i64::from(value as i8)
+            } else {
+                i64::from(value)
+            }
+        }
+        16 => {
+            let value = data
+                .read_at::<endian::U16Bytes<E>>(offset)
+                .ok()?
+                .get(endian);
+            if relative {
+                i64::from(value as i16)
+            } else {
+                i64::from(value)
+            }
+        }
+        32 => {
+            let value = data
+                .read_at::<endian::U32Bytes<E>>(offset)
+                .ok()?
+                .get(endian);
+            if relative {
+                i64::from(value as i32)
+            } else {
+                i64::from(value)
+            }
+        }
+        64 => data
+            .read_at::<endian::U64Bytes<E>>(offset)
+            .ok()?
+            .get(endian) as i64,
+        _ => return None,
+    };
+    Some(addend)
+}
+
+impl<'data, 'file, Mach: MachHeader> read::private::Sealed for MachOSection<'data, 'file, Mach> {}
+
+impl<'data, 'file, Mach: MachHeader> ObjectSection<'data> for MachOSection<'data, 'file, Mach> {
+    type RelocationIterator = MachORelocationIterator<'data, 'file, Mach>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.internal.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.internal.section.addr(self.file.endian).into()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.internal.section.size(self.file.endian).into()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        1 << self.internal.section.align(self.file.endian)
+    }
+
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        self.internal.section.file_range(self.file.endian)
+    }
+
+    #[inline]
+    fn data(&self) -> Result<&'data [u8]> {
+        Ok(self.bytes()?.0)
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::data_range(
+            self.bytes()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[cfg(feature = "compression")]
+    #[inline]
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        Ok(Cow::from(self.data()?))
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&str> {
+        str::from_utf8(self.internal.section.name())
+            .ok()
+            .read_error("Non UTF-8 Mach-O section name")
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(Some(
+            str::from_utf8(self.internal.section.segment_name())
+                .ok()
+                .read_error("Non UTF-8 Mach-O segment name")?,
+        ))
+    }
+
+    fn kind(&self) -> SectionKind {
+        self.internal.kind
+    }
+
+    fn relocations(&self) -> MachORelocationIterator<'data, 'file, Mach> {
+        self.try_relocations()
+            .unwrap_or_else(|_| MachORelocationIterator {
+                file: self.file,
+                relocations: [].iter(),
+            })
+    }
+
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::MachO {
+            flags: self.internal.section.flags(self.file.endian),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MachOSectionInternal<'data, Mach: MachHeader> {
+    index: SectionIndex,
+    kind: SectionKind,
+    section: &'data Mach::Section,
+}
+
+impl<'data, Mach: MachHeader> MachOSectionInternal<'data, Mach> {
+    fn parse(endian: Mach::Endian, index: SectionIndex, section: &'data Mach::Section) -> Self {
+        // TODO: we don't validate flags for known section names, should we?
+        let segment_name = match section.segment_name() {
+            // Some object files leave the segment name of the section empty,
+            // so use the segment that the section name is normally in.
+            b"" => match section.name() {
+                b"__data" | b"__bss" | b"__common" | b"__thread_data" | b"__thread_bss"
+                | b"__thread_vars" => &b"__DATA"[..],
+                name if name.starts_with(b"__debug_") => &b"__DWARF"[..],
+                _ => &b"__TEXT"[..],
+            },
+            segment_name => segment_name,
+        };
+        let kind = match (segment_name, section.name()) {
+            (b"__TEXT", b"__text") => SectionKind::Text,
+            (b"__TEXT", b"__const") => SectionKind::ReadOnlyData,
+            (b"__TEXT", b"__cstring") => SectionKind::ReadOnlyString,
+            (b"__TEXT", b"__literal4") => SectionKind::ReadOnlyData,
+            (b"__TEXT", b"__literal8") => SectionKind::ReadOnlyData,
+            (b"__TEXT", b"__literal16") => SectionKind::ReadOnlyData,
+            (b"__TEXT", b"__eh_frame") => SectionKind::ReadOnlyData,
+            (b"__TEXT", b"__gcc_except_tab") => SectionKind::ReadOnlyData,
+            (b"__TEXT", name) if name.starts_with(b"__swift5_") => SectionKind::ReadOnlyData,
+            (b"__DATA", b"__data") => SectionKind::Data,
+            (b"__DATA", b"__const") => SectionKind::ReadOnlyData,
+            (b"__DATA", b"__bss") => SectionKind::UninitializedData,
+            (b"__DATA", b"__common") => SectionKind::Common,
+            (b"__DATA", b"__thread_data") => SectionKind::Tls,
+            (b"__DATA", b"__thread_bss") => SectionKind::UninitializedTls,
+            (b"__DATA", b"__thread_vars") => SectionKind::TlsVariables,
+            (b"__DATA_CONST", b"__const") => SectionKind::ReadOnlyData,
+            (b"__DATA_CONST", b"__got") => SectionKind::ReadOnlyData,
+            (b"__DATA_CONST", b"__cfstring") => SectionKind::ReadOnlyData,
+            (b"__DATA_DIRTY", b"__data") => SectionKind::Data,
+            (b"__DATA_DIRTY", b"__bss") => SectionKind::UninitializedData,
+            (b"__DATA_DIRTY", b"__common") => SectionKind::Common,
+            (b"__DWARF", _) => SectionKind::Debug,
+            _ => {
+                let flags = section.flags(endian);
+                if flags & (macho::S_ATTR_PURE_INSTRUCTIONS | macho::S_ATTR_SOME_INSTRUCTIONS) != 0
+                {
+                    SectionKind::Text
+                } else {
+                    SectionKind::Unknown
+                }
+            }
+        };
+        MachOSectionInternal {
+            index,
+            kind,
+            section,
+        }
+    }
+}
+
+/// An iterator over the symbols of a `MachOFile32`.
+pub type MachOSymbolIterator32<'data, 'file, Endian = RunTimeEndian> =
+    MachOSymbolIterator<'data, 'file, macho::MachHeader32<Endian>>;
+/// An iterator over the symbols of a `MachOFile64`.
+pub type MachOSymbolIterator64<'data, 'file, Endian = RunTimeEndian> =
+    MachOSymbolIterator<'data, 'file, macho::MachHeader64<Endian>>;
+
+/// An iterator over the symbols of a `MachOFile`.
+pub struct MachOSymbolIterator<'data, 'file, Mach: MachHeader> {
+    file: &'file MachOFile<'data, Mach>,
+    symbols: SymbolTable<'data, Mach>,
+    index: usize,
+}
+
+impl<'data, 'file, Mach: MachHeader> fmt::Debug for MachOSymbolIterator<'data, 'file, Mach> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MachOSymbolIterator").finish()
+    }
+}
+
+impl<'data, 'file, Mach: MachHeader> Iterator for MachOSymbolIterator<'data, 'file, Mach> {
+    type Item = (SymbolIndex, Symbol<'data>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.index;
+            let nlist = self.symbols.symbols.get(index)?;
+            self.index += 1;
+            if let Some(symbol) = parse_symbol(self.file, nlist, self.symbols.strings) {
+                return Some((SymbolIndex(index), symbol));
             }
         }
     }
 }
 
-/// A segment of a `MachOFile32`.
-pub type MachOSegment32<'data, 'file, Endian = RunTimeEndian> =
-    MachOSegment<'data, 'file, macho::MachHeader32<Endian>>;
-/// A segment of a `MachOFile64`.
-pub type MachOSegment64<'data, 'file, Endian = RunTimeEndian> =
-    MachOSegment<'data, 'file, macho::MachHeader64<Endian>>;
+fn parse_symbol<'data, Mach: MachHeader>(
+    file: &MachOFile<'data, Mach>,
+    nlist: &Mach::Nlist,
+    strings: StringTable<'data>,
+) -> Option<Symbol<'data>> {
+    let endian = file.endian;
+    let name = strings
+        .get(nlist.n_strx(endian))
+        .ok()
+        .and_then(|s| str::from_utf8(s).ok());
+    let n_type = nlist.n_type();
+    let n_desc = nlist.n_desc(endian);
+    if n_type & macho::N_STAB != 0 {
+        return None;
+    }
+    let section = match n_type & macho::N_TYPE {
+        macho::N_UNDF => SymbolSection::Undefined,
+        macho::N_ABS => SymbolSection::Absolute,
+        macho::N_SECT => {
+            let n_sect = nlist.n_sect();
+            if n_sect != 0 {
+                SymbolSection::Section(SectionIndex(n_sect as usize))
+            } else {
+                SymbolSection::Unknown
+            }
+        }
+        // A prebound undefined symbol.
+        macho::N_PBUD => SymbolSection::Undefined,
+        // An alias for another symbol, which may be defined in a different file.
+        // Use `MachOFile::indirect_name` to get the name of the target symbol.
+        macho::N_INDR => SymbolSection::Unknown,
+        _ => SymbolSection::Unknown,
+    };
+    // For `N_INDR` symbols, `n_value` is the string table offset of the target name.
+    let address = if n_type & macho::N_TYPE == macho::N_INDR {
+        0
+    } else {
+        nlist.n_value(endian).into()
+    };
+    let kind = section
+        .index()
+        .and_then(|index| file.section_internal(index).ok())
+        .map(|section| match section.kind {
+            SectionKind::Text => SymbolKind::Text,
+            SectionKind::Data
+            | SectionKind::ReadOnlyData
+            | SectionKind::ReadOnlyString
+            | SectionKind::UninitializedData
+            | SectionKind::Common => SymbolKind::Data,
+            SectionKind::Tls | SectionKind::UninitializedTls | SectionKind::TlsVariables => {
+                SymbolKind::Tls
+            }
+            _ => SymbolKind::Unknown,
+        })
+        .unwrap_or(SymbolKind::Unknown);
+    let weak = n_desc & (macho::N_WEAK_REF | macho::N_WEAK_DEF) != 0;
+    let scope = if section == SymbolSection::Undefined {
+        SymbolScope::Unknown
+    } else if n_type & macho::N_EXT == 0 {
+        SymbolScope::Compilation
+    } else if n_type & macho::N_PEXT != 0 {
+        SymbolScope::Linkage
+    } else {
+        SymbolScope::Dynamic
+    };
+    let flags = SymbolFlags::MachO { n_desc };
+    Some(Symbol {
+        name,
+        address,
+        // Only calculated for symbol maps
+        size: 0,
+        kind,
+        section,
+        weak,
+        scope,
+        flags,
+    })
+}
 
-/// A segment of a `MachOFile`.
-#[derive(Debug)]
-pub struct MachOSegment<'data, 'file, Mach>
+/// An iterator over the relocations in a `MachOSection32`.
+pub type MachORelocationIterator32<'data, 'file, Endian = RunTimeEndian> =
+    MachORelocationIterator<'data, 'file, macho::MachHeader32<Endian>>;
+/// An iterator over the relocations in a `MachOSection64`.
+pub type MachORelocationIterator64<'data, 'file, Endian = RunTimeEndian> =
+    MachORelocationIterator<'data, 'file, macho::MachHeader64<Endian>>;
+
+/// An iterator over the relocations in a `MachOSection`.
+///
+/// The offset of each relocation is the `r_address` field, which is relative to
+/// the start of the section. Use `MachOSection::relocations_with_addresses` or
+/// `MachOSection::relocations_with_file_offsets` for other bases.
+pub struct MachORelocationIterator<'data, 'file, Mach>
 where
     'data: 'file,
     Mach: MachHeader,
 {
     file: &'file MachOFile<'data, Mach>,
-    segment: &'data Mach::Segment,
+    relocations: slice::Iter<'data, macho::Relocation<Mach::Endian>>,
 }
 
-impl<'data, 'file, Mach: MachHeader> MachOSegment<'data, 'file, Mach> {
-    fn bytes(&self) -> Result<Bytes<'data>> {
-        self.segment
-            .data(self.file.endian, self.file.data)
-            .read_error("Invalid Mach-O segment size or offset")
+impl<'data, 'file, Mach: MachHeader> Iterator for MachORelocationIterator<'data, 'file, Mach> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let reloc = self.relocations.next()?;
+            let endian = self.file.endian;
+            let cputype = self.file.header.cputype(endian);
+            if reloc.r_scattered(endian, cputype) {
+                // FIXME: handle scattered relocations
+                // We need to add `RelocationTarget::Address` for this.
+                continue;
+            }
+            let reloc = reloc.info(self.file.endian);
+            let mut encoding = RelocationEncoding::Generic;
+            let kind = match cputype {
+                macho::CPU_TYPE_ARM => match (reloc.r_type, reloc.r_pcrel) {
+                    (macho::ARM_RELOC_VANILLA, false) => RelocationKind::Absolute,
+                    _ => RelocationKind::MachO {
+                        value: reloc.r_type,
+                        relative: reloc.r_pcrel,
+                    },
+                },
+                macho::CPU_TYPE_ARM64 => match (reloc.r_type, reloc.r_pcrel) {
+                    (macho::ARM64_RELOC_UNSIGNED, false) => RelocationKind::Absolute,
+                    (macho::ARM64_RELOC_TLVP_LOAD_PAGE21, true) => {
+                        encoding = RelocationEncoding::AArch64Page21;
+                        RelocationKind::TlvRelative
+                    }
+                    (macho::ARM64_RELOC_TLVP_LOAD_PAGEOFF12, false) => {
+                        encoding = RelocationEncoding::AArch64PageOffset12;
+                        RelocationKind::Tlv
+                    }
+                    _ => RelocationKind::MachO {
+                        value: reloc.r_type,
+                        relative: reloc.r_pcrel,
+                    },
+                },
+                macho::CPU_TYPE_X86 => match (reloc.r_type, reloc.r_pcrel) {
+                    (macho::GENERIC_RELOC_VANILLA, false) => RelocationKind::Absolute,
+                    (macho::GENERIC_RELOC_TLV, false) => RelocationKind::Tlv,
+                    _ => RelocationKind::MachO {
+                        value: reloc.r_type,
+                        relative: reloc.r_pcrel,
+                    },
+                },
+                macho::CPU_TYPE_X86_64 => match (reloc.r_type, reloc.r_pcrel) {
+                    (macho::X86_64_RELOC_UNSIGNED, false) => RelocationKind::Absolute,
+                    (macho::X86_64_RELOC_SIGNED, true) => {
+                        encoding = RelocationEncoding::X86RipRelative;
+                        RelocationKind::Relative
+                    }
+                    (macho::X86_64_RELOC_BRANCH, true) => {
+                        encoding = RelocationEncoding::X86Branch;
+                        RelocationKind::Relative
+                    }
+                    // Any other instruction that uses the GOT entry. This can't be relaxed.
+                    (macho::X86_64_RELOC_GOT, true) => {
+                        encoding = RelocationEncoding::X86RipRelative;
+                        RelocationKind::GotRelative
+                    }
+                    // A `movq` that can be relaxed to a `leaq` if the symbol is local.
+                    (macho::X86_64_RELOC_GOT_LOAD, true) => {
+                        encoding = RelocationEncoding::X86RipRelativeMovq;
+                        RelocationKind::GotRelative
+                    }
+                    (macho::X86_64_RELOC_TLV, true) => {
+                        encoding = RelocationEncoding::X86RipRelativeMovq;
+                        RelocationKind::TlvRelative
+                    }
+                    _ => RelocationKind::MachO {
+                        value: reloc.r_type,
+                        relative: reloc.r_pcrel,
+                    },
+                },
+                macho::CPU_TYPE_POWERPC | macho::CPU_TYPE_POWERPC64 => {
+                    match (reloc.r_type, reloc.r_pcrel) {
+                        (macho::PPC_RELOC_VANILLA, false) => RelocationKind::Absolute,
+                        _ => RelocationKind::MachO {
+                            value: reloc.r_type,
+                            relative: reloc.r_pcrel,
+                        },
+                    }
+                }
+                _ => RelocationKind::MachO {
+                    value: reloc.r_type,
+                    relative: reloc.r_pcrel,
+                },
+            };
+            let size = 8 << reloc.r_length;
+            let target = if reloc.r_extern {
+                RelocationTarget::Symbol(SymbolIndex(reloc.r_symbolnum as usize))
+            } else {
+                RelocationTarget::Section(SectionIndex(reloc.r_symbolnum as usize))
+            };
+            let addend = if reloc.r_pcrel { -4 } else { 0 };
+            return Some((
+                reloc.r_address as u64,
+                Relocation {
+                    kind,
+                    encoding,
+                    size,
+                    target,
+                    addend,
+                    implicit_addend: true,
+                },
+            ));
+        }
     }
 }
 
-impl<'data, 'file, Mach: MachHeader> read::private::Sealed for MachOSegment<'data, 'file, Mach> {}
+impl<'data, 'file, Mach: MachHeader> fmt::Debug for MachORelocationIterator<'data, 'file, Mach> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MachORelocationIterator").finish()
+    }
+}
 
-impl<'data, 'file, Mach: MachHeader> ObjectSegment<'data> for MachOSegment<'data, 'file, Mach> {
+/// A symbol binding decoded from the bind information of a `LC_DYLD_INFO` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOBind<'data> {
+    segment_index: u8,
+    segment_offset: u64,
+    library_ordinal: i32,
+    name: &'data [u8],
+    flags: u8,
+    bind_type: u8,
+    addend: i64,
+}
+
+impl<'data> MachOBind<'data> {
+    /// The index of the segment containing the place to bind.
     #[inline]
-    fn address(&self) -> u64 {
-        self.segment.vmaddr(self.file.endian).into()
+    pub fn segment_index(&self) -> u8 {
+        self.segment_index
     }
 
+    /// The offset of the place to bind within its segment.
     #[inline]
-    fn size(&self) -> u64 {
-        self.segment.vmsize(self.file.endian).into()
+    pub fn segment_offset(&self) -> u64 {
+        self.segment_offset
     }
 
+    /// The ordinal of the library that defines the symbol.
+    ///
+    /// Values less than 1 are one of the `BIND_SPECIAL_DYLIB_*` constants.
     #[inline]
-    fn align(&self) -> u64 {
-        // Page size.
-        0x1000
+    pub fn library_ordinal(&self) -> i32 {
+        self.library_ordinal
+    }
+
+    /// The name of the symbol.
+    pub fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 Mach-O bind symbol name")
     }
 
+    /// The raw bytes of the symbol name.
     #[inline]
-    fn file_range(&self) -> (u64, u64) {
-        self.segment.file_range(self.file.endian)
+    pub fn name_bytes(&self) -> &'data [u8] {
+        self.name
     }
 
-    fn data(&self) -> Result<&'data [u8]> {
-        Ok(self.bytes()?.0)
+    /// The `BIND_SYMBOL_FLAGS_*` flags of the symbol.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.flags
     }
 
-    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
-        Ok(read::data_range(
-            self.bytes()?,
-            self.address(),
-            address,
-            size,
-        ))
+    /// The `BIND_TYPE_*` type of the binding.
+    #[inline]
+    pub fn bind_type(&self) -> u8 {
+        self.bind_type
     }
 
+    /// The addend to add to the symbol address.
     #[inline]
-    fn name(&self) -> Result<Option<&str>> {
-        Ok(Some(
-            str::from_utf8(self.segment.name())
-                .ok()
-                .read_error("Non UTF-8 Mach-O segment name")?,
-        ))
+    pub fn addend(&self) -> i64 {
+        self.addend
     }
 }
 
-/// An iterator over the sections of a `MachOFile32`.
-pub type MachOSectionIterator32<'data, 'file, Endian = RunTimeEndian> =
-    MachOSectionIterator<'data, 'file, macho::MachHeader32<Endian>>;
-/// An iterator over the sections of a `MachOFile64`.
-pub type MachOSectionIterator64<'data, 'file, Endian = RunTimeEndian> =
-    MachOSectionIterator<'data, 'file, macho::MachHeader64<Endian>>;
+/// A symbol imported by a Mach-O file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOImport<'data> {
+    name: &'data [u8],
+    library: Option<&'data str>,
+}
 
-/// An iterator over the sections of a `MachOFile`.
-pub struct MachOSectionIterator<'data, 'file, Mach>
-where
-    'data: 'file,
-    Mach: MachHeader,
-{
-    file: &'file MachOFile<'data, Mach>,
-    iter: slice::Iter<'file, MachOSectionInternal<'data, Mach>>,
+impl<'data> MachOImport<'data> {
+    /// The name of the symbol.
+    pub fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 Mach-O import name")
+    }
+
+    /// The raw bytes of the symbol name.
+    #[inline]
+    pub fn name_bytes(&self) -> &'data [u8] {
+        self.name
+    }
+
+    /// The install name of the library that provides the symbol.
+    ///
+    /// This is `None` if the library is not specified, such as for a flat namespace
+    /// lookup.
+    #[inline]
+    pub fn library(&self) -> Option<&'data str> {
+        self.library
+    }
+}
+
+/// An entry in the imports table of a `LC_DYLD_CHAINED_FIXUPS` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOChainedImport<'data> {
+    name: &'data [u8],
+    library_ordinal: i32,
+    weak_import: bool,
+    addend: i64,
 }
 
-impl<'data, 'file, Mach: MachHeader> fmt::Debug for MachOSectionIterator<'data, 'file, Mach> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // It's painful to do much better than this
-        f.debug_struct("MachOSectionIterator").finish()
+impl<'data> MachOChainedImport<'data> {
+    /// The name of the symbol.
+    pub fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 Mach-O chained import name")
     }
-}
 
-impl<'data, 'file, Mach: MachHeader> Iterator for MachOSectionIterator<'data, 'file, Mach> {
-    type Item = MachOSection<'data, 'file, Mach>;
+    /// The raw bytes of the symbol name.
+    #[inline]
+    pub fn name_bytes(&self) -> &'data [u8] {
+        self.name
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|&internal| MachOSection {
-            file: self.file,
-            internal,
-        })
+    /// The ordinal of the library that defines the symbol.
+    ///
+    /// Values less than 1 are one of the `BIND_SPECIAL_DYLIB_*` constants.
+    #[inline]
+    pub fn library_ordinal(&self) -> i32 {
+        self.library_ordinal
     }
-}
 
-/// A section of a `MachOFile32`.
-pub type MachOSection32<'data, 'file, Endian = RunTimeEndian> =
-    MachOSection<'data, 'file, macho::MachHeader32<Endian>>;
-/// A section of a `MachOFile64`.
-pub type MachOSection64<'data, 'file, Endian = RunTimeEndian> =
-    MachOSection<'data, 'file, macho::MachHeader64<Endian>>;
+    /// Return true if the symbol is weakly imported.
+    #[inline]
+    pub fn is_weak_import(&self) -> bool {
+        self.weak_import
+    }
 
-/// A section of a `MachOFile`.
-#[derive(Debug)]
-pub struct MachOSection<'data, 'file, Mach>
-where
-    'data: 'file,
-    Mach: MachHeader,
-{
-    file: &'file MachOFile<'data, Mach>,
-    internal: MachOSectionInternal<'data, Mach>,
+    /// The addend to add to the symbol address.
+    ///
+    /// This is always 0 for the `DYLD_CHAINED_IMPORT` format.
+    #[inline]
+    pub fn addend(&self) -> i64 {
+        self.addend
+    }
 }
 
-impl<'data, 'file, Mach: MachHeader> MachOSection<'data, 'file, Mach> {
-    fn bytes(&self) -> Result<Bytes<'data>> {
-        self.internal
-            .section
-            .data(self.file.endian, self.file.data)
-            .read_error("Invalid Mach-O section size or offset")
+/// The debug map of a linked `MachOFile`.
+///
+/// Returned by `MachOFile::debug_map`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachODebugMap<'data> {
+    objects: Vec<MachODebugMapObject<'data>>,
+}
+
+impl<'data> MachODebugMap<'data> {
+    /// The object files that contributed to the image, in symbol table order.
+    #[inline]
+    pub fn objects(&self) -> &[MachODebugMapObject<'data>] {
+        &self.objects
     }
 }
 
-impl<'data, 'file, Mach: MachHeader> read::private::Sealed for MachOSection<'data, 'file, Mach> {}
+/// An object file in a `MachODebugMap`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachODebugMapObject<'data> {
+    path: &'data [u8],
+    timestamp: u64,
+    symbols: Vec<MachODebugMapSymbol<'data>>,
+}
 
-impl<'data, 'file, Mach: MachHeader> ObjectSection<'data> for MachOSection<'data, 'file, Mach> {
-    type RelocationIterator = MachORelocationIterator<'data, 'file, Mach>;
+impl<'data> MachODebugMapObject<'data> {
+    /// The path of the object file.
+    ///
+    /// For a member of a static library, this is of the form `libfoo.a(foo.o)`.
+    pub fn path(&self) -> Result<&'data str> {
+        str::from_utf8(self.path)
+            .ok()
+            .read_error("Non UTF-8 Mach-O debug map object path")
+    }
 
+    /// The raw bytes of the path of the object file.
     #[inline]
-    fn index(&self) -> SectionIndex {
-        self.internal.index
+    pub fn path_bytes(&self) -> &'data [u8] {
+        self.path
     }
 
+    /// The modification time of the object file, in seconds since the Unix epoch.
+    ///
+    /// This can be used to check that the object file has not changed since linking.
     #[inline]
-    fn address(&self) -> u64 {
-        self.internal.section.addr(self.file.endian).into()
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
     }
 
+    /// The functions and variables that the object file defined, in symbol table order.
     #[inline]
-    fn size(&self) -> u64 {
-        self.internal.section.size(self.file.endian).into()
+    pub fn symbols(&self) -> &[MachODebugMapSymbol<'data>] {
+        &self.symbols
+    }
+}
+
+/// A function or variable in a `MachODebugMapObject`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachODebugMapSymbol<'data> {
+    name: &'data [u8],
+    address: u64,
+    size: u64,
+}
+
+impl<'data> MachODebugMapSymbol<'data> {
+    /// The name of the symbol.
+    pub fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 Mach-O debug map symbol name")
     }
 
+    /// The raw bytes of the symbol name.
     #[inline]
-    fn align(&self) -> u64 {
-        1 << self.internal.section.align(self.file.endian)
+    pub fn name_bytes(&self) -> &'data [u8] {
+        self.name
     }
 
+    /// The address of the symbol in the linked image.
     #[inline]
-    fn file_range(&self) -> Option<(u64, u64)> {
-        self.internal.section.file_range(self.file.endian)
+    pub fn address(&self) -> u64 {
+        self.address
     }
 
+    /// The size of the function.
+    ///
+    /// This is 0 for variables, and for functions with no size entry.
     #[inline]
-    fn data(&self) -> Result<&'data [u8]> {
-        Ok(self.bytes()?.0)
+    pub fn size(&self) -> u64 {
+        self.size
     }
+}
 
-    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
-        Ok(read::data_range(
-            self.bytes()?,
-            self.address(),
-            address,
-            size,
-        ))
+/// An exported symbol decoded from the export trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachOExport<'data> {
+    flags: u32,
+    address: u64,
+    library_ordinal: u64,
+    import_name: &'data [u8],
+    resolver: Option<u64>,
+}
+
+impl<'data> MachOExport<'data> {
+    /// Parse the terminal information of an export trie node.
+    fn parse(mut data: Bytes<'data>) -> Result<Self> {
+        let flags = u32::try_from(data.read_uleb128().read_error("Invalid ULEB128 value")?)
+            .ok()
+            .read_error("Invalid Mach-O export flags")?;
+        let mut export = MachOExport {
+            flags,
+            address: 0,
+            library_ordinal: 0,
+            import_name: &[],
+            resolver: None,
+        };
+        if flags & macho::EXPORT_SYMBOL_FLAGS_REEXPORT != 0 {
+            export.library_ordinal = data.read_uleb128().read_error("Invalid ULEB128 value")?;
+            export.import_name = data
+                .read_string()
+                .read_error("Invalid Mach-O export import name")?;
+        } else {
+            export.address = data.read_uleb128().read_error("Invalid ULEB128 value")?;
+            if flags & macho::EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER != 0 {
+                export.resolver = Some(data.read_uleb128().read_error("Invalid ULEB128 value")?);
+            }
+        }
+        Ok(export)
     }
 
-    #[cfg(feature = "compression")]
+    /// The `EXPORT_SYMBOL_FLAGS_*` flags of the export.
     #[inline]
-    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
-        Ok(Cow::from(self.data()?))
+    pub fn flags(&self) -> u32 {
+        self.flags
     }
 
+    /// The `EXPORT_SYMBOL_FLAGS_KIND_*` kind of the export.
     #[inline]
-    fn name(&self) -> Result<&str> {
-        str::from_utf8(self.internal.section.name())
-            .ok()
-            .read_error("Non UTF-8 Mach-O section name")
+    pub fn kind(&self) -> u32 {
+        self.flags & macho::EXPORT_SYMBOL_FLAGS_KIND_MASK
     }
 
+    /// Return true if the symbol is re-exported from another library.
     #[inline]
-    fn segment_name(&self) -> Result<Option<&str>> {
-        Ok(Some(
-            str::from_utf8(self.internal.section.segment_name())
-                .ok()
-                .read_error("Non UTF-8 Mach-O segment name")?,
-        ))
+    pub fn is_reexport(&self) -> bool {
+        self.flags & macho::EXPORT_SYMBOL_FLAGS_REEXPORT != 0
     }
 
-    fn kind(&self) -> SectionKind {
-        self.internal.kind
+    /// The address of the symbol, relative to the start of the image.
+    ///
+    /// For a stub and resolver export, this is the address of the stub.
+    /// This is zero for re-exports.
+    #[inline]
+    pub fn address(&self) -> u64 {
+        self.address
     }
 
-    fn relocations(&self) -> MachORelocationIterator<'data, 'file, Mach> {
-        MachORelocationIterator {
-            file: self.file,
-            relocations: self
-                .internal
-                .section
-                .relocations(self.file.endian, self.file.data)
-                .unwrap_or(&[])
-                .iter(),
-        }
+    /// The ordinal of the library that the symbol is re-exported from.
+    ///
+    /// This is zero if the symbol is not a re-export.
+    #[inline]
+    pub fn library_ordinal(&self) -> u64 {
+        self.library_ordinal
     }
 
-    fn flags(&self) -> SectionFlags {
-        SectionFlags::MachO {
-            flags: self.internal.section.flags(self.file.endian),
-        }
+    /// The name of the symbol in the library that it is re-exported from.
+    ///
+    /// This is empty if the name is the same as the exported name, or if the
+    /// symbol is not a re-export.
+    #[inline]
+    pub fn import_name(&self) -> &'data [u8] {
+        self.import_name
+    }
+
+    /// The address of the resolver function, relative to the start of the image.
+    ///
+    /// This is only present for `EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER` exports.
+    #[inline]
+    pub fn resolver(&self) -> Option<u64> {
+        self.resolver
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct MachOSectionInternal<'data, Mach: MachHeader> {
-    index: SectionIndex,
-    kind: SectionKind,
-    section: &'data Mach::Section,
+/// An exported symbol returned by `MachOFile::all_exports`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachOExportedSymbol<'data> {
+    name: Cow<'data, [u8]>,
+    export: MachOExport<'data>,
 }
 
-impl<'data, Mach: MachHeader> MachOSectionInternal<'data, Mach> {
-    fn parse(index: SectionIndex, section: &'data Mach::Section) -> Self {
-        // TODO: we don't validate flags, should we?
-        let kind = match (section.segment_name(), section.name()) {
-            (b"__TEXT", b"__text") => SectionKind::Text,
-            (b"__TEXT", b"__const") => SectionKind::ReadOnlyData,
-            (b"__TEXT", b"__cstring") => SectionKind::ReadOnlyString,
-            (b"__TEXT", b"__literal4") => SectionKind::ReadOnlyData,
-            (b"__TEXT", b"__literal8") => SectionKind::ReadOnlyData,
-            (b"__TEXT", b"__literal16") => SectionKind::ReadOnlyData,
-            (b"__TEXT", b"__eh_frame") => SectionKind::ReadOnlyData,
-            (b"__TEXT", b"__gcc_except_tab") => SectionKind::ReadOnlyData,
-            (b"__DATA", b"__data") => SectionKind::Data,
-            (b"__DATA", b"__const") => SectionKind::ReadOnlyData,
-            (b"__DATA", b"__bss") => SectionKind::UninitializedData,
-            (b"__DATA", b"__common") => SectionKind::Common,
-            (b"__DATA", b"__thread_data") => SectionKind::Tls,
-            (b"__DATA", b"__thread_bss") => SectionKind::UninitializedTls,
-            (b"__DATA", b"__thread_vars") => SectionKind::TlsVariables,
-            (b"__DWARF", _) => SectionKind::Debug,
-            _ => SectionKind::Unknown,
-        };
-        MachOSectionInternal {
-            index,
-            kind,
-            section,
-        }
+impl<'data> MachOExportedSymbol<'data> {
+    /// The name of the symbol.
+    pub fn name(&self) -> Result<&str> {
+        str::from_utf8(&self.name)
+            .ok()
+            .read_error("Non UTF-8 Mach-O export name")
     }
-}
 
-/// An iterator over the symbols of a `MachOFile32`.
-pub type MachOSymbolIterator32<'data, 'file, Endian = RunTimeEndian> =
-    MachOSymbolIterator<'data, 'file, macho::MachHeader32<Endian>>;
-/// An iterator over the symbols of a `MachOFile64`.
-pub type MachOSymbolIterator64<'data, 'file, Endian = RunTimeEndian> =
-    MachOSymbolIterator<'data, 'file, macho::MachHeader64<Endian>>;
+    /// The raw bytes of the symbol name.
+    #[inline]
+    pub fn name_bytes(&self) -> &[u8] {
+        &self.name
+    }
 
-/// An iterator over the symbols of a `MachOFile`.
-pub struct MachOSymbolIterator<'data, 'file, Mach: MachHeader> {
-    file: &'file MachOFile<'data, Mach>,
-    symbols: SymbolTable<'data, Mach>,
-    index: usize,
+    /// The export information for the symbol.
+    #[inline]
+    pub fn export(&self) -> MachOExport<'data> {
+        self.export
+    }
 }
 
-impl<'data, 'file, Mach: MachHeader> fmt::Debug for MachOSymbolIterator<'data, 'file, Mach> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("MachOSymbolIterator").finish()
-    }
+/// An iterator over the bindings in a bind opcode stream.
+///
+/// Iteration stops at `BIND_OPCODE_DONE` or at the end of the data.
+#[derive(Debug)]
+struct MachOBindIterator<'data, 'a> {
+    data: Bytes<'data>,
+    pointer_size: u64,
+    /// The size in memory of each segment, indexed by the bind segment index.
+    segment_sizes: &'a [u64],
+    bind: MachOBind<'data>,
+    /// The remaining count and skip for `BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB`.
+    repeat: u64,
+    skip: u64,
 }
 
-impl<'data, 'file, Mach: MachHeader> Iterator for MachOSymbolIterator<'data, 'file, Mach> {
-    type Item = (SymbolIndex, Symbol<'data>);
+impl<'data, 'a> MachOBindIterator<'data, 'a> {
+    fn new(data: Bytes<'data>, pointer_size: u64, segment_sizes: &'a [u64]) -> Self {
+        MachOBindIterator {
+            data,
+            pointer_size,
+            segment_sizes,
+            bind: MachOBind {
+                segment_index: 0,
+                segment_offset: 0,
+                library_ordinal: 0,
+                name: &[],
+                flags: 0,
+                // Lazy bindings don't set the type, and are always pointers.
+                bind_type: macho::BIND_TYPE_POINTER,
+                addend: 0,
+            },
+            repeat: 0,
+            skip: 0,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Return the current binding and advance the offset past it.
+    fn bind(&mut self, advance: u64) -> MachOBind<'data> {
+        let bind = self.bind;
+        self.bind.segment_offset = self
+            .bind
+            .segment_offset
+            .wrapping_add(self.pointer_size)
+            .wrapping_add(advance);
+        bind
+    }
+
+    fn next(&mut self) -> Result<Option<MachOBind<'data>>> {
+        if self.repeat != 0 {
+            self.repeat -= 1;
+            return Ok(Some(self.bind(self.skip)));
+        }
         loop {
-            let index = self.index;
-            let nlist = self.symbols.symbols.get(index)?;
-            self.index += 1;
-            if let Some(symbol) = parse_symbol(self.file, nlist, self.symbols.strings) {
-                return Some((SymbolIndex(index), symbol));
+            match self.step()? {
+                BindStep::Done => return Ok(None),
+                BindStep::Bind(bind) => return Ok(Some(bind)),
+                BindStep::Symbol | BindStep::Other => {}
             }
         }
     }
-}
 
-fn parse_symbol<'data, Mach: MachHeader>(
-    file: &MachOFile<'data, Mach>,
-    nlist: &Mach::Nlist,
-    strings: StringTable<'data>,
-) -> Option<Symbol<'data>> {
-    let endian = file.endian;
-    let name = strings
-        .get(nlist.n_strx(endian))
-        .ok()
-        .and_then(|s| str::from_utf8(s).ok());
-    let n_type = nlist.n_type();
-    let n_desc = nlist.n_desc(endian);
-    if n_type & macho::N_STAB != 0 {
-        return None;
-    }
-    let section = match n_type & macho::N_TYPE {
-        macho::N_UNDF => SymbolSection::Undefined,
-        macho::N_ABS => SymbolSection::Absolute,
-        macho::N_SECT => {
-            let n_sect = nlist.n_sect();
-            if n_sect != 0 {
-                SymbolSection::Section(SectionIndex(n_sect as usize))
-            } else {
-                SymbolSection::Unknown
+    /// Return the next symbol name and flags set by
+    /// `BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM`, skipping any bindings.
+    fn next_symbol(&mut self) -> Result<Option<(&'data [u8], u8)>> {
+        self.repeat = 0;
+        loop {
+            match self.step()? {
+                BindStep::Done => return Ok(None),
+                BindStep::Symbol => return Ok(Some((self.bind.name, self.bind.flags))),
+                BindStep::Bind(_) | BindStep::Other => {}
             }
         }
-        _ => SymbolSection::Unknown,
-    };
-    let kind = section
-        .index()
-        .and_then(|index| file.section_internal(index).ok())
-        .map(|section| match section.kind {
-            SectionKind::Text => SymbolKind::Text,
-            SectionKind::Data
-            | SectionKind::ReadOnlyData
-            | SectionKind::ReadOnlyString
-            | SectionKind::UninitializedData
-            | SectionKind::Common => SymbolKind::Data,
-            SectionKind::Tls | SectionKind::UninitializedTls | SectionKind::TlsVariables => {
-                SymbolKind::Tls
+    }
+
+    /// Decode one opcode.
+    fn step(&mut self) -> Result<BindStep<'data>> {
+        let byte = match self.data.read::<u8>() {
+            Ok(byte) => *byte,
+            Err(()) => return Ok(BindStep::Done),
+        };
+        let immediate = byte & macho::BIND_IMMEDIATE_MASK;
+        match byte & macho::BIND_OPCODE_MASK {
+            macho::BIND_OPCODE_DONE => return Ok(BindStep::Done),
+            macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => {
+                self.bind.library_ordinal = i32::from(immediate);
             }
-            _ => SymbolKind::Unknown,
-        })
-        .unwrap_or(SymbolKind::Unknown);
-    let weak = n_desc & (macho::N_WEAK_REF | macho::N_WEAK_DEF) != 0;
-    let scope = if section == SymbolSection::Undefined {
-        SymbolScope::Unknown
-    } else if n_type & macho::N_EXT == 0 {
-        SymbolScope::Compilation
-    } else if n_type & macho::N_PEXT != 0 {
-        SymbolScope::Linkage
-    } else {
-        SymbolScope::Dynamic
-    };
-    let flags = SymbolFlags::MachO { n_desc };
-    Some(Symbol {
-        name,
-        address: nlist.n_value(endian).into(),
-        // Only calculated for symbol maps
-        size: 0,
-        kind,
-        section,
-        weak,
-        scope,
-        flags,
-    })
+            macho::BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => {
+                let ordinal = self
+                    .data
+                    .read_uleb128()
+                    .read_error("Invalid ULEB128 value")?;
+                self.bind.library_ordinal = i32::try_from(ordinal)
+                    .ok()
+                    .read_error("Invalid Mach-O bind library ordinal")?;
+            }
+            macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => {
+                // The immediate is a sign extended negative number.
+                self.bind.library_ordinal = if immediate == 0 {
+                    0
+                } else {
+                    i32::from((macho::BIND_OPCODE_MASK | immediate) as i8)
+                };
+            }
+            macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                self.bind.flags = immediate;
+                self.bind.name = self
+                    .data
+                    .read_string()
+                    .read_error("Invalid Mach-O bind symbol name")?;
+                return Ok(BindStep::Symbol);
+            }
+            macho::BIND_OPCODE_SET_TYPE_IMM => {
+                self.bind.bind_type = immediate;
+            }
+            macho::BIND_OPCODE_SET_ADDEND_SLEB => {
+                self.bind.addend = self
+                    .data
+                    .read_sleb128()
+                    .read_error("Invalid SLEB128 value")?;
+            }
+            macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                self.bind.segment_index = immediate;
+                self.bind.segment_offset = self
+                    .data
+                    .read_uleb128()
+                    .read_error("Invalid ULEB128 value")?;
+            }
+            macho::BIND_OPCODE_ADD_ADDR_ULEB => {
+                let offset = self
+                    .data
+                    .read_uleb128()
+                    .read_error("Invalid ULEB128 value")?;
+                self.bind.segment_offset = self.bind.segment_offset.wrapping_add(offset);
+            }
+            macho::BIND_OPCODE_DO_BIND => return Ok(BindStep::Bind(self.bind(0))),
+            macho::BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB => {
+                let offset = self
+                    .data
+                    .read_uleb128()
+                    .read_error("Invalid ULEB128 value")?;
+                return Ok(BindStep::Bind(self.bind(offset)));
+            }
+            macho::BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED => {
+                let offset = u64::from(immediate) * self.pointer_size;
+                return Ok(BindStep::Bind(self.bind(offset)));
+            }
+            macho::BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = self
+                    .data
+                    .read_uleb128()
+                    .read_error("Invalid ULEB128 value")?;
+                self.skip = self
+                    .data
+                    .read_uleb128()
+                    .read_error("Invalid ULEB128 value")?;
+                if count != 0 {
+                    // Limit the count to the bindings that fit in the segment, so that
+                    // a corrupt count can't make iteration take an unbounded time.
+                    let size = self
+                        .segment_sizes
+                        .get(usize::from(self.bind.segment_index))
+                        .cloned()
+                        .read_error("Invalid Mach-O bind segment index")?;
+                    self.skip
+                        .checked_add(self.pointer_size)
+                        .and_then(|stride| (count - 1).checked_mul(stride))
+                        .and_then(|offset| offset.checked_add(self.bind.segment_offset))
+                        .and_then(|offset| offset.checked_add(self.pointer_size))
+                        .filter(|end| *end <= size)
+                        .read_error("Invalid Mach-O bind repeat count")?;
+                    self.repeat = count - 1;
+                    return Ok(BindStep::Bind(self.bind(self.skip)));
+                }
+            }
+            _ => return Err(Error("Unsupported Mach-O bind opcode")),
+        }
+        Ok(BindStep::Other)
+    }
 }
 
-/// An iterator over the relocations in a `MachOSection32`.
-pub type MachORelocationIterator32<'data, 'file, Endian = RunTimeEndian> =
-    MachORelocationIterator<'data, 'file, macho::MachHeader32<Endian>>;
-/// An iterator over the relocations in a `MachOSection64`.
-pub type MachORelocationIterator64<'data, 'file, Endian = RunTimeEndian> =
-    MachORelocationIterator<'data, 'file, macho::MachHeader64<Endian>>;
-
-/// An iterator over the relocations in a `MachOSection`.
-pub struct MachORelocationIterator<'data, 'file, Mach>
-where
-    'data: 'file,
-    Mach: MachHeader,
-{
-    file: &'file MachOFile<'data, Mach>,
-    relocations: slice::Iter<'data, macho::Relocation<Mach::Endian>>,
+/// The result of decoding one bind opcode.
+#[derive(Debug)]
+enum BindStep<'data> {
+    /// The end of the opcodes.
+    Done,
+    /// A symbol name was set.
+    Symbol,
+    /// A binding was made.
+    Bind(MachOBind<'data>),
+    /// Some other state was set.
+    Other,
 }
 
-impl<'data, 'file, Mach: MachHeader> Iterator for MachORelocationIterator<'data, 'file, Mach> {
-    type Item = (u64, Relocation);
+/// Read the header of the data of an `LC_DYLD_CHAINED_FIXUPS` command.
+fn chained_fixups_header<E: Endian>(data: Bytes<'_>) -> Result<&macho::DyldChainedFixupsHeader<E>> {
+    data.read_at::<macho::DyldChainedFixupsHeader<E>>(0)
+        .read_error("Invalid Mach-O chained fixups header size or alignment")
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            let reloc = self.relocations.next()?;
-            let endian = self.file.endian;
-            let cputype = self.file.header.cputype(endian);
-            if reloc.r_scattered(endian, cputype) {
-                // FIXME: handle scattered relocations
-                // We need to add `RelocationTarget::Address` for this.
-                continue;
+/// Return the target address of a chained rebase pointer.
+///
+/// Returns an error if the pointer is a bind, or for unsupported pointer formats.
+fn chained_rebase_target(pointer_format: u16, value: u64, image_base: u64) -> Result<u64> {
+    match pointer_format {
+        macho::DYLD_CHAINED_PTR_64 | macho::DYLD_CHAINED_PTR_64_OFFSET => {
+            if value & (1 << 63) != 0 {
+                return Err(Error("Unsupported Mach-O chained bind pointer"));
             }
-            let reloc = reloc.info(self.file.endian);
-            let mut encoding = RelocationEncoding::Generic;
-            let kind = match cputype {
-                macho::CPU_TYPE_ARM => match (reloc.r_type, reloc.r_pcrel) {
-                    (macho::ARM_RELOC_VANILLA, false) => RelocationKind::Absolute,
-                    _ => RelocationKind::MachO {
-                        value: reloc.r_type,
-                        relative: reloc.r_pcrel,
-                    },
-                },
-                macho::CPU_TYPE_ARM64 => match (reloc.r_type, reloc.r_pcrel) {
-                    (macho::ARM64_RELOC_UNSIGNED, false) => RelocationKind::Absolute,
-                    _ => RelocationKind::MachO {
-                        value: reloc.r_type,
-                        relative: reloc.r_pcrel,
-                    },
-                },
-                macho::CPU_TYPE_X86 => match (reloc.r_type, reloc.r_pcrel) {
-                    (macho::GENERIC_RELOC_VANILLA, false) => RelocationKind::Absolute,
-                    _ => RelocationKind::MachO {
-                        value: reloc.r_type,
-                        relative: reloc.r_pcrel,
-                    },
-                },
-                macho::CPU_TYPE_X86_64 => match (reloc.r_type, reloc.r_pcrel) {
-                    (macho::X86_64_RELOC_UNSIGNED, false) => RelocationKind::Absolute,
-                    (macho::X86_64_RELOC_SIGNED, true) => {
-                        encoding = RelocationEncoding::X86RipRelative;
-                        RelocationKind::Relative
-                    }
-                    (macho::X86_64_RELOC_BRANCH, true) => {
-                        encoding = RelocationEncoding::X86Branch;
-                        RelocationKind::Relative
-                    }
-                    (macho::X86_64_RELOC_GOT, true) => RelocationKind::GotRelative,
-                    (macho::X86_64_RELOC_GOT_LOAD, true) => {
-                        encoding = RelocationEncoding::X86RipRelativeMovq;
-                        RelocationKind::GotRelative
-                    }
-                    _ => RelocationKind::MachO {
-                        value: reloc.r_type,
-                        relative: reloc.r_pcrel,
-                    },
-                },
-                _ => RelocationKind::MachO {
-                    value: reloc.r_type,
-                    relative: reloc.r_pcrel,
-                },
-            };
-            let size = 8 << reloc.r_length;
-            let target = if reloc.r_extern {
-                RelocationTarget::Symbol(SymbolIndex(reloc.r_symbolnum as usize))
+            // target:36, high8:8
+            let target = (value & 0xf_ffff_ffff) | ((value >> 36) & 0xff) << 56;
+            if pointer_format == macho::DYLD_CHAINED_PTR_64_OFFSET {
+                Ok(image_base.wrapping_add(target))
             } else {
-                RelocationTarget::Section(SectionIndex(reloc.r_symbolnum as usize))
-            };
-            let addend = if reloc.r_pcrel { -4 } else { 0 };
-            return Some((
-                reloc.r_address as u64,
-                Relocation {
-                    kind,
-                    encoding,
-                    size,
-                    target,
-                    addend,
-                    implicit_addend: true,
-                },
-            ));
+                Ok(target)
+            }
+        }
+        macho::DYLD_CHAINED_PTR_ARM64E
+        | macho::DYLD_CHAINED_PTR_ARM64E_USERLAND
+        | macho::DYLD_CHAINED_PTR_ARM64E_USERLAND24 => {
+            if value & (1 << 62) != 0 {
+                return Err(Error("Unsupported Mach-O chained bind pointer"));
+            }
+            if value & (1 << 63) != 0 {
+                // Authenticated rebases have a 32-bit offset from the image base.
+                return Ok(image_base.wrapping_add(value & 0xffff_ffff));
+            }
+            // target:43, high8:8
+            let target = (value & 0x7ff_ffff_ffff) | ((value >> 43) & 0xff) << 56;
+            if pointer_format == macho::DYLD_CHAINED_PTR_ARM64E {
+                Ok(target)
+            } else {
+                Ok(image_base.wrapping_add(target))
+            }
         }
+        macho::DYLD_CHAINED_PTR_32 => {
+            if value & (1 << 31) != 0 {
+                return Err(Error("Unsupported Mach-O chained bind pointer"));
+            }
+            // target:26
+            Ok(value & 0x3ff_ffff)
+        }
+        _ => Err(Error("Unsupported Mach-O chained pointer format")),
     }
 }
 
-impl<'data, 'file, Mach: MachHeader> fmt::Debug for MachORelocationIterator<'data, 'file, Mach> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("MachORelocationIterator").finish()
+/// Convert the 8-bit library ordinal of a chained import.
+///
+/// Values above `0xf0` are the sign extended special ordinals.
+fn lib_ordinal_8(lib_ordinal: u8) -> i32 {
+    if lib_ordinal > 0xf0 {
+        i32::from(lib_ordinal as i8)
+    } else {
+        i32::from(lib_ordinal)
+    }
+}
+
+/// Return the library for the library ordinal in the `n_desc` of an undefined symbol.
+fn symbol_library<'data>(libraries: &[&'data str], n_desc: u16) -> Result<Option<&'data str>> {
+    let ordinal = (n_desc >> 8) as u8;
+    match ordinal {
+        macho::SELF_LIBRARY_ORDINAL | macho::EXECUTABLE_ORDINAL => return Ok(None),
+        // Images with more than `MAX_LIBRARY_ORDINAL` libraries use this as an ordinal.
+        macho::DYNAMIC_LOOKUP_ORDINAL
+            if libraries.len() <= usize::from(macho::MAX_LIBRARY_ORDINAL) =>
+        {
+            return Ok(None)
+        }
+        _ => {}
     }
+    libraries
+        .get(usize::from(ordinal) - 1)
+        .cloned()
+        .read_error("Invalid Mach-O symbol library ordinal")
+        .map(Some)
 }
 
 /// An iterator over the load commands of a `MachHeader`.
@@ -800,39 +4895,79 @@ impl<'data, 'file, Mach: MachHeader> fmt::Debug for MachORelocationIterator<'dat
 pub struct MachOLoadCommandIterator<'data, E: Endian> {
     endian: E,
     data: Bytes<'data>,
+    // The file offset of `data`.
+    offset: u64,
+    // The remaining size given by `sizeofcmds`. This is larger than `data` if the
+    // load commands extend past the end of the file.
+    size: usize,
     ncmds: u32,
+    truncated: bool,
 }
 
 impl<'data, E: Endian> MachOLoadCommandIterator<'data, E> {
-    fn new(endian: E, data: Bytes<'data>, ncmds: u32) -> Self {
+    fn new(endian: E, data: Bytes<'data>, offset: u64, size: usize, ncmds: u32) -> Self {
         MachOLoadCommandIterator {
             endian,
             data,
+            offset,
+            size,
             ncmds,
+            truncated: false,
         }
     }
 
-    fn next(&mut self) -> Result<Option<MachOLoadCommand<'data, E>>> {
+    /// Return true if iteration stopped at a load command that extends past
+    /// the end of the file.
+    #[inline]
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Return the next load command.
+    ///
+    /// Returns `Ok(None)` after the last command, or if a command that is within
+    /// `sizeofcmds` extends past the end of the file. Returns an error for other
+    /// invalid commands, such as a misaligned command or a command that extends
+    /// past `sizeofcmds`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<MachOLoadCommand<'data, E>>> {
         if self.ncmds == 0 {
             return Ok(None);
         }
-        let header = self
-            .data
-            .read_at::<macho::LoadCommand<E>>(0)
-            .read_error("Invalid Mach-O load command header")?;
+        let header_size = mem::size_of::<macho::LoadCommand<E>>();
+        let header = match self.data.read_at::<macho::LoadCommand<E>>(0) {
+            Ok(header) => header,
+            Err(()) if self.data.len() < header_size && self.size >= header_size => {
+                return Ok(self.truncate());
+            }
+            Err(()) => return Err(Error("Invalid Mach-O load command header")),
+        };
         let cmd = header.cmd.get(self.endian);
         let cmdsize = header.cmdsize.get(self.endian) as usize;
-        let data = self
-            .data
-            .read_bytes(cmdsize)
-            .read_error("Invalid Mach-O load command size")?;
+        if cmdsize > self.size {
+            return Err(Error("Invalid Mach-O load command size"));
+        }
+        let data = match self.data.read_bytes(cmdsize) {
+            Ok(data) => data,
+            Err(()) => return Ok(self.truncate()),
+        };
+        let offset = self.offset;
+        self.offset += cmdsize as u64;
+        self.size -= cmdsize;
         self.ncmds -= 1;
         Ok(Some(MachOLoadCommand {
             cmd,
             data,
+            offset,
             marker: Default::default(),
         }))
     }
+
+    fn truncate(&mut self) -> Option<MachOLoadCommand<'data, E>> {
+        self.ncmds = 0;
+        self.truncated = true;
+        None
+    }
 }
 
 /// A parsed `LoadCommand`.
@@ -841,10 +4976,33 @@ pub struct MachOLoadCommand<'data, E: Endian> {
     cmd: u32,
     // Includes the header.
     data: Bytes<'data>,
+    offset: u64,
     marker: PhantomData<E>,
 }
 
 impl<'data, E: Endian> MachOLoadCommand<'data, E> {
+    /// The `cmd` field of the command.
+    #[inline]
+    pub fn cmd(&self) -> u32 {
+        self.cmd
+    }
+
+    /// The raw data of the command.
+    ///
+    /// The data includes the `cmd` and `cmdsize` fields.
+    #[inline]
+    pub fn data(&self) -> &'data [u8] {
+        self.data.0
+    }
+
+    /// The offset of the command within the file data.
+    ///
+    /// This can be used to modify the command in place.
+    #[inline]
+    pub fn file_offset(&self) -> u64 {
+        self.offset
+    }
+
     /// Try to parse this command as a `SegmentCommand32`.
     pub fn segment_32(self) -> Result<Option<(&'data macho::SegmentCommand32<E>, Bytes<'data>)>> {
         if self.cmd == macho::LC_SEGMENT {
@@ -901,6 +5059,157 @@ impl<'data, E: Endian> MachOLoadCommand<'data, E> {
         }
     }
 
+    /// Try to parse this command as a `DyldInfoCommand`.
+    ///
+    /// Accepts both `LC_DYLD_INFO` and `LC_DYLD_INFO_ONLY`.
+    pub fn dyld_info(self) -> Result<Option<&'data macho::DyldInfoCommand<E>>> {
+        if self.cmd == macho::LC_DYLD_INFO || self.cmd == macho::LC_DYLD_INFO_ONLY {
+            Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O LC_DYLD_INFO command size"),
+            )
+            .transpose()
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Try to parse this command as a `DylinkerCommand`.
+    ///
+    /// Accepts `LC_ID_DYLINKER`, `LC_LOAD_DYLINKER` and `LC_DYLD_ENVIRONMENT`.
+    pub fn dylinker(self) -> Result<Option<&'data macho::DylinkerCommand<E>>> {
+        match self.cmd {
+            macho::LC_ID_DYLINKER | macho::LC_LOAD_DYLINKER | macho::LC_DYLD_ENVIRONMENT => Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O dylinker command size"),
+            )
+            .transpose(),
+            _ => Ok(None),
+        }
+    }
+
+    /// Try to parse this command as a `DylibCommand`.
+    ///
+    /// Accepts `LC_ID_DYLIB` and any of the commands that load a dylib.
+    pub fn dylib(self) -> Result<Option<&'data macho::DylibCommand<E>>> {
+        match self.cmd {
+            macho::LC_ID_DYLIB
+            | macho::LC_LOAD_DYLIB
+            | macho::LC_LOAD_WEAK_DYLIB
+            | macho::LC_REEXPORT_DYLIB
+            | macho::LC_LAZY_LOAD_DYLIB
+            | macho::LC_LOAD_UPWARD_DYLIB => Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O dylib command size"),
+            )
+            .transpose(),
+            _ => Ok(None),
+        }
+    }
+
+    /// Read a string that is referenced by an `LcStr` field of this command.
+    ///
+    /// The string must be contained within the `cmdsize` of the command.
+    pub fn string(self, endian: E, lc_str: macho::LcStr<E>) -> Result<&'data [u8]> {
+        self.data
+            .read_string_at(lc_str.offset.get(endian) as usize)
+            .read_error("Invalid Mach-O load command string offset")
+    }
+
+    /// Try to parse this command as a `FilesetEntryCommand`.
+    pub fn fileset_entry(self) -> Result<Option<&'data macho::FilesetEntryCommand<E>>> {
+        if self.cmd == macho::LC_FILESET_ENTRY {
+            Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O LC_FILESET_ENTRY command size"),
+            )
+            .transpose()
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Try to parse this command as a `DysymtabCommand`.
+    pub fn dysymtab(self) -> Result<Option<&'data macho::DysymtabCommand<E>>> {
+        if self.cmd == macho::LC_DYSYMTAB {
+            Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O LC_DYSYMTAB command size"),
+            )
+            .transpose()
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Try to parse this command as a `BuildVersionCommand`.
+    pub fn build_version(self) -> Result<Option<&'data macho::BuildVersionCommand<E>>> {
+        if self.cmd == macho::LC_BUILD_VERSION {
+            Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O LC_BUILD_VERSION command size"),
+            )
+            .transpose()
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Try to parse this command as a `VersionMinCommand`.
+    ///
+    /// Accepts any of the `LC_VERSION_MIN_*` commands.
+    pub fn version_min(self) -> Result<Option<&'data macho::VersionMinCommand<E>>> {
+        match self.cmd {
+            macho::LC_VERSION_MIN_MACOSX
+            | macho::LC_VERSION_MIN_IPHONEOS
+            | macho::LC_VERSION_MIN_TVOS
+            | macho::LC_VERSION_MIN_WATCHOS => Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O LC_VERSION_MIN command size"),
+            )
+            .transpose(),
+            _ => Ok(None),
+        }
+    }
+
+    /// Try to parse this command as a `LinkeditDataCommand`.
+    ///
+    /// Accepts any command that refers to a blob of data in the `__LINKEDIT` segment,
+    /// such as `LC_CODE_SIGNATURE` or `LC_FUNCTION_STARTS`.
+    pub fn linkedit_data(self) -> Result<Option<&'data macho::LinkeditDataCommand<E>>> {
+        match self.cmd {
+            macho::LC_CODE_SIGNATURE
+            | macho::LC_SEGMENT_SPLIT_INFO
+            | macho::LC_FUNCTION_STARTS
+            | macho::LC_DATA_IN_CODE
+            | macho::LC_DYLIB_CODE_SIGN_DRS
+            | macho::LC_LINKER_OPTIMIZATION_HINT
+            | macho::LC_DYLD_EXPORTS_TRIE
+            | macho::LC_DYLD_CHAINED_FIXUPS => Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O linkedit data command size"),
+            )
+            .transpose(),
+            _ => Ok(None),
+        }
+    }
+
     /// Try to parse this command as an `EntryPointCommand`.
     pub fn entry_point(self) -> Result<Option<&'data macho::EntryPointCommand<E>>> {
         if self.cmd == macho::LC_MAIN {
@@ -915,6 +5224,21 @@ impl<'data, E: Endian> MachOLoadCommand<'data, E> {
             Ok(None)
         }
     }
+
+    /// Try to parse this command as a `SymSegCommand`.
+    pub fn symseg(self) -> Result<Option<&'data macho::SymSegCommand<E>>> {
+        if self.cmd == macho::LC_SYMSEG {
+            Some(
+                self.data
+                    .clone()
+                    .read()
+                    .read_error("Invalid Mach-O LC_SYMSEG command size"),
+            )
+            .transpose()
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -950,6 +5274,8 @@ pub trait MachHeader: Debug + Pod {
     fn ncmds(&self, endian: Self::Endian) -> u32;
     fn sizeofcmds(&self, endian: Self::Endian) -> u32;
     fn flags(&self, endian: Self::Endian) -> u32;
+    /// The `reserved` field of a 64-bit header. Always 0 for a 32-bit header.
+    fn reserved(&self, endian: Self::Endian) -> u32;
 
     // Provided methods.
 
@@ -966,12 +5292,35 @@ pub trait MachHeader: Debug + Pod {
         endian: Self::Endian,
         data: Bytes<'data>,
     ) -> Result<MachOLoadCommandIterator<'data, Self::Endian>> {
+        self.load_commands_at(endian, data, 0)
+    }
+
+    /// Return an iterator over the load commands that follow the header.
+    ///
+    /// `header_offset` is the offset of this header within `data`.
+    fn load_commands_at<'data>(
+        &self,
+        endian: Self::Endian,
+        data: Bytes<'data>,
+        header_offset: u64,
+    ) -> Result<MachOLoadCommandIterator<'data, Self::Endian>> {
+        // Stop at the end of the file if the load command table is truncated.
+        // Any command that doesn't fit is detected by the iterator.
+        let offset = (header_offset as usize)
+            .checked_add(mem::size_of::<Self>())
+            .read_error("Invalid Mach-O header offset")?;
+        let size = cmp::min(
+            self.sizeofcmds(endian) as usize,
+            data.len().saturating_sub(offset),
+        );
         let data = data
-            .read_bytes_at(mem::size_of::<Self>(), self.sizeofcmds(endian) as usize)
+            .read_bytes_at(offset, size)
             .read_error("Invalid Mach-O load command table size")?;
         Ok(MachOLoadCommandIterator::new(
             endian,
             data,
+            offset as u64,
+            self.sizeofcmds(endian) as usize,
             self.ncmds(endian),
         ))
     }
@@ -1052,6 +5401,8 @@ pub trait Section: Debug + Pod {
     fn reloff(&self, endian: Self::Endian) -> u32;
     fn nreloc(&self, endian: Self::Endian) -> u32;
     fn flags(&self, endian: Self::Endian) -> u32;
+    fn reserved1(&self, endian: Self::Endian) -> u32;
+    fn reserved2(&self, endian: Self::Endian) -> u32;
 
     /// Return the `sectname` bytes up until the null terminator.
     fn name(&self) -> &[u8] {
@@ -1169,6 +5520,10 @@ impl<Endian: endian::Endian> MachHeader for macho::MachHeader32<Endian> {
     fn flags(&self, endian: Self::Endian) -> u32 {
         self.flags.get(endian)
     }
+
+    fn reserved(&self, _endian: Self::Endian) -> u32 {
+        0
+    }
 }
 
 impl<Endian: endian::Endian> MachHeader for macho::MachHeader64<Endian> {
@@ -1217,6 +5572,10 @@ impl<Endian: endian::Endian> MachHeader for macho::MachHeader64<Endian> {
     fn flags(&self, endian: Self::Endian) -> u32 {
         self.flags.get(endian)
     }
+
+    fn reserved(&self, endian: Self::Endian) -> u32 {
+        self.reserved.get(endian)
+    }
 }
 
 impl<Endian: endian::Endian> Segment for macho::SegmentCommand32<Endian> {
@@ -1338,6 +5697,12 @@ impl<Endian: endian::Endian> Section for macho::Section32<Endian> {
     fn flags(&self, endian: Self::Endian) -> u32 {
         self.flags.get(endian)
     }
+    fn reserved1(&self, endian: Self::Endian) -> u32 {
+        self.reserved1.get(endian)
+    }
+    fn reserved2(&self, endian: Self::Endian) -> u32 {
+        self.reserved2.get(endian)
+    }
 }
 
 impl<Endian: endian::Endian> Section for macho::Section64<Endian> {
@@ -1371,6 +5736,12 @@ impl<Endian: endian::Endian> Section for macho::Section64<Endian> {
     fn flags(&self, endian: Self::Endian) -> u32 {
         self.flags.get(endian)
     }
+    fn reserved1(&self, endian: Self::Endian) -> u32 {
+        self.reserved1.get(endian)
+    }
+    fn reserved2(&self, endian: Self::Endian) -> u32 {
+        self.reserved2.get(endian)
+    }
 }
 
 impl<Endian: endian::Endian> Nlist for macho::Nlist32<Endian> {
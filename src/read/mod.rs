@@ -4,12 +4,13 @@ use alloc::vec::Vec;
 use core::{cmp, fmt, result};
 
 use crate::common::{
-    FileFlags, RelocationEncoding, RelocationKind, SectionFlags, SectionKind, SymbolFlags,
-    SymbolKind, SymbolScope,
+    FileFlags, Platform, RelocationEncoding, RelocationKind, SectionFlags, SectionKind,
+    SymbolFlags, SymbolKind, SymbolScope,
 };
 use crate::pod::Bytes;
 
 mod util;
+pub use util::{read_sleb128, read_uleb128};
 
 mod any;
 pub use any::*;
@@ -98,11 +99,11 @@ pub type NativeFile<'data> = pe::PeFile64<'data>;
 pub type NativeFile<'data> = wasm::WasmFile<'data>;
 
 /// The index used to identify a section of a file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SectionIndex(pub usize);
 
 /// The index used to identify a symbol of a file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SymbolIndex(pub usize);
 
 /// The section where a symbol is defined.
@@ -1,10 +1,12 @@
 #[cfg(feature = "compression")]
 use alloc::borrow::Cow;
+use alloc::string::String;
 use target_lexicon::{Architecture, Endianness};
 
 use crate::read::{self, Result};
 use crate::{
-    FileFlags, Relocation, SectionFlags, SectionIndex, SectionKind, Symbol, SymbolIndex, SymbolMap,
+    FileFlags, Platform, Relocation, SectionFlags, SectionIndex, SectionKind, Symbol, SymbolIndex,
+    SymbolMap,
 };
 
 /// An object file.
@@ -142,6 +144,25 @@ pub trait Object<'data, 'file>: read::private::Sealed {
         Ok(None)
     }
 
+    /// An identifier for the file that can be used as a symbol server key.
+    ///
+    /// This is formatted as lowercase hex. For Mach-O, this is the UUID given by
+    /// `mach_uuid`. For ELF, this is the build ID given by `build_id`.
+    /// Other formats currently return `None`.
+    #[inline]
+    fn code_id(&self) -> Option<String> {
+        None
+    }
+
+    /// The platform that the file is built for.
+    ///
+    /// For Mach-O, this is read from the `LC_BUILD_VERSION` or `LC_VERSION_MIN_*`
+    /// command. Other formats currently return `None`.
+    #[inline]
+    fn platform(&self) -> Option<Platform> {
+        None
+    }
+
     /// File flags that are specific to each file format.
     fn flags(&self) -> FileFlags;
 }
@@ -211,7 +232,8 @@ pub trait ObjectSection<'data>: read::private::Sealed {
 
     /// Return the raw contents of the section data in the given range.
     ///
-    /// This does not do any decompression.
+    /// This can be used to read the data at an address given by a relocation
+    /// or pointer within the section. This does not do any decompression.
     ///
     /// Returns `Ok(None)` if the section does not contain the given range.
     fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>>;
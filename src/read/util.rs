@@ -1,10 +1,23 @@
+use alloc::string::String;
+use core::fmt::Write;
+
 use crate::pod::Bytes;
+use crate::read::{self, ReadError};
 
 #[inline]
 pub(crate) fn align(offset: usize, size: usize) -> usize {
     (offset + (size - 1)) & !(size - 1)
 }
 
+/// Format bytes as lowercase hex.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(s, "{:02x}", byte).unwrap();
+    }
+    s
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub(crate) struct StringTable<'data> {
     pub data: Bytes<'data>,
@@ -15,3 +28,25 @@ impl<'data> StringTable<'data> {
         self.data.read_string_at(offset as usize)
     }
 }
+
+/// Read an unsigned LEB128 number from the start of `data`.
+///
+/// On success, `data` is advanced past the number. Returns an error if the number
+/// is truncated or does not fit in a `u64`.
+pub fn read_uleb128(data: &mut &[u8]) -> read::Result<u64> {
+    let mut bytes = Bytes(data);
+    let value = bytes.read_uleb128().read_error("Invalid ULEB128 value")?;
+    *data = bytes.0;
+    Ok(value)
+}
+
+/// Read a signed LEB128 number from the start of `data`.
+///
+/// On success, `data` is advanced past the number. Returns an error if the number
+/// is truncated or does not fit in an `i64`.
+pub fn read_sleb128(data: &mut &[u8]) -> read::Result<i64> {
+    let mut bytes = Bytes(data);
+    let value = bytes.read_sleb128().read_error("Invalid SLEB128 value")?;
+    *data = bytes.0;
+    Ok(value)
+}
@@ -178,7 +178,8 @@ impl Object {
         let constant = match relocation.kind {
             RelocationKind::Relative
             | RelocationKind::GotRelative
-            | RelocationKind::PltRelative => relocation.addend + 4,
+            | RelocationKind::PltRelative
+            | RelocationKind::TlvRelative => relocation.addend + 4,
             _ => relocation.addend,
         };
         relocation.addend -= constant;
@@ -555,11 +556,21 @@ impl Object {
                             (RelocationKind::GotRelative, RelocationEncoding::Generic, -4) => {
                                 (true, macho::X86_64_RELOC_GOT)
                             }
+                            (
+                                RelocationKind::GotRelative,
+                                RelocationEncoding::X86RipRelative,
+                                -4,
+                            ) => (true, macho::X86_64_RELOC_GOT),
                             (
                                 RelocationKind::GotRelative,
                                 RelocationEncoding::X86RipRelativeMovq,
                                 -4,
                             ) => (true, macho::X86_64_RELOC_GOT_LOAD),
+                            (
+                                RelocationKind::TlvRelative,
+                                RelocationEncoding::X86RipRelativeMovq,
+                                -4,
+                            ) => (true, macho::X86_64_RELOC_TLV),
                             (RelocationKind::MachO { value, relative }, _, _) => (relative, value),
                             _ => {
                                 return Err(Error(format!("unimplemented relocation {:?}", reloc)));
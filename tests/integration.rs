@@ -1 +1,2 @@
+mod read;
 mod round_trip;
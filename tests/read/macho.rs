@@ -0,0 +1,4585 @@
+use std::str::FromStr;
+
+use object::endian::{LittleEndian, RunTimeEndian, U32};
+use object::macho;
+use object::read::macho::{
+    MachOBuildVersion, MachOCodeSignature, MachOCodeSignatureFlags, MachOCompactUnwindEntry,
+    MachOEntrySource, MachOExport, MachOFatArch, MachOFatFile, MachOFile32, MachOFile64,
+    MachOFilesetEntry, MachOLinkeditFormat, MachOObjcImageInfo, MachOSection64, ParseOptions,
+};
+use object::read::{Object, ObjectSection, ObjectSegment};
+use object::target_lexicon::{
+    Aarch64Architecture, Architecture, ArmArchitecture, BinaryFormat, Environment, OperatingSystem,
+    Triple,
+};
+use object::{
+    RelocationEncoding, RelocationKind, RelocationTarget, SectionIndex, SectionKind, SymbolIndex,
+};
+
+/// A minimal Mach-O writer for building test files by hand.
+///
+/// Load commands are appended in order, and arbitrary data can be placed
+/// at fixed file offsets after the load commands.
+struct Builder {
+    is_64: bool,
+    big_endian: bool,
+    cputype: u32,
+    cpusubtype: u32,
+    filetype: u32,
+    flags: u32,
+    ncmds: u32,
+    commands: Vec<u8>,
+    data: Vec<(usize, Vec<u8>)>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Sect<'a> {
+    sectname: &'a [u8],
+    segname: &'a [u8],
+    addr: u64,
+    size: u64,
+    offset: u32,
+    align: u32,
+    reloff: u32,
+    nreloc: u32,
+    flags: u32,
+    reserved1: u32,
+    reserved2: u32,
+}
+
+impl Builder {
+    fn new(is_64: bool, big_endian: bool, cputype: u32, filetype: u32) -> Self {
+        Builder {
+            is_64,
+            big_endian,
+            cputype,
+            cpusubtype: 0,
+            filetype,
+            flags: 0,
+            ncmds: 0,
+            commands: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    fn new64(cputype: u32, filetype: u32) -> Self {
+        Builder::new(true, false, cputype, filetype)
+    }
+
+    fn put_u16(&self, buf: &mut Vec<u8>, val: u16) {
+        if self.big_endian {
+            buf.extend_from_slice(&val.to_be_bytes());
+        } else {
+            buf.extend_from_slice(&val.to_le_bytes());
+        }
+    }
+
+    fn put_u32(&self, buf: &mut Vec<u8>, val: u32) {
+        if self.big_endian {
+            buf.extend_from_slice(&val.to_be_bytes());
+        } else {
+            buf.extend_from_slice(&val.to_le_bytes());
+        }
+    }
+
+    fn put_u64(&self, buf: &mut Vec<u8>, val: u64) {
+        if self.big_endian {
+            buf.extend_from_slice(&val.to_be_bytes());
+        } else {
+            buf.extend_from_slice(&val.to_le_bytes());
+        }
+    }
+
+    fn put_word(&self, buf: &mut Vec<u8>, val: u64) {
+        if self.is_64 {
+            self.put_u64(buf, val);
+        } else {
+            self.put_u32(buf, val as u32);
+        }
+    }
+
+    fn put_name(buf: &mut Vec<u8>, name: &[u8]) {
+        let mut bytes = [0; 16];
+        bytes[..name.len()].copy_from_slice(name);
+        buf.extend_from_slice(&bytes);
+    }
+
+    /// Encode a plain relocation entry.
+    fn reloc(
+        &self,
+        address: u32,
+        symbolnum: u32,
+        pcrel: bool,
+        length: u8,
+        is_extern: bool,
+        r_type: u8,
+    ) -> Vec<u8> {
+        let info = macho::RelocationInfo {
+            r_address: address,
+            r_symbolnum: symbolnum,
+            r_pcrel: pcrel,
+            r_length: length,
+            r_extern: is_extern,
+            r_type,
+        };
+        let mut buf = Vec::new();
+        let reloc = if self.big_endian {
+            let r = info.relocation(object::endian::BigEndian);
+            [
+                r.r_word0.get(object::endian::BigEndian),
+                r.r_word1.get(object::endian::BigEndian),
+            ]
+        } else {
+            let r = info.relocation(object::endian::LittleEndian);
+            [
+                r.r_word0.get(object::endian::LittleEndian),
+                r.r_word1.get(object::endian::LittleEndian),
+            ]
+        };
+        self.put_u32(&mut buf, reloc[0]);
+        self.put_u32(&mut buf, reloc[1]);
+        buf
+    }
+
+    /// Encode a list of 32-bit values in the file endianness.
+    fn u32s(&self, vals: &[u32]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for &val in vals {
+            self.put_u32(&mut buf, val);
+        }
+        buf
+    }
+
+    /// Encode a symbol table entry.
+    fn nlist(&self, strx: u32, n_type: u8, n_sect: u8, n_desc: u16, n_value: u64) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.put_u32(&mut buf, strx);
+        buf.push(n_type);
+        buf.push(n_sect);
+        self.put_u16(&mut buf, n_desc);
+        self.put_word(&mut buf, n_value);
+        buf
+    }
+
+    /// Append a load command with the given payload (excluding `cmd` and `cmdsize`).
+    fn command(&mut self, cmd: u32, payload: &[u8]) -> &mut Self {
+        let align = if self.is_64 { 8 } else { 4 };
+        let size = (8 + payload.len() + align - 1) & !(align - 1);
+        let mut buf = Vec::new();
+        self.put_u32(&mut buf, cmd);
+        self.put_u32(&mut buf, size as u32);
+        buf.extend_from_slice(payload);
+        buf.resize(size, 0);
+        self.commands.extend_from_slice(&buf);
+        self.ncmds += 1;
+        self
+    }
+
+    /// Append a segment load command containing the given sections.
+    #[allow(clippy::too_many_arguments)]
+    fn segment(
+        &mut self,
+        segname: &[u8],
+        vmaddr: u64,
+        vmsize: u64,
+        fileoff: u64,
+        filesize: u64,
+        prot: u32,
+        sections: &[Sect],
+    ) -> &mut Self {
+        let mut buf = Vec::new();
+        Self::put_name(&mut buf, segname);
+        self.put_word(&mut buf, vmaddr);
+        self.put_word(&mut buf, vmsize);
+        self.put_word(&mut buf, fileoff);
+        self.put_word(&mut buf, filesize);
+        self.put_u32(&mut buf, prot);
+        self.put_u32(&mut buf, prot);
+        self.put_u32(&mut buf, sections.len() as u32);
+        self.put_u32(&mut buf, 0);
+        for section in sections {
+            Self::put_name(&mut buf, section.sectname);
+            Self::put_name(&mut buf, section.segname);
+            self.put_word(&mut buf, section.addr);
+            self.put_word(&mut buf, section.size);
+            self.put_u32(&mut buf, section.offset);
+            self.put_u32(&mut buf, section.align);
+            self.put_u32(&mut buf, section.reloff);
+            self.put_u32(&mut buf, section.nreloc);
+            self.put_u32(&mut buf, section.flags);
+            self.put_u32(&mut buf, section.reserved1);
+            self.put_u32(&mut buf, section.reserved2);
+            if self.is_64 {
+                self.put_u32(&mut buf, 0);
+            }
+        }
+        let cmd = if self.is_64 {
+            macho::LC_SEGMENT_64
+        } else {
+            macho::LC_SEGMENT
+        };
+        self.command(cmd, &buf)
+    }
+
+    /// Append an `LC_SYMTAB` command.
+    fn symtab(&mut self, symoff: u32, nsyms: u32, stroff: u32, strsize: u32) -> &mut Self {
+        let payload = self.u32s(&[symoff, nsyms, stroff, strsize]);
+        self.command(macho::LC_SYMTAB, &payload)
+    }
+
+    /// Place data at the given file offset.
+    fn data(&mut self, offset: usize, data: &[u8]) -> &mut Self {
+        self.data.push((offset, data.to_vec()));
+        self
+    }
+
+    fn build(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let magic = match (self.is_64, self.big_endian) {
+            (false, false) => macho::MH_CIGAM,
+            (false, true) => macho::MH_MAGIC,
+            (true, false) => macho::MH_CIGAM_64,
+            (true, true) => macho::MH_MAGIC_64,
+        };
+        buf.extend_from_slice(&magic.to_be_bytes());
+        self.put_u32(&mut buf, self.cputype);
+        self.put_u32(&mut buf, self.cpusubtype);
+        self.put_u32(&mut buf, self.filetype);
+        self.put_u32(&mut buf, self.ncmds);
+        self.put_u32(&mut buf, self.commands.len() as u32);
+        self.put_u32(&mut buf, self.flags);
+        if self.is_64 {
+            self.put_u32(&mut buf, 0);
+        }
+        buf.extend_from_slice(&self.commands);
+        for (offset, data) in &self.data {
+            let end = offset + data.len();
+            if buf.len() < end {
+                buf.resize(end, 0);
+            }
+            buf[*offset..end].copy_from_slice(data);
+        }
+        buf
+    }
+}
+
+const TEXT_ADDR: u64 = 0x1_0000_0000;
+const STRINGS: &[u8] = b"\0_main\0_helper\0_data\0_printf\0_start\0\0\0\0\0";
+
+/// Start building a small x86-64 executable.
+///
+/// Contains `__PAGEZERO`, `__TEXT` (`__text`, `__cstring`), `__DATA` (`__data`, `__bss`)
+/// and `__LINKEDIT` segments, a symbol table and an `LC_MAIN` command.
+///
+/// The symbols are `_helper` (local), `_main`, `_start` (a weak alias of `_main`),
+/// `_data`, and `_printf` (undefined).
+fn executable() -> Builder {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder.flags = macho::MH_NOUNDEFS | macho::MH_DYLDLINK | macho::MH_TWOLEVEL | macho::MH_PIE;
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        addr: TEXT_ADDR + 0xf00,
+        size: 0x40,
+        offset: 0xf00,
+        align: 4,
+        flags: macho::S_ATTR_PURE_INSTRUCTIONS | macho::S_ATTR_SOME_INSTRUCTIONS,
+        ..Default::default()
+    };
+    let cstring = Sect {
+        sectname: b"__cstring",
+        segname: b"__TEXT",
+        addr: TEXT_ADDR + 0xf40,
+        size: 0x10,
+        offset: 0xf40,
+        flags: macho::S_CSTRING_LITERALS,
+        ..Default::default()
+    };
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: TEXT_ADDR + 0x1000,
+        size: 0x10,
+        offset: 0x1000,
+        align: 3,
+        ..Default::default()
+    };
+    let bss = Sect {
+        sectname: b"__bss",
+        segname: b"__DATA",
+        addr: TEXT_ADDR + 0x1010,
+        size: 0x20,
+        align: 3,
+        flags: macho::S_ZEROFILL,
+        ..Default::default()
+    };
+    let rx = macho::VM_PROT_READ | macho::VM_PROT_EXECUTE;
+    let rw = macho::VM_PROT_READ | macho::VM_PROT_WRITE;
+    let mut symbols = builder.nlist(7, macho::N_SECT, 1, 0, TEXT_ADDR + 0xf20);
+    symbols.extend(builder.nlist(1, macho::N_SECT | macho::N_EXT, 1, 0, TEXT_ADDR + 0xf00));
+    symbols.extend(builder.nlist(
+        29,
+        macho::N_SECT | macho::N_EXT,
+        1,
+        macho::N_WEAK_DEF,
+        TEXT_ADDR + 0xf00,
+    ));
+    symbols.extend(builder.nlist(15, macho::N_SECT | macho::N_EXT, 3, 0, TEXT_ADDR + 0x1000));
+    symbols.extend(builder.nlist(21, macho::N_UNDF | macho::N_EXT, 0, 0x100, 0));
+    let strings_offset = 0x2000 + symbols.len();
+    let entry = builder.u32s(&[0xf00, 0, 0, 0]);
+    builder
+        .segment(b"__PAGEZERO", 0, TEXT_ADDR, 0, 0, 0, &[])
+        .segment(
+            b"__TEXT",
+            TEXT_ADDR,
+            0x1000,
+            0,
+            0x1000,
+            rx,
+            &[text, cstring],
+        )
+        .segment(
+            b"__DATA",
+            TEXT_ADDR + 0x1000,
+            0x1000,
+            0x1000,
+            0x1000,
+            rw,
+            &[data, bss],
+        )
+        .segment(
+            b"__LINKEDIT",
+            TEXT_ADDR + 0x2000,
+            0x1000,
+            0x2000,
+            (symbols.len() + STRINGS.len()) as u64,
+            macho::VM_PROT_READ,
+            &[],
+        )
+        .symtab(0x2000, 5, strings_offset as u32, STRINGS.len() as u32)
+        .command(macho::LC_MAIN, &entry)
+        .data(0xf00, &[0xc3; 0x40])
+        .data(0xf40, b"hello\0world\0\0\0\0\0")
+        .data(0x1000, &[0x11; 0x10])
+        .data(0x2000, &symbols)
+        .data(strings_offset, STRINGS);
+    builder
+}
+
+#[test]
+fn uuid_matches() {
+    let mut builder = executable();
+    builder.command(macho::LC_UUID, &[0x12; 16]);
+    let binary = builder.build();
+    let binary = MachOFile64::<RunTimeEndian>::parse(&binary).unwrap();
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_DSYM);
+    builder.command(macho::LC_UUID, &[0x12; 16]);
+    let dsym = builder.build();
+    let dsym = MachOFile64::<RunTimeEndian>::parse(&dsym).unwrap();
+    assert_eq!(binary.uuid_matches(&dsym), Ok(true));
+    assert_eq!(dsym.uuid_matches(&binary), Ok(true));
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_DSYM);
+    builder.command(macho::LC_UUID, &[0x34; 16]);
+    let other = builder.build();
+    let other = MachOFile64::<RunTimeEndian>::parse(&other).unwrap();
+    assert_eq!(binary.uuid_matches(&other), Ok(false));
+
+    let none = executable().build();
+    let none = MachOFile64::<RunTimeEndian>::parse(&none).unwrap();
+    assert_eq!(binary.uuid_matches(&none), Ok(false));
+    assert_eq!(none.uuid_matches(&none), Ok(false));
+}
+
+#[test]
+fn code_id() {
+    let mut builder = executable();
+    let uuid = [
+        0x6e, 0x3b, 0x0a, 0x82, 0x13, 0x2c, 0x3e, 0x4d, 0x9a, 0x1f, 0x05, 0xd2, 0x8c, 0x71, 0xb4,
+        0xe0,
+    ];
+    builder.command(macho::LC_UUID, &uuid);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.code_id().as_deref(),
+        Some("6e3b0a82132c3e4d9a1f05d28c71b4e0")
+    );
+    let file = object::File::parse(&data).unwrap();
+    assert_eq!(
+        file.code_id().as_deref(),
+        Some("6e3b0a82132c3e4d9a1f05d28c71b4e0")
+    );
+
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.code_id(), None);
+}
+
+#[test]
+fn zero_sizeofcmds() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    builder.command(macho::LC_UUID, &[0; 16]);
+    let mut data = builder.build();
+    // Clear `sizeofcmds` while leaving `ncmds` set.
+    data[20..24].copy_from_slice(&[0; 4]);
+    assert!(MachOFile64::<RunTimeEndian>::parse(&data).is_err());
+
+    // Consistent files without any load commands are still valid.
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.sections().count(), 0);
+}
+
+#[test]
+fn segment_data() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder
+        .segment(b"__PAGEZERO", 0, 0x1_0000_0000, 0, 0, 0, &[])
+        .segment(b"__TEXT", 0x1_0000_0000, 0x1000, 0, 0x1000, 5, &[])
+        .segment(b"__DATA", 0x1_0000_1000, 0x1000, 0x1000, 0x800, 3, &[])
+        .data(0x1000, &[0xaa; 0x800]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let segments = file.segment_data().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].len(), 0x1000);
+    assert_eq!(segments[0][..4], data[..4]);
+    assert_eq!(segments[1], &[0xaa; 0x800][..]);
+    let total: usize = segments.iter().map(|data| data.len()).sum();
+    assert_eq!(total, data.len());
+}
+
+#[test]
+fn text_segment() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.text_segment().unwrap().unwrap();
+    assert_eq!(text.address(), TEXT_ADDR);
+    assert_eq!(text.file_range(), (0, 0x1000));
+
+    // An object file has a single unnamed segment.
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT)
+        .segment(b"", 0, 0, 0, 0, 7, &[])
+        .build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.text_segment().unwrap().is_none());
+}
+
+#[test]
+fn segments_with_sections() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let counts: Vec<_> = file
+        .segments_with_sections()
+        .map(|(segment, sections)| (segment.address(), sections.len()))
+        .collect();
+    assert_eq!(
+        counts[..3],
+        [(0, 0), (TEXT_ADDR, 2), (TEXT_ADDR + 0x1000, 2)]
+    );
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    assert_eq!(total, file.sections().count());
+
+    let (segment, sections) = file.segments_with_sections().nth(2).unwrap();
+    assert_eq!(segment.address(), TEXT_ADDR + 0x1000);
+    assert_eq!(&sections[1].sectname[..6], b"__bss\0");
+}
+
+#[test]
+fn section_contents() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 4,
+        offset: 0x100,
+        ..Default::default()
+    };
+    let custom = Sect {
+        sectname: b"__cus\xfftom",
+        segname: b"__DATA",
+        addr: 4,
+        size: 2,
+        offset: 0x104,
+        ..Default::default()
+    };
+    let bss = Sect {
+        sectname: b"__bss",
+        segname: b"__DATA",
+        addr: 8,
+        size: 8,
+        flags: macho::S_ZEROFILL,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 0x10, 0x100, 6, 7, &[text, custom, bss])
+        .data(0x100, &[0x55, 0x48, 0x89, 0xe5, 1, 2]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let contents = file
+        .section_contents()
+        .map(|(segment, section, data)| (segment.into_owned(), section.into_owned(), data))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        contents,
+        [
+            (
+                "__TEXT".to_string(),
+                "__text".to_string(),
+                Ok(&[0x55, 0x48, 0x89, 0xe5][..])
+            ),
+            (
+                "__DATA".to_string(),
+                "__cus\u{fffd}tom".to_string(),
+                Ok(&[1, 2][..])
+            ),
+            ("__DATA".to_string(), "__bss".to_string(), Ok(&[][..])),
+        ]
+    );
+}
+
+#[test]
+fn object_empty_segment_names() {
+    // `clang -c` output where the sections don't give a segment name.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let sect = |sectname, addr: u64, size, offset, flags| Sect {
+        sectname,
+        segname: b"",
+        addr,
+        size,
+        offset,
+        flags,
+        ..Default::default()
+    };
+    let mut text = sect(b"__text", 0, 0x10, 0x200, macho::S_ATTR_SOME_INSTRUCTIONS);
+    text.reloff = 0x300;
+    text.nreloc = 1;
+    let sections = [
+        text,
+        sect(b"__data", 0x10, 8, 0x210, 0),
+        sect(b"__cstring", 0x18, 6, 0x218, macho::S_CSTRING_LITERALS),
+        sect(b"__bss", 0x20, 8, 0, macho::S_ZEROFILL),
+        sect(b"__debug_info", 0x28, 4, 0x220, macho::S_ATTR_DEBUG),
+    ];
+    // callq _puts
+    let reloc = builder.reloc(1, 0, true, 2, true, macho::X86_64_RELOC_BRANCH);
+    builder
+        .segment(b"", 0, 0x2c, 0x200, 0x24, 7, &sections)
+        .data(0x200, &[0xe8, 0, 0, 0, 0])
+        .data(0x300, &reloc);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let kinds = file
+        .sections()
+        .map(|section| (section.name_bytes(), section.kind()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            (&b"__text"[..], SectionKind::Text),
+            (b"__data", SectionKind::Data),
+            (b"__cstring", SectionKind::ReadOnlyString),
+            (b"__bss", SectionKind::UninitializedData),
+            (b"__debug_info", SectionKind::Debug),
+        ]
+    );
+
+    let text = file.section_by_name("__text").unwrap();
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 1);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::Relative);
+    assert_eq!(
+        relocations[0].1.target(),
+        RelocationTarget::Symbol(SymbolIndex(0))
+    );
+}
+
+#[test]
+fn tlv_relocations() {
+    // movq _var@TLVPCREL(%rip), %rdi
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let reloc = builder.reloc(3, 0, true, 2, true, macho::X86_64_RELOC_TLV);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 7,
+        offset: 0x100,
+        reloff: 0x108,
+        nreloc: 1,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 7, 0x100, 7, 7, &[text])
+        .data(0x100, &[0x48, 0x8b, 0x3d, 0, 0, 0, 0])
+        .data(0x108, &reloc);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.section_by_name("__text").unwrap();
+    let (offset, relocation) = text.relocations().next().unwrap();
+    assert_eq!(offset, 3);
+    assert_eq!(relocation.kind(), RelocationKind::TlvRelative);
+    assert_eq!(
+        relocation.encoding(),
+        RelocationEncoding::X86RipRelativeMovq
+    );
+    assert_eq!(relocation.size(), 32);
+
+    // adrp x0, _var@TLVPPAGE
+    // ldr x0, [x0, _var@TLVPPAGEOFF]
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_OBJECT);
+    let mut relocs = builder.reloc(0, 0, true, 2, true, macho::ARM64_RELOC_TLVP_LOAD_PAGE21);
+    relocs.extend(builder.reloc(4, 0, false, 2, true, macho::ARM64_RELOC_TLVP_LOAD_PAGEOFF12));
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 8,
+        offset: 0x100,
+        reloff: 0x108,
+        nreloc: 2,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 8, 0x100, 8, 7, &[text])
+        .data(0x108, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.section_by_name("__text").unwrap();
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].0, 0);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::TlvRelative);
+    assert_eq!(
+        relocations[0].1.encoding(),
+        RelocationEncoding::AArch64Page21
+    );
+    assert_eq!(relocations[1].0, 4);
+    assert_eq!(relocations[1].1.kind(), RelocationKind::Tlv);
+    assert_eq!(
+        relocations[1].1.encoding(),
+        RelocationEncoding::AArch64PageOffset12
+    );
+}
+
+#[test]
+fn string_table_data() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let strings = file.string_table_data();
+    assert_eq!(strings, STRINGS);
+    assert_eq!(strings[0], 0);
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.string_table_data().is_empty());
+}
+
+#[test]
+fn symbols_at_address() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let symbols = file.symbols_at_address(TEXT_ADDR + 0xf00);
+    let names = symbols.iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, [Some("_main"), Some("_start")]);
+    assert!(!symbols[0].is_weak());
+    assert!(symbols[1].is_weak());
+
+    let symbols = file.symbols_at_address(TEXT_ADDR + 0xf20);
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name(), Some("_helper"));
+
+    // Undefined symbols have an address of 0, but are not defined there.
+    assert!(file.symbols_at_address(0).is_empty());
+    assert!(file.symbols_at_address(TEXT_ADDR + 0xf01).is_empty());
+}
+
+#[test]
+fn resolved_relocations() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    // leaq _var+8(%rip), %rax
+    let mut relocs = builder.reloc(3, 0, true, 2, true, macho::X86_64_RELOC_SIGNED);
+    // .quad _var+0x10
+    relocs.extend(builder.reloc(8, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED));
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 16,
+        offset: 0x100,
+        reloff: 0x110,
+        nreloc: 2,
+        ..Default::default()
+    };
+    let mut code = vec![0x48, 0x8d, 0x05, 8, 0, 0, 0, 0x90];
+    code.extend_from_slice(&0x10u64.to_le_bytes());
+    builder
+        .segment(b"", 0, 16, 0x100, 16, 7, &[text])
+        .data(0x100, &code)
+        .data(0x110, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.section_by_name("__text").unwrap();
+
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations[0].1.addend(), -4);
+    assert!(relocations[0].1.has_implicit_addend());
+
+    let relocations = text.resolved_relocations().unwrap().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].0, 3);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::Relative);
+    assert_eq!(relocations[0].1.addend(), 4);
+    assert!(!relocations[0].1.has_implicit_addend());
+    assert_eq!(relocations[1].0, 8);
+    assert_eq!(relocations[1].1.kind(), RelocationKind::Absolute);
+    assert_eq!(relocations[1].1.addend(), 0x10);
+    assert!(!relocations[1].1.has_implicit_addend());
+}
+
+#[test]
+fn relocation_target_address() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    // .quad __text+4
+    let mut relocs = builder.reloc(0, 1, false, 3, false, macho::X86_64_RELOC_UNSIGNED);
+    // .quad _printf
+    relocs.extend(builder.reloc(8, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED));
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x10,
+        offset: 0x200,
+        ..Default::default()
+    };
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: 0x10,
+        size: 0x10,
+        offset: 0x210,
+        reloff: 0x220,
+        nreloc: 2,
+        ..Default::default()
+    };
+    let strings = b"\0_printf\0";
+    let symbols = builder.nlist(1, macho::N_UNDF | macho::N_EXT, 0, 0, 0);
+    let mut contents = vec![0x90; 0x10];
+    contents.extend_from_slice(&4u64.to_le_bytes());
+    contents.extend_from_slice(&0u64.to_le_bytes());
+    builder
+        .segment(b"", 0, 0x20, 0x200, 0x20, 7, &[text, data])
+        .symtab(0x240, 1, 0x250, strings.len() as u32)
+        .data(0x200, &contents)
+        .data(0x220, &relocs)
+        .data(0x240, &symbols)
+        .data(0x250, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let section = file.section_by_name("__data").unwrap();
+    let relocations = section.relocations().collect::<Vec<_>>();
+
+    let target = relocations[0].1.target();
+    assert_eq!(target, RelocationTarget::Section(SectionIndex(1)));
+    assert_eq!(file.relocation_target_address(&target), Ok(Some(0)));
+    let target = relocations[1].1.target();
+    assert_eq!(target, RelocationTarget::Symbol(SymbolIndex(0)));
+    assert_eq!(file.relocation_target_address(&target), Ok(None));
+
+    let target = RelocationTarget::Section(SectionIndex(2));
+    assert_eq!(file.relocation_target_address(&target), Ok(Some(0x10)));
+    let target = RelocationTarget::Section(SectionIndex(3));
+    assert!(file.relocation_target_address(&target).is_err());
+
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let target = RelocationTarget::Symbol(SymbolIndex(1));
+    assert_eq!(
+        file.relocation_target_address(&target),
+        Ok(Some(TEXT_ADDR + 0xf00))
+    );
+}
+
+#[test]
+fn section_data_owned() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        addr: TEXT_ADDR + 0xf00,
+        size: 0x10,
+        offset: 0xf00,
+        ..Default::default()
+    };
+    // Only the first 0x10 bytes are in the file data of the segment.
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: TEXT_ADDR + 0x1000,
+        size: 0x20,
+        offset: 0x1000,
+        ..Default::default()
+    };
+    let bss = Sect {
+        sectname: b"__bss",
+        segname: b"__DATA",
+        addr: TEXT_ADDR + 0x1020,
+        size: 0x8,
+        flags: macho::S_ZEROFILL,
+        ..Default::default()
+    };
+    builder
+        .segment(b"__TEXT", TEXT_ADDR, 0x1000, 0, 0x1000, 5, &[text])
+        .segment(
+            b"__DATA",
+            TEXT_ADDR + 0x1000,
+            0x1000,
+            0x1000,
+            0x10,
+            3,
+            &[data, bss],
+        )
+        .data(0xf00, &[0x90; 0x10])
+        .data(0x1000, &[0xaa; 0x10]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let text = file.section_by_name("__text").unwrap();
+    assert_eq!(text.data_owned().unwrap(), text.data().unwrap());
+
+    let data = file.section_by_name("__data").unwrap();
+    assert!(data.data().is_err());
+    let mut expected = vec![0xaa; 0x10];
+    expected.resize(0x20, 0);
+    assert_eq!(data.data_owned().unwrap(), expected);
+
+    let bss = file.section_by_name("__bss").unwrap();
+    assert_eq!(bss.data_owned().unwrap(), [0; 8]);
+
+    // A zero-fill section that is larger than its segment.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let bss = Sect {
+        sectname: b"__bss",
+        segname: b"__DATA",
+        addr: TEXT_ADDR,
+        size: 0xffff_ffff_ffff,
+        flags: macho::S_ZEROFILL,
+        ..Default::default()
+    };
+    builder.segment(b"__DATA", TEXT_ADDR, 0x1000, 0, 0, 3, &[bss]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let bss = file.section_by_name("__bss").unwrap();
+    assert_eq!(
+        bss.data_owned().unwrap_err().to_string(),
+        "Mach-O section is not within a segment"
+    );
+}
+
+#[test]
+fn xrefs_to_symbols() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    // callq _printf; callq _printf; movq _var(%rip), %rax
+    let mut text_relocs = builder.reloc(1, 0, true, 2, true, macho::X86_64_RELOC_BRANCH);
+    text_relocs.extend(builder.reloc(6, 0, true, 2, true, macho::X86_64_RELOC_BRANCH));
+    text_relocs.extend(builder.reloc(13, 1, true, 2, true, macho::X86_64_RELOC_SIGNED));
+    // .quad _var; .quad __text
+    let mut data_relocs = builder.reloc(0, 1, false, 3, true, macho::X86_64_RELOC_UNSIGNED);
+    data_relocs.extend(builder.reloc(8, 1, false, 3, false, macho::X86_64_RELOC_UNSIGNED));
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x20,
+        offset: 0x200,
+        reloff: 0x240,
+        nreloc: 3,
+        ..Default::default()
+    };
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: 0x20,
+        size: 0x10,
+        offset: 0x220,
+        reloff: 0x260,
+        nreloc: 2,
+        ..Default::default()
+    };
+    let strings = b"\0_printf\0_var\0";
+    let mut symbols = builder.nlist(1, macho::N_UNDF | macho::N_EXT, 0, 0, 0);
+    symbols.extend(builder.nlist(9, macho::N_SECT | macho::N_EXT, 2, 0, 0x28));
+    builder
+        .segment(b"", 0, 0x30, 0x200, 0x30, 7, &[text, data])
+        .symtab(0x280, 2, 0x2a0, strings.len() as u32)
+        .data(0x200, &[0; 0x30])
+        .data(0x240, &text_relocs)
+        .data(0x260, &data_relocs)
+        .data(0x280, &symbols)
+        .data(0x2a0, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let xrefs = file.xrefs_to_symbols().unwrap();
+    assert_eq!(
+        xrefs.into_iter().collect::<Vec<_>>(),
+        [
+            (
+                SymbolIndex(0),
+                vec![(SectionIndex(1), 1), (SectionIndex(1), 6)]
+            ),
+            (
+                SymbolIndex(1),
+                vec![(SectionIndex(1), 13), (SectionIndex(2), 0)]
+            ),
+        ]
+    );
+
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.xrefs_to_symbols().unwrap().is_empty());
+}
+
+#[test]
+fn resolved_relocations_subtractor() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    // .quad _a - _b + 0x10
+    let mut relocs = builder.reloc(0, 1, false, 3, true, macho::X86_64_RELOC_SUBTRACTOR);
+    relocs.extend(builder.reloc(0, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED));
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        size: 8,
+        offset: 0x200,
+        reloff: 0x208,
+        nreloc: 2,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 8, 0x200, 8, 7, &[data])
+        .data(0x200, &0x10u64.to_le_bytes())
+        .data(0x208, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let data = file.section_by_name("__data").unwrap();
+
+    // The stored value is only added to the `X86_64_RELOC_UNSIGNED`.
+    let relocations = data.resolved_relocations().unwrap().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(
+        relocations[0].1.kind(),
+        RelocationKind::MachO {
+            value: macho::X86_64_RELOC_SUBTRACTOR,
+            relative: false,
+        }
+    );
+    assert_eq!(relocations[0].1.addend(), 0);
+    assert!(relocations[0].1.has_implicit_addend());
+    assert_eq!(relocations[1].1.kind(), RelocationKind::Absolute);
+    assert_eq!(relocations[1].1.addend(), 0x10);
+    assert!(!relocations[1].1.has_implicit_addend());
+}
+
+#[test]
+fn resolved_relocations_section() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    // leaq __data+8(%rip), %rax
+    let text_relocs = builder.reloc(3, 2, true, 2, false, macho::X86_64_RELOC_SIGNED);
+    // .quad __text+4
+    let data_relocs = builder.reloc(0, 1, false, 3, false, macho::X86_64_RELOC_UNSIGNED);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        addr: 0x100,
+        size: 8,
+        offset: 0x400,
+        reloff: 0x600,
+        nreloc: 1,
+        ..Default::default()
+    };
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: 0x200,
+        size: 8,
+        offset: 0x500,
+        reloff: 0x610,
+        nreloc: 1,
+        ..Default::default()
+    };
+    // The displacement is from the end of the instruction at 0x107 to 0x208.
+    let code = [0x48, 0x8d, 0x05, 0x01, 0x01, 0, 0, 0x90];
+    builder
+        .segment(b"", 0x100, 0x108, 0x400, 0x108, 7, &[text, data])
+        .data(0x400, &code)
+        .data(0x500, &0x104u64.to_le_bytes())
+        .data(0x600, &text_relocs)
+        .data(0x610, &data_relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    // S + A gives the target address, and S + A - P gives the stored value.
+    let text = file.section_by_name("__text").unwrap();
+    let relocations = text.resolved_relocations().unwrap().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    let (offset, relocation) = &relocations[0];
+    assert_eq!(
+        relocation.target(),
+        RelocationTarget::Section(SectionIndex(2))
+    );
+    assert!(!relocation.has_implicit_addend());
+    let target = file.relocation_target_address(&relocation.target());
+    assert_eq!(target, Ok(Some(0x200)));
+    let value = 0x200u64.wrapping_add(relocation.addend() as u64);
+    assert_eq!(value, 0x208 - 4);
+    assert_eq!(value.wrapping_sub(0x100 + offset), 0x101);
+
+    let data = file.section_by_name("__data").unwrap();
+    let relocations = data.resolved_relocations().unwrap().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    let (_, relocation) = &relocations[0];
+    assert_eq!(
+        relocation.target(),
+        RelocationTarget::Section(SectionIndex(1))
+    );
+    assert!(!relocation.has_implicit_addend());
+    let target = file.relocation_target_address(&relocation.target());
+    assert_eq!(target, Ok(Some(0x100)));
+    assert_eq!(relocation.addend(), 4);
+}
+
+#[test]
+fn symtab_sizes() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.nsyms(), 5);
+    assert_eq!(file.nsyms() as usize, file.symbols().count());
+    assert_eq!(file.strsize() as usize, STRINGS.len());
+    assert_eq!(file.strsize() as usize, file.string_table_data().len());
+}
+
+#[test]
+fn instruction_section_kind() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let code = Sect {
+        sectname: b"__hot_code",
+        segname: b"__TEXT",
+        size: 1,
+        offset: 0x200,
+        flags: macho::S_ATTR_SOME_INSTRUCTIONS,
+        ..Default::default()
+    };
+    let pure = Sect {
+        sectname: b"__mycode",
+        segname: b"__CODE",
+        size: 1,
+        offset: 0x201,
+        flags: macho::S_ATTR_PURE_INSTRUCTIONS,
+        ..Default::default()
+    };
+    let other = Sect {
+        sectname: b"__mydata",
+        segname: b"__CODE",
+        size: 1,
+        offset: 0x202,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 3, 0x200, 3, 7, &[code, pure, other])
+        .data(0x200, &[0xc3; 3]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let kinds = file.sections().map(|s| s.kind()).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [SectionKind::Text, SectionKind::Text, SectionKind::Unknown]
+    );
+}
+
+#[test]
+fn non_utf8_names() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let section = Sect {
+        sectname: b"__te\xfft",
+        segname: b"__TE\xffT",
+        size: 1,
+        offset: 0x200,
+        ..Default::default()
+    };
+    builder
+        .segment(b"__TE\xffT", 0, 0x1000, 0, 0x201, 5, &[section])
+        .data(0x200, &[0xc3]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let segment = file.segments().next().unwrap();
+    assert!(segment.name().is_err());
+    assert_eq!(segment.name_bytes(), b"__TE\xffT");
+
+    let section = file.sections().next().unwrap();
+    assert!(section.name().is_err());
+    assert!(section.segment_name().is_err());
+    assert_eq!(section.name_bytes(), b"__te\xfft");
+    assert_eq!(section.segment_name_bytes(), b"__TE\xffT");
+}
+
+#[test]
+fn slide() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let entry = TEXT_ADDR + file.entry();
+    let runtime_base = 0x1_0a00_0000;
+    let slide = file.slide(runtime_base);
+    assert_eq!(slide, runtime_base - TEXT_ADDR);
+
+    let runtime_entry = entry + slide;
+    assert_eq!(file.unslide(runtime_entry, slide), entry);
+    assert_eq!(file.unslide(runtime_base, slide), TEXT_ADDR);
+}
+
+#[test]
+fn swift_sections() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let mut sections = Vec::new();
+    let mut offset = 0x400;
+    for &name in &[
+        &b"__text"[..],
+        b"__swift5_typeref",
+        b"__swift5_fieldmd",
+        b"__swift5_types",
+        b"__swift5_protos",
+        b"__swift5_proto",
+    ] {
+        sections.push(Sect {
+            sectname: name,
+            segname: b"__TEXT",
+            addr: TEXT_ADDR + offset,
+            size: 4,
+            offset: offset as u32,
+            align: 2,
+            ..Default::default()
+        });
+        offset += 4;
+    }
+    builder.segment(b"__TEXT", TEXT_ADDR, 0x1000, 0, 0x1000, 5, &sections);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let swift = file.swift_sections();
+    let names = swift.iter().map(|&(name, _)| name).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "__swift5_typeref",
+            "__swift5_fieldmd",
+            "__swift5_types",
+            "__swift5_protos",
+            "__swift5_proto",
+        ]
+    );
+    for (name, section) in &swift {
+        assert_eq!(section.name(), Ok(*name));
+        assert_eq!(section.kind(), SectionKind::ReadOnlyData);
+        assert_eq!(section.size(), 4);
+    }
+}
+
+#[test]
+fn relocation_offsets() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    // .quad _var
+    let relocs = builder.reloc(8, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED);
+    let data_section = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: 0x20,
+        size: 16,
+        offset: 0x120,
+        reloff: 0x130,
+        nreloc: 1,
+        ..Default::default()
+    };
+    let mut bytes = vec![0x11; 8];
+    bytes.extend_from_slice(&0x1234_5678u64.to_le_bytes());
+    builder
+        .segment(b"", 0, 0x30, 0x100, 0x30, 7, &[data_section])
+        .data(0x120, &bytes)
+        .data(0x130, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let section = file.section_by_name("__data").unwrap();
+    let section_data = section.data().unwrap();
+
+    let (offset, _) = section.relocations().next().unwrap();
+    assert_eq!(offset, 8);
+    assert_eq!(section_data[offset as usize], 0x78);
+
+    let (address, _) = section.relocations_with_addresses().next().unwrap();
+    assert_eq!(address, 0x28);
+    assert_eq!(section_data[(address - section.address()) as usize], 0x78);
+
+    let (file_offset, _) = section.relocations_with_file_offsets().next().unwrap();
+    assert_eq!(file_offset, 0x128);
+    assert_eq!(data[file_offset as usize], 0x78);
+}
+
+#[test]
+fn relocation_range() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let mut relocs = builder.reloc(0, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED);
+    relocs.extend(builder.reloc(8, 1, false, 3, true, macho::X86_64_RELOC_UNSIGNED));
+    let data_section = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        size: 16,
+        offset: 0x200,
+        reloff: 0x210,
+        nreloc: 2,
+        ..Default::default()
+    };
+    let const_section = Sect {
+        sectname: b"__const",
+        segname: b"__DATA",
+        addr: 16,
+        size: 16,
+        offset: 0x220,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 32, 0x200, 0x30, 7, &[data_section, const_section])
+        .data(0x210, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let section = file.section_by_name("__data").unwrap();
+    assert_eq!(section.relocation_range(), Some((0x210, 2)));
+    let (reloff, nreloc) = section.relocation_range().unwrap();
+    let size = nreloc as usize * 8;
+    assert_eq!(&data[reloff as usize..][..size], &relocs[..]);
+
+    let section = file.section_by_name("__const").unwrap();
+    assert_eq!(section.relocation_range(), None);
+}
+
+#[test]
+fn try_relocations() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let relocs = builder.reloc(0, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED);
+    let valid = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        size: 8,
+        offset: 0x200,
+        reloff: 0x210,
+        nreloc: 1,
+        ..Default::default()
+    };
+    let invalid = Sect {
+        sectname: b"__const",
+        segname: b"__DATA",
+        addr: 8,
+        size: 8,
+        offset: 0x208,
+        reloff: 0x10_0000,
+        nreloc: 1,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 16, 0x200, 0x10, 7, &[valid, invalid])
+        .data(0x210, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let section = file.section_by_name("__data").unwrap();
+    assert_eq!(section.try_relocations().unwrap().count(), 1);
+    assert_eq!(section.relocations().count(), 1);
+
+    let section = file.section_by_name("__const").unwrap();
+    assert_eq!(
+        section.try_relocations().unwrap_err().to_string(),
+        "Invalid Mach-O relocations offset or number"
+    );
+    assert_eq!(section.relocations().count(), 0);
+}
+
+#[test]
+fn truncated_load_command() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let bss = Sect {
+        sectname: b"__bss",
+        segname: b"__DATA",
+        size: 0x10,
+        flags: macho::S_ZEROFILL,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 0x10, 0, 0, 3, &[bss])
+        .command(macho::LC_UUID, &[0x55; 16]);
+    let mut data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.load_commands_truncated());
+
+    // A `cmdsize` that extends past `sizeofcmds` is an error, even if it is
+    // within the file.
+    let mut invalid = data.clone();
+    invalid.extend_from_slice(&[0; 0x10]);
+    let uuid_offset = invalid.len() - 0x10 - 24;
+    invalid[uuid_offset + 4..uuid_offset + 8].copy_from_slice(&32u32.to_le_bytes());
+    let file = MachOFile64::<RunTimeEndian>::parse(&invalid).unwrap();
+    assert!(!file.load_commands_truncated());
+    assert!(file.mach_uuid().is_err());
+
+    // Cut the `LC_UUID` command in half.
+    data.truncate(data.len() - 12);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.load_commands_truncated());
+    assert_eq!(file.segments().count(), 1);
+    assert_eq!(file.section_by_name("__bss").unwrap().size(), 0x10);
+    assert_eq!(file.mach_uuid(), Ok(None));
+}
+
+#[test]
+fn section_segment() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let text = file.section_by_name("__text").unwrap();
+    let segment = text.segment().unwrap();
+    assert_eq!(segment.name(), Ok(Some("__TEXT")));
+    assert_eq!(segment.address(), TEXT_ADDR);
+
+    let bss = file.section_by_name("__bss").unwrap();
+    assert_eq!(bss.segment().unwrap().name(), Ok(Some("__DATA")));
+}
+
+#[test]
+fn section_raw_file_offset() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: 0x1000,
+        size: 0x10,
+        offset: 0x1000,
+        ..Default::default()
+    };
+    let bss = Sect {
+        sectname: b"__bss",
+        segname: b"__DATA",
+        addr: 0x1010,
+        size: 0x20,
+        offset: 0x1010,
+        flags: macho::S_ZEROFILL,
+        ..Default::default()
+    };
+    let rw = macho::VM_PROT_READ | macho::VM_PROT_WRITE;
+    builder
+        .segment(b"__DATA", 0x1000, 0x1000, 0x1000, 0x10, rw, &[data, bss])
+        .data(0x1000, &[0; 0x10]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let data = file.section_by_name("__data").unwrap();
+    assert_eq!(data.raw_file_offset(), 0x1000);
+    assert_eq!(data.file_range(), Some((0x1000, 0x10)));
+
+    let bss = file.section_by_name("__bss").unwrap();
+    assert_eq!(bss.raw_file_offset(), 0x1010);
+    assert_eq!(bss.file_range(), None);
+}
+
+#[test]
+fn lazy_bind_at() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.lazy_bind_at(0), Ok(None));
+
+    let mut lazy_bind = Vec::new();
+    // _printf in libSystem, at __DATA+0x10.
+    lazy_bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2, 0x10]);
+    lazy_bind.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | 1);
+    lazy_bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    lazy_bind.extend_from_slice(b"_printf\0");
+    lazy_bind.extend_from_slice(&[macho::BIND_OPCODE_DO_BIND, macho::BIND_OPCODE_DONE]);
+    let puts_offset = lazy_bind.len() as u32;
+    // _puts, weakly imported with a flat lookup, at __DATA+0x88.
+    lazy_bind.extend_from_slice(&[
+        macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2,
+        0x88,
+        0x01,
+    ]);
+    lazy_bind.push(macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM | 0xe);
+    lazy_bind.push(
+        macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM | macho::BIND_SYMBOL_FLAGS_WEAK_IMPORT,
+    );
+    lazy_bind.extend_from_slice(b"_puts\0");
+    lazy_bind.extend_from_slice(&[macho::BIND_OPCODE_DO_BIND, macho::BIND_OPCODE_DONE]);
+
+    let mut builder = executable();
+    let dyld_info = builder.u32s(&[0, 0, 0, 0, 0, 0, 0x2100, lazy_bind.len() as u32, 0, 0]);
+    builder
+        .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+        .data(0x2100, &lazy_bind);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let printf = file.lazy_bind_at(0).unwrap().unwrap();
+    assert_eq!(printf.name(), Ok("_printf"));
+    assert_eq!(printf.segment_index(), 2);
+    assert_eq!(printf.segment_offset(), 0x10);
+    assert_eq!(printf.library_ordinal(), 1);
+    assert_eq!(printf.bind_type(), macho::BIND_TYPE_POINTER);
+
+    let puts = file.lazy_bind_at(puts_offset).unwrap().unwrap();
+    assert_eq!(puts.name(), Ok("_puts"));
+    assert_eq!(puts.segment_index(), 2);
+    assert_eq!(puts.segment_offset(), 0x88);
+    assert_eq!(
+        puts.library_ordinal(),
+        i32::from(macho::BIND_SPECIAL_DYLIB_FLAT_LOOKUP)
+    );
+    assert_eq!(puts.flags(), macho::BIND_SYMBOL_FLAGS_WEAK_IMPORT);
+
+    // The offset of a `BIND_OPCODE_DONE` has no binding.
+    assert_eq!(file.lazy_bind_at(puts_offset - 1), Ok(None));
+    assert!(file.lazy_bind_at(lazy_bind.len() as u32 + 1).is_err());
+}
+
+#[test]
+fn weak_definitions() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.weak_definitions(), Ok(Vec::new()));
+
+    // Weak bindings as emitted for C++ inline functions and typeinfo.
+    let mut weak_bind = Vec::new();
+    weak_bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    weak_bind.extend_from_slice(b"__ZNKSt3__16vectorIiNS_9allocatorIiEEE4sizeEv\0");
+    weak_bind.push(macho::BIND_OPCODE_SET_TYPE_IMM | macho::BIND_TYPE_POINTER);
+    weak_bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2, 0x10]);
+    weak_bind.push(macho::BIND_OPCODE_DO_BIND);
+    weak_bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    weak_bind.extend_from_slice(b"__ZTI3Foo\0");
+    weak_bind.extend_from_slice(&[macho::BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB, 2, 0]);
+    // A strong definition that overrides weak definitions, which is not listed.
+    weak_bind.push(
+        macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM
+            | macho::BIND_SYMBOL_FLAGS_NON_WEAK_DEFINITION,
+    );
+    weak_bind.extend_from_slice(b"__Znwm\0");
+    weak_bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    weak_bind.extend_from_slice(b"__ZTI3Foo\0");
+    weak_bind.push(macho::BIND_OPCODE_DO_BIND);
+    weak_bind.push(macho::BIND_OPCODE_DONE);
+
+    let mut builder = executable();
+    let dyld_info = builder.u32s(&[0, 0, 0, 0, 0x2100, weak_bind.len() as u32, 0, 0, 0, 0]);
+    builder
+        .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+        .data(0x2100, &weak_bind);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.weak_definitions().unwrap(),
+        ["__ZNKSt3__16vectorIiNS_9allocatorIiEEE4sizeEv", "__ZTI3Foo"]
+    );
+}
+
+#[test]
+fn no_undefined_symbols() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.no_undefined_symbols());
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.no_undefined_symbols());
+}
+
+#[test]
+fn defined_functions() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let functions = file
+        .defined_functions()
+        .iter()
+        .map(|symbol| (symbol.name().unwrap(), symbol.address(), symbol.size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        functions,
+        [
+            ("_main", TEXT_ADDR + 0xf00, 0x20),
+            ("_start", TEXT_ADDR + 0xf00, 0x20),
+            ("_helper", TEXT_ADDR + 0xf20, 0x20),
+        ]
+    );
+}
+
+#[test]
+fn symbol_map_aliases() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x10,
+        offset: 0x100,
+        flags: macho::S_ATTR_PURE_INSTRUCTIONS,
+        ..Default::default()
+    };
+    let strings = b"\0_zeta\0_alpha\0_beta\0";
+    let mut symbols = builder.nlist(1, macho::N_SECT | macho::N_EXT, 1, 0, 0);
+    symbols.extend(builder.nlist(7, macho::N_SECT | macho::N_EXT, 1, 0, 0));
+    symbols.extend(builder.nlist(14, macho::N_SECT | macho::N_EXT, 1, 0, 8));
+    builder
+        .segment(b"", 0, 0x10, 0x100, 0x10, 7, &[text])
+        .symtab(0x200, 3, 0x240, strings.len() as u32)
+        .data(0x100, &[0x90; 0x10])
+        .data(0x200, &symbols)
+        .data(0x240, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    // Aliases are sorted by name, regardless of their order in the symbol table.
+    let map = file.symbol_map();
+    let symbols = map
+        .symbols()
+        .iter()
+        .map(|symbol| (symbol.name().unwrap(), symbol.address(), symbol.size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [("_alpha", 0, 8), ("_zeta", 0, 8), ("_beta", 8, 8)]
+    );
+}
+
+#[test]
+fn symbols_with_sizes() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let symbols = file.symbols_with_sizes();
+    assert_eq!(symbols.len(), file.symbols().count());
+    let symbols = symbols
+        .iter()
+        .take(4)
+        .map(|(index, symbol)| (index.0, symbol.name().unwrap(), symbol.size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [
+            (0, "_helper", 0x20),
+            (1, "_main", 0x20),
+            (2, "_start", 0x20),
+            (3, "_data", 0x10),
+        ]
+    );
+}
+
+#[test]
+fn debug_map() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let mut strings = vec![0];
+    let mut strx = |name: &[u8]| {
+        let offset = strings.len() as u32;
+        strings.extend_from_slice(name);
+        strings.push(0);
+        offset
+    };
+    let stabs: &[(u8, u32, u64)] = &[
+        // Ignored because it is not preceded by an `N_OSO`.
+        (macho::N_FUN, strx(b"_orphan"), 0x3000),
+        (macho::N_SO, strx(b"/src/"), 0),
+        (macho::N_SO, strx(b"main.c"), 0),
+        (macho::N_OSO, strx(b"/obj/main.o"), 0x5f00_0000),
+        (macho::N_BNSYM, 0, 0x1000),
+        (macho::N_FUN, strx(b"_main"), 0x1000),
+        (macho::N_FUN, 0, 0x20),
+        (macho::N_ENSYM, 0, 0x1000),
+        (macho::N_STSYM, strx(b"_counter"), 0x2000),
+        (macho::N_GSYM, strx(b"_global"), 0),
+        (macho::N_SO, 0, 0),
+        (macho::N_SO, strx(b"util.c"), 0),
+        (macho::N_OSO, strx(b"/obj/libutil.a(util.o)"), 0x5f00_0001),
+        (macho::N_FUN, strx(b"_helper"), 0x1020),
+        (macho::N_FUN, 0, 0x10),
+        (macho::N_SO, 0, 0),
+        (macho::N_SECT | macho::N_EXT, strx(b"_main"), 0x1000),
+        (macho::N_SECT | macho::N_EXT, strx(b"_global"), 0x2008),
+    ];
+    let mut symbols = Vec::new();
+    for &(n_type, n_strx, n_value) in stabs {
+        symbols.extend(builder.nlist(n_strx, n_type, 0, 0, n_value));
+    }
+    builder
+        .symtab(0x200, stabs.len() as u32, 0x400, strings.len() as u32)
+        .data(0x200, &symbols)
+        .data(0x400, &strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let map = file.debug_map().unwrap();
+    let objects = map
+        .objects()
+        .iter()
+        .map(|object| {
+            let symbols = object
+                .symbols()
+                .iter()
+                .map(|symbol| (symbol.name().unwrap(), symbol.address(), symbol.size()))
+                .collect::<Vec<_>>();
+            (object.path().unwrap(), object.timestamp(), symbols)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        objects,
+        [
+            (
+                "/obj/main.o",
+                0x5f00_0000,
+                vec![
+                    ("_main", 0x1000, 0x20),
+                    ("_counter", 0x2000, 0),
+                    ("_global", 0x2008, 0),
+                ]
+            ),
+            (
+                "/obj/libutil.a(util.o)",
+                0x5f00_0001,
+                vec![("_helper", 0x1020, 0x10)]
+            ),
+        ]
+    );
+
+    // A file without stabs has an empty debug map.
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.debug_map().unwrap().objects().is_empty());
+}
+
+#[test]
+fn symbols_in_section() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.section_by_name("__text").unwrap().index();
+    let symbols = file
+        .symbols_in_section(text)
+        .into_iter()
+        .map(|(index, symbol)| (index.0, symbol.name().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(symbols, [(0, "_helper"), (1, "_main"), (2, "_start")]);
+
+    let data = file.section_by_name("__data").unwrap().index();
+    assert_eq!(file.symbols_in_section(data).len(), 1);
+    let bss = file.section_by_name("__bss").unwrap().index();
+    assert!(file.symbols_in_section(bss).is_empty());
+}
+
+#[test]
+fn segment_data_by_name() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.segment_data_by_name("__TEXT").unwrap().unwrap();
+    assert_eq!(text, &data[..0x1000]);
+    let linkedit = file.segment_data_by_name("__LINKEDIT").unwrap().unwrap();
+    assert_eq!(linkedit, &data[0x2000..]);
+    assert_eq!(
+        file.segment_data_by_name("__PAGEZERO").unwrap(),
+        Some(&[][..])
+    );
+    assert_eq!(file.segment_data_by_name("__OBJC").unwrap(), None);
+
+    // A custom segment with no sections, containing a single blob.
+    let mut builder = executable();
+    let blob = b"custom segment data";
+    builder
+        .segment(
+            b"__CUSTOM",
+            TEXT_ADDR + 0x3000,
+            0x1000,
+            0x3000,
+            blob.len() as u64,
+            macho::VM_PROT_READ,
+            &[],
+        )
+        .data(0x3000, blob);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.segment_data_by_name("__CUSTOM"), Ok(Some(&blob[..])));
+    assert!(file.section_by_name("__CUSTOM").is_none());
+
+    // The file range is outside the file.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder.segment(b"__DATA", 0, 0x1000, 0x1000, 0x1000, 3, &[]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.segment_data_by_name("__DATA").is_err());
+}
+
+#[test]
+fn ppc_relocations() {
+    let mut builder = Builder::new(false, true, macho::CPU_TYPE_POWERPC, macho::MH_OBJECT);
+    // bl _func
+    let mut relocs = builder.u32s(&[4, 0x0000_01d3]);
+    // .long _var
+    relocs.extend(builder.u32s(&[8, 0x0000_0250]));
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 12,
+        offset: 0x100,
+        reloff: 0x110,
+        nreloc: 2,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 12, 0x100, 12, 7, &[text])
+        .data(0x110, &relocs);
+    let data = builder.build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.section_by_name("__text").unwrap();
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+
+    let (offset, branch) = &relocations[0];
+    assert_eq!(*offset, 4);
+    assert_eq!(
+        branch.kind(),
+        RelocationKind::MachO {
+            value: macho::PPC_RELOC_BR24,
+            relative: true,
+        }
+    );
+    assert_eq!(branch.size(), 32);
+    assert_eq!(branch.target(), RelocationTarget::Symbol(SymbolIndex(1)));
+
+    let (offset, absolute) = &relocations[1];
+    assert_eq!(*offset, 8);
+    assert_eq!(absolute.kind(), RelocationKind::Absolute);
+    assert_eq!(absolute.size(), 32);
+    assert_eq!(absolute.target(), RelocationTarget::Symbol(SymbolIndex(2)));
+
+    // Encoding the fields must give back the original words.
+    assert_eq!(
+        builder.reloc(4, 1, true, 2, true, macho::PPC_RELOC_BR24),
+        &relocs[..8]
+    );
+    assert_eq!(
+        builder.reloc(8, 2, false, 2, true, macho::PPC_RELOC_VANILLA),
+        &relocs[8..]
+    );
+}
+
+#[test]
+fn layout() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let mut dump = Vec::new();
+    for segment in file.layout().segments {
+        dump.push(format!(
+            "{} {:#x} {:#x} {:x?} {}/{}",
+            String::from_utf8_lossy(segment.name),
+            segment.address,
+            segment.size,
+            segment.file_range,
+            segment.initprot,
+            segment.maxprot,
+        ));
+        for section in segment.sections {
+            dump.push(format!(
+                "  {},{} {:#x} {:#x} {:x?} {} {:#x}",
+                String::from_utf8_lossy(section.segment_name),
+                String::from_utf8_lossy(section.name),
+                section.address,
+                section.size,
+                section.file_range,
+                section.align,
+                section.flags,
+            ));
+        }
+    }
+    assert_eq!(
+        dump,
+        [
+            "__PAGEZERO 0x0 0x100000000 (0, 0) 0/0",
+            "__TEXT 0x100000000 0x1000 (0, 1000) 5/5",
+            "  __TEXT,__text 0x100000f00 0x40 Some((f00, 40)) 16 0x80000400",
+            "  __TEXT,__cstring 0x100000f40 0x10 Some((f40, 10)) 1 0x2",
+            "__DATA 0x100001000 0x1000 (1000, 1000) 3/3",
+            "  __DATA,__data 0x100001000 0x10 Some((1000, 10)) 8 0x0",
+            "  __DATA,__bss 0x100001010 0x20 None 8 0x1",
+            "__LINKEDIT 0x100002000 0x1000 (2000, 78) 1/1",
+        ]
+    );
+}
+
+#[test]
+fn load_commands_matching() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let first = builder.u32s(&[1, 2]);
+    let second = builder.u32s(&[3, 4]);
+    builder
+        .command(macho::LC_RPATH, &first)
+        .command(macho::LC_UUID, &[0x42; 16])
+        .command(macho::LC_RPATH, &second);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let uuid = file.load_command(macho::LC_UUID).unwrap().unwrap();
+    assert_eq!(&uuid[8..], &[0x42; 16]);
+    assert_eq!(file.load_command(macho::LC_SYMTAB), Ok(None));
+
+    let rpaths = file.load_commands_matching(macho::LC_RPATH).unwrap();
+    assert_eq!(rpaths.len(), 2);
+    assert_eq!(&rpaths[0][8..], &first[..]);
+    assert_eq!(&rpaths[1][8..], &second[..]);
+}
+
+#[test]
+fn load_command_offsets() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let rpath = builder.u32s(&[1, 2]);
+    builder
+        .command(macho::LC_RPATH, &rpath)
+        .command(macho::LC_UUID, &[0x42; 16]);
+    let mut data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let mut commands = file.load_commands().unwrap();
+    let command = commands.next().unwrap().unwrap();
+    assert_eq!(command.cmd(), macho::LC_RPATH);
+    assert_eq!(command.file_offset(), 32);
+    let command = commands.next().unwrap().unwrap();
+    assert_eq!(command.cmd(), macho::LC_UUID);
+    assert_eq!(command.file_offset(), 48);
+    assert_eq!(command.data().len(), 24);
+    assert!(commands.next().unwrap().is_none());
+
+    // Patch the UUID in place.
+    let offset = command.file_offset() as usize + 8;
+    data[offset..][..16].copy_from_slice(&[0x24; 16]);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.mach_uuid(), Ok(Some([0x24; 16])));
+}
+
+#[test]
+fn data_const_section_kinds() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let sect = |segname, sectname, offset, flags| Sect {
+        sectname,
+        segname,
+        addr: TEXT_ADDR + offset,
+        size: 8,
+        offset: offset as u32,
+        align: 3,
+        flags,
+        ..Default::default()
+    };
+    builder
+        .segment(
+            b"__DATA_CONST",
+            TEXT_ADDR + 0x4000,
+            0x4000,
+            0x4000,
+            0x4000,
+            3,
+            &[
+                sect(
+                    b"__DATA_CONST",
+                    b"__got",
+                    0x4000,
+                    macho::S_NON_LAZY_SYMBOL_POINTERS,
+                ),
+                sect(b"__DATA_CONST", b"__const", 0x4008, 0),
+                sect(b"__DATA_CONST", b"__cfstring", 0x4010, 0),
+            ],
+        )
+        .segment(
+            b"__DATA_DIRTY",
+            TEXT_ADDR + 0x8000,
+            0x4000,
+            0x8000,
+            0x4000,
+            3,
+            &[
+                sect(b"__DATA_DIRTY", b"__data", 0x8000, 0),
+                sect(b"__DATA_DIRTY", b"__bss", 0x8008, macho::S_ZEROFILL),
+                sect(b"__DATA_DIRTY", b"__common", 0x8010, macho::S_ZEROFILL),
+            ],
+        )
+        .data(0xbfff, &[0]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let kinds = file
+        .sections()
+        .map(|section| (section.name().unwrap().to_string(), section.kind()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            ("__got".to_string(), SectionKind::ReadOnlyData),
+            ("__const".to_string(), SectionKind::ReadOnlyData),
+            ("__cfstring".to_string(), SectionKind::ReadOnlyData),
+            ("__data".to_string(), SectionKind::Data),
+            ("__bss".to_string(), SectionKind::UninitializedData),
+            ("__common".to_string(), SectionKind::Common),
+        ]
+    );
+}
+
+#[test]
+fn try_symbols() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x10,
+        offset: 0x200,
+        ..Default::default()
+    };
+    let strings = b"\0_good\0_bad\xff\0";
+    // A stab for the source file, which is skipped.
+    let mut symbols = builder.nlist(1, macho::N_SO, 0, 0, 0);
+    symbols.extend(builder.nlist(1, macho::N_SECT | macho::N_EXT, 1, 0, 0));
+    // Name offset past the end of the string table.
+    symbols.extend(builder.nlist(0x100, macho::N_SECT | macho::N_EXT, 1, 0, 4));
+    // Non UTF-8 name.
+    symbols.extend(builder.nlist(7, macho::N_SECT | macho::N_EXT, 1, 0, 8));
+    // Section index past the end of the section table.
+    symbols.extend(builder.nlist(1, macho::N_SECT | macho::N_EXT, 2, 0, 12));
+    let symoff = 0x300;
+    let stroff = symoff + symbols.len() as u32;
+    builder
+        .segment(b"", 0, 0x10, 0x200, 0x10, 7, &[text])
+        .symtab(symoff, 5, stroff, strings.len() as u32)
+        .data(symoff as usize, &symbols)
+        .data(stroff as usize, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    // The plain iterator doesn't report any problems.
+    assert_eq!(file.symbols().count(), 4);
+
+    let symbols = file.try_symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 4);
+    let (index, symbol) = symbols[0].as_ref().unwrap();
+    assert_eq!(*index, SymbolIndex(1));
+    assert_eq!(symbol.name(), Some("_good"));
+    assert!(symbols[1].is_err());
+    assert!(symbols[2].is_err());
+    assert!(symbols[3].is_err());
+}
+
+#[test]
+fn resolve_address() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let info = file.resolve_address(TEXT_ADDR + 0xf24).unwrap();
+    assert_eq!(info.segment_name, b"__TEXT");
+    assert_eq!(info.section_name, b"__text");
+    assert_eq!(info.section_index, SectionIndex(1));
+    assert_eq!(info.offset, 0x24);
+
+    let info = file.resolve_address(TEXT_ADDR + 0x102f).unwrap();
+    assert_eq!(info.segment_name, b"__DATA");
+    assert_eq!(info.section_name, b"__bss");
+    assert_eq!(info.offset, 0x1f);
+
+    // Unmapped, or in a segment but not in a section.
+    assert_eq!(file.resolve_address(0), None);
+    assert_eq!(file.resolve_address(TEXT_ADDR + 0xf50), None);
+    assert_eq!(file.resolve_address(TEXT_ADDR + 0x1030), None);
+}
+
+/// Build a universal binary containing the given files at 0x1000 byte intervals.
+fn fat(slices: &[(u32, &[u8])]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&macho::FAT_MAGIC.to_be_bytes());
+    data.extend_from_slice(&(slices.len() as u32).to_be_bytes());
+    for (i, (cputype, slice)) in slices.iter().enumerate() {
+        let offset = 0x1000 * (i as u32 + 1);
+        for &val in &[*cputype, 0, offset, slice.len() as u32, 12] {
+            data.extend_from_slice(&val.to_be_bytes());
+        }
+    }
+    for (i, (_, slice)) in slices.iter().enumerate() {
+        data.resize(0x1000 * (i + 1), 0);
+        data.extend_from_slice(slice);
+    }
+    data
+}
+
+#[test]
+fn fat_build_info() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let build_version = builder.u32s(&[macho::PLATFORM_MACOS, 0x000a_0e00, 0x000a_0f00, 0]);
+    builder
+        .command(macho::LC_BUILD_VERSION, &build_version)
+        .command(macho::LC_UUID, &[0x11; 16]);
+    let x86_64 = builder.build();
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let version_min = builder.u32s(&[0x000c_0000, 0x000d_0000]);
+    builder
+        .command(macho::LC_UUID, &[0x22; 16])
+        .command(macho::LC_VERSION_MIN_IPHONEOS, &version_min);
+    let arm64 = builder.build();
+
+    let data = fat(&[
+        (macho::CPU_TYPE_X86_64, &x86_64),
+        (macho::CPU_TYPE_ARM64, &arm64),
+        (macho::CPU_TYPE_X86, &[0; 16]),
+    ]);
+    let fat = MachOFatFile::parse(&data).unwrap();
+    let info = fat.build_info();
+    assert_eq!(
+        info,
+        [
+            (
+                Architecture::X86_64,
+                Some(MachOBuildVersion {
+                    platform: macho::PLATFORM_MACOS,
+                    minos: 0x000a_0e00,
+                    sdk: 0x000a_0f00,
+                }),
+                Some([0x11; 16]),
+            ),
+            (
+                Architecture::Aarch64(Aarch64Architecture::Aarch64),
+                Some(MachOBuildVersion {
+                    platform: macho::PLATFORM_IOS,
+                    minos: 0x000c_0000,
+                    sdk: 0x000d_0000,
+                }),
+                Some([0x22; 16]),
+            ),
+            (Architecture::I386, None, None),
+        ]
+    );
+
+    assert!(MachOFatFile::parse(&x86_64).is_err());
+}
+
+#[test]
+fn indirect_symbols() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let stubs = Sect {
+        sectname: b"__stubs",
+        segname: b"__TEXT",
+        addr: TEXT_ADDR + 0xf00,
+        size: 12,
+        offset: 0xf00,
+        flags: macho::S_SYMBOL_STUBS | macho::S_ATTR_PURE_INSTRUCTIONS,
+        reserved1: 0,
+        reserved2: 6,
+        ..Default::default()
+    };
+    let got = Sect {
+        sectname: b"__got",
+        segname: b"__DATA_CONST",
+        addr: TEXT_ADDR + 0x1000,
+        size: 24,
+        offset: 0x1000,
+        flags: macho::S_NON_LAZY_SYMBOL_POINTERS,
+        reserved1: 2,
+        ..Default::default()
+    };
+    let strings = b"\0_malloc\0_free\0";
+    let mut symbols = builder.nlist(1, macho::N_UNDF | macho::N_EXT, 0, 0x100, 0);
+    symbols.extend(builder.nlist(9, macho::N_UNDF | macho::N_EXT, 0, 0x100, 0));
+    let indirect = builder.u32s(&[
+        1,
+        0,
+        0,
+        macho::INDIRECT_SYMBOL_LOCAL,
+        macho::INDIRECT_SYMBOL_LOCAL | macho::INDIRECT_SYMBOL_ABS,
+    ]);
+    let mut dysymtab = [0; 18];
+    dysymtab[12] = 0x2040;
+    dysymtab[13] = 5;
+    let dysymtab = builder.u32s(&dysymtab);
+    builder
+        .segment(b"__TEXT", TEXT_ADDR, 0x1000, 0, 0x1000, 5, &[stubs])
+        .segment(
+            b"__DATA_CONST",
+            TEXT_ADDR + 0x1000,
+            0x1000,
+            0x1000,
+            0x1000,
+            3,
+            &[got],
+        )
+        .symtab(0x2000, 2, 0x2020, strings.len() as u32)
+        .command(macho::LC_DYSYMTAB, &dysymtab)
+        .data(0x2000, &symbols)
+        .data(0x2020, strings)
+        .data(0x2040, &indirect);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let label = |name| {
+        file.section_by_name(name)
+            .unwrap()
+            .indirect_symbols()
+            .unwrap()
+            .map(|(address, index)| {
+                let name = index.map(|index| file.symbol_by_index(index).unwrap().name().unwrap());
+                (address, name)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        label("__stubs"),
+        [
+            (TEXT_ADDR + 0xf00, Some("_free")),
+            (TEXT_ADDR + 0xf06, Some("_malloc")),
+        ]
+    );
+    assert_eq!(
+        label("__got"),
+        [
+            (TEXT_ADDR + 0x1000, Some("_malloc")),
+            (TEXT_ADDR + 0x1008, None),
+            (TEXT_ADDR + 0x1010, None),
+        ]
+    );
+}
+
+#[test]
+fn entry_source() {
+    let mut thread_state = [0u64; 21];
+    // rip
+    thread_state[16] = TEXT_ADDR + 0xf20;
+    let mut unixthread = Vec::new();
+    unixthread.extend_from_slice(&4u32.to_le_bytes());
+    unixthread.extend_from_slice(&42u32.to_le_bytes());
+    for reg in &thread_state {
+        unixthread.extend_from_slice(&reg.to_le_bytes());
+    }
+
+    // Both commands are present, so `LC_MAIN` is used.
+    let mut builder = executable();
+    builder.command(macho::LC_UNIXTHREAD, &unixthread);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entry_source(), Some(MachOEntrySource::Main));
+    assert_eq!(file.entry(), 0xf00);
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder.command(macho::LC_UNIXTHREAD, &unixthread);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entry_source(), Some(MachOEntrySource::UnixThread));
+    assert_eq!(file.entry_address(), Some(TEXT_ADDR + 0xf20));
+    // `entry` only returns the `LC_MAIN` file offset.
+    assert_eq!(file.entry(), 0);
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entry_source(), None);
+    assert_eq!(file.entry(), 0);
+}
+
+#[test]
+fn is_stripped() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.is_stripped());
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.is_stripped());
+
+    // Only stabs remain.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let strings = b"\0main.c\0";
+    let symbols = builder.nlist(1, macho::N_SO, 0, 0, 0);
+    builder
+        .symtab(0x200, 1, 0x210, strings.len() as u32)
+        .data(0x200, &symbols)
+        .data(0x210, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.nsyms(), 1);
+    assert!(file.is_stripped());
+}
+
+#[test]
+fn is_loadable() {
+    let mut builder = executable();
+    let mut dylinker = builder.u32s(&[12]);
+    dylinker.extend_from_slice(b"/usr/lib/dyld\0");
+    builder.command(macho::LC_LOAD_DYLINKER, &dylinker);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.is_loadable().unwrap());
+
+    // An executable without a dylinker is not loadable.
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.is_loadable().unwrap());
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x10,
+        offset: 0x100,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 0x10, 0x100, 0x10, 7, &[text])
+        .symtab(0x110, 0, 0x110, 0)
+        .data(0x100, &[0x90; 0x10]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.is_loadable().unwrap());
+}
+
+#[test]
+fn symseg() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.symseg(), Ok(None));
+
+    let mut builder = Builder::new(false, true, macho::CPU_TYPE_MC680X0, macho::MH_EXECUTE);
+    let symseg = builder.u32s(&[0x200, 0x10]);
+    builder
+        .command(macho::LC_SYMSEG, &symseg)
+        .data(0x200, &[0; 0x10]);
+    let data = builder.build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.symseg(), Ok(Some((0x200, 0x10))));
+
+    // The symbol segment extends past the end of the file.
+    let mut builder = Builder::new(false, true, macho::CPU_TYPE_MC680X0, macho::MH_EXECUTE);
+    let symseg = builder.u32s(&[0x200, 0x20]);
+    builder
+        .command(macho::LC_SYMSEG, &symseg)
+        .data(0x200, &[0; 0x10]);
+    let data = builder.build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.symseg().unwrap_err().to_string(),
+        "Invalid Mach-O symbol segment offset or size"
+    );
+
+    // The command is too small.
+    let mut builder = Builder::new(false, true, macho::CPU_TYPE_MC680X0, macho::MH_EXECUTE);
+    let symseg = builder.u32s(&[0x200]);
+    builder.command(macho::LC_SYMSEG, &symseg);
+    let data = builder.build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.symseg().is_err());
+}
+
+#[test]
+fn dyld_environment() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let mut dylinker = builder.u32s(&[12]);
+    dylinker.extend_from_slice(b"/usr/lib/dyld\0");
+    let mut environment = builder.u32s(&[12]);
+    environment.extend_from_slice(b"DYLD_FRAMEWORK_PATH=/tmp/frameworks\0");
+    builder
+        .command(macho::LC_LOAD_DYLINKER, &dylinker)
+        .command(macho::LC_DYLD_ENVIRONMENT, &environment);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.dyld_environment(),
+        Ok(vec!["DYLD_FRAMEWORK_PATH=/tmp/frameworks"])
+    );
+
+    // The string offset is past the end of the command.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let mut environment = builder.u32s(&[0x100]);
+    environment.extend_from_slice(b"DYLD_INSERT_LIBRARIES=x\0");
+    builder.command(macho::LC_DYLD_ENVIRONMENT, &environment);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.dyld_environment().is_err());
+}
+
+#[test]
+fn parse_with_options() {
+    let limits = ParseOptions {
+        max_sections: Some(16),
+        max_commands: Some(16),
+    };
+    let data = executable().build();
+    assert!(MachOFile64::<RunTimeEndian>::parse_with_options(&data, limits).is_ok());
+
+    // A segment claiming an absurd number of sections.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    builder.segment(b"", 0, 0, 0, 0, 7, &[]);
+    let mut data = builder.build();
+    // `nsects` in the `SegmentCommand64`.
+    data[32 + 64..32 + 68].copy_from_slice(&0x0fff_ffffu32.to_le_bytes());
+    let err = MachOFile64::<RunTimeEndian>::parse_with_options(&data, limits).unwrap_err();
+    assert_eq!(err.to_string(), "Too many Mach-O sections");
+
+    // Too many load commands.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    for _ in 0..17 {
+        builder.command(macho::LC_UUID, &[0; 16]);
+    }
+    let data = builder.build();
+    assert!(MachOFile64::<RunTimeEndian>::parse(&data).is_ok());
+    let err = MachOFile64::<RunTimeEndian>::parse_with_options(&data, limits).unwrap_err();
+    assert_eq!(err.to_string(), "Too many Mach-O load commands");
+}
+
+#[test]
+fn bytes_checked() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let section = file.section_by_name("__cstring").unwrap();
+    let bytes = section.bytes_checked().unwrap();
+    assert_eq!(bytes.0, section.data().unwrap());
+    assert_eq!(bytes.read_string_at(6), Ok(&b"world"[..]));
+
+    let segment = file.segments().nth(2).unwrap();
+    let bytes = segment.bytes_checked().unwrap();
+    assert_eq!(bytes.0, segment.data().unwrap());
+    let word = bytes.read_at::<U32<LittleEndian>>(0).unwrap();
+    assert_eq!(word.get(LittleEndian), 0x1111_1111);
+}
+
+#[test]
+fn image_base() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.image_base(), TEXT_ADDR);
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_DYLIB);
+    let rx = macho::VM_PROT_READ | macho::VM_PROT_EXECUTE;
+    let rw = macho::VM_PROT_READ | macho::VM_PROT_WRITE;
+    builder
+        .segment(b"__TEXT", 0, 0x1000, 0, 0x1000, rx, &[])
+        .segment(b"__DATA", 0x1000, 0x1000, 0x1000, 0x1000, rw, &[])
+        .data(0x1000, &[0; 0x1000]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.image_base(), 0);
+    assert_eq!(file.slide(0x1_0400_0000), 0x1_0400_0000);
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.image_base(), 0);
+}
+
+#[test]
+fn sections_by_address() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let sect = |sectname, segname, addr| Sect {
+        sectname,
+        segname,
+        addr,
+        ..Default::default()
+    };
+    let rx = macho::VM_PROT_READ | macho::VM_PROT_EXECUTE;
+    let rw = macho::VM_PROT_READ | macho::VM_PROT_WRITE;
+    builder
+        .segment(
+            b"__DATA",
+            0x2000,
+            0x1000,
+            0,
+            0,
+            rw,
+            &[
+                sect(b"__bss", b"__DATA", 0x2100),
+                sect(b"__data", b"__DATA", 0x2000),
+            ],
+        )
+        .segment(
+            b"__TEXT",
+            0x1000,
+            0x1000,
+            0,
+            0,
+            rx,
+            &[
+                sect(b"__text", b"__TEXT", 0x1000),
+                sect(b"__empty", b"__TEXT", 0x1800),
+                sect(b"__const", b"__TEXT", 0x1800),
+            ],
+        );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let names = |sections: Vec<MachOSection64<RunTimeEndian>>| {
+        sections
+            .iter()
+            .map(|section| section.name().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(file.sections().collect()),
+        ["__bss", "__data", "__text", "__empty", "__const"]
+    );
+    assert_eq!(
+        names(file.sections_by_address()),
+        ["__text", "__empty", "__const", "__data", "__bss"]
+    );
+}
+
+#[test]
+fn section_by_name() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let sections = [
+        (&b"__text"[..], &b"__TEXT"[..]),
+        (b"__debug_info", b"__DWARF"),
+        (b"_debug_line", b"__DWARF"),
+        (b"\xff\xfe", b"__DATA"),
+        (b"__data", b"__DATA"),
+        (b"0123456789abcdef", b"__DATA"),
+    ];
+    let sections = sections
+        .iter()
+        .map(|&(sectname, segname)| Sect {
+            sectname,
+            segname,
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    builder.segment(b"", 0, 0, 0, 0, 0, &sections);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    for &name in &[
+        "__text",
+        ".text",
+        "__debug_info",
+        ".debug_info",
+        "_debug_line",
+        ".debug_line",
+        "\u{fffd}",
+        "__data",
+        "data",
+        "0123456789abcdef",
+        "0123456789abcde",
+        "",
+        ".",
+    ] {
+        // Check against a lookup using the UTF-8 names.
+        let expected = file.sections().find(|section| match section.name() {
+            Ok(section_name) => {
+                section_name == name
+                    || (name.starts_with('.')
+                        && section_name.starts_with("__")
+                        && section_name[2..] == name[1..])
+            }
+            Err(_) => false,
+        });
+        assert_eq!(
+            file.section_by_name(name).map(|section| section.index()),
+            expected.map(|section| section.index()),
+            "{}",
+            name
+        );
+    }
+    assert_eq!(file.section_by_name(".debug_info").unwrap().index().0, 2);
+    assert_eq!(
+        file.section_by_name("0123456789abcdef").unwrap().index().0,
+        6
+    );
+    assert!(file.section_by_name(".debug_line").is_none());
+}
+
+#[test]
+fn section_data_range() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let section = file.section_by_name("__cstring").unwrap();
+    let address = TEXT_ADDR + 0xf40;
+    assert_eq!(section.data_range(address, 5), Ok(Some(&b"hello"[..])));
+    assert_eq!(section.data_range(address + 6, 6), Ok(Some(&b"world\0"[..])));
+    assert_eq!(section.data_range(address + 0x10, 0), Ok(Some(&[][..])));
+    assert_eq!(section.data_range(address + 0xc, 5), Ok(None));
+    assert_eq!(section.data_range(address - 1, 2), Ok(None));
+    assert_eq!(section.data_range(address + 0x20, 1), Ok(None));
+}
+
+#[test]
+fn read_at_address() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let word = file
+        .read_at_address::<U32<LittleEndian>>(TEXT_ADDR + 0x1000)
+        .unwrap()
+        .unwrap();
+    assert_eq!(word.get(LittleEndian), 0x1111_1111);
+    let word = file
+        .read_at_address::<U32<LittleEndian>>(TEXT_ADDR + 0xf40)
+        .unwrap()
+        .unwrap();
+    assert_eq!(word.get(LittleEndian), 0x6c6c_6568);
+
+    // `__bss` is within the file data of `__DATA` here.
+    assert_eq!(
+        file.read_at_address::<U32<LittleEndian>>(TEXT_ADDR + 0x1010),
+        Ok(Some(&U32::new(LittleEndian, 0)))
+    );
+
+    // Unmapped: `__PAGEZERO`, and past all segments.
+    assert_eq!(file.read_at_address::<U32<LittleEndian>>(0x1000), Ok(None));
+    assert_eq!(
+        file.read_at_address::<U32<LittleEndian>>(TEXT_ADDR + 0x10_0000),
+        Ok(None)
+    );
+
+    // Truncated at the end of the segment.
+    let end = TEXT_ADDR + 0x1000 - 2;
+    assert_eq!(
+        file.read_at_address::<U32<LittleEndian>>(end)
+            .unwrap_err()
+            .to_string(),
+        "Invalid Mach-O data size or alignment at address"
+    );
+}
+
+#[test]
+fn dysymtab_relocations() {
+    let mut builder = Builder::new(false, false, macho::CPU_TYPE_X86, macho::MH_EXECUTE);
+    // A pointer to `_printf` in `__DATA`.
+    let extrel = builder.reloc(0x1010, 0, false, 2, true, macho::GENERIC_RELOC_VANILLA);
+    // A pointer to `__text` in `__DATA`, which must be slid.
+    let mut locrel = builder.reloc(0x1000, 1, false, 2, false, macho::GENERIC_RELOC_VANILLA);
+    locrel.extend(builder.reloc(0x1004, 1, false, 2, false, macho::GENERIC_RELOC_VANILLA));
+    let mut dysymtab = [0; 18];
+    dysymtab[14] = 0x2000;
+    dysymtab[15] = 1;
+    dysymtab[16] = 0x2008;
+    dysymtab[17] = 2;
+    let dysymtab = builder.u32s(&dysymtab);
+    builder
+        .command(macho::LC_DYSYMTAB, &dysymtab)
+        .data(0x2000, &extrel)
+        .data(0x2008, &locrel);
+    let data = builder.build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+
+    let external = file.external_relocations().unwrap().collect::<Vec<_>>();
+    assert_eq!(external.len(), 1);
+    assert_eq!(external[0].0, 0x1010);
+    assert_eq!(external[0].1.kind(), RelocationKind::Absolute);
+    assert_eq!(external[0].1.size(), 32);
+    assert_eq!(
+        external[0].1.target(),
+        RelocationTarget::Symbol(SymbolIndex(0))
+    );
+
+    let local = file.local_relocations().unwrap().collect::<Vec<_>>();
+    assert_eq!(local.len(), 2);
+    assert_eq!(local[0].0, 0x1000);
+    assert_eq!(local[1].0, 0x1004);
+    assert_eq!(
+        local[1].1.target(),
+        RelocationTarget::Section(SectionIndex(1))
+    );
+
+    assert_eq!(file.relocation_count(), 3);
+
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.external_relocations().unwrap().count(), 0);
+    assert_eq!(file.local_relocations().unwrap().count(), 0);
+}
+
+#[test]
+fn dysymtab() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.dysymtab().unwrap().is_none());
+
+    let mut builder = executable();
+    // One local symbol, then three external defined symbols, then one undefined symbol.
+    let mut dysymtab = [0; 18];
+    dysymtab[1] = 1;
+    dysymtab[2] = 1;
+    dysymtab[3] = 3;
+    dysymtab[4] = 4;
+    dysymtab[5] = 1;
+    let dysymtab = builder.u32s(&dysymtab);
+    builder.command(macho::LC_DYSYMTAB, &dysymtab);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let endian = RunTimeEndian::Little;
+    let dysymtab = file.dysymtab().unwrap().unwrap();
+    assert_eq!(dysymtab.nlocalsym.get(endian), 1);
+    assert_eq!(dysymtab.iextdefsym.get(endian), 1);
+    assert_eq!(dysymtab.nextdefsym.get(endian), 3);
+    assert_eq!(dysymtab.iundefsym.get(endian), 4);
+    assert_eq!(dysymtab.nundefsym.get(endian), 1);
+}
+
+#[test]
+fn relocation_count() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.relocation_count(), 0);
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let mut relocs = builder.reloc(3, 0, true, 2, true, macho::X86_64_RELOC_BRANCH);
+    relocs.extend(builder.reloc(8, 0, true, 2, true, macho::X86_64_RELOC_SIGNED));
+    let data_reloc = builder.reloc(0, 1, false, 3, false, macho::X86_64_RELOC_UNSIGNED);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x10,
+        offset: 0x100,
+        reloff: 0x200,
+        nreloc: 2,
+        ..Default::default()
+    };
+    let data = Sect {
+        sectname: b"__data",
+        segname: b"__DATA",
+        addr: 0x10,
+        size: 8,
+        offset: 0x110,
+        reloff: 0x210,
+        nreloc: 1,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 0x18, 0x100, 0x18, 7, &[text, data])
+        .data(0x100, &[0; 0x18])
+        .data(0x200, &relocs)
+        .data(0x210, &data_reloc);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let total: usize = file
+        .sections()
+        .map(|section| section.relocations().count())
+        .sum();
+    assert_eq!(total, 3);
+    assert_eq!(file.relocation_count(), total as u32);
+}
+
+#[test]
+fn symbol_section() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    // `_main`
+    let section = file.symbol_section(SymbolIndex(1)).unwrap().unwrap();
+    assert_eq!(section.name(), Ok("__text"));
+    // `_data`
+    let section = file.symbol_section(SymbolIndex(3)).unwrap().unwrap();
+    assert_eq!(section.name(), Ok("__data"));
+    assert_eq!(section.data().unwrap(), &[0x11; 0x10][..]);
+    // `_printf` is undefined.
+    assert!(file.symbol_section(SymbolIndex(4)).unwrap().is_none());
+    assert!(file.symbol_section(SymbolIndex(5)).is_err());
+}
+
+/// Encode a code signing blob with the given magic.
+fn cs_blob(magic: u32, payload: &[u8]) -> Vec<u8> {
+    let mut blob = Vec::new();
+    blob.extend_from_slice(&magic.to_be_bytes());
+    blob.extend_from_slice(&(8 + payload.len() as u32).to_be_bytes());
+    blob.extend_from_slice(payload);
+    blob
+}
+
+/// Encode an embedded signature `SuperBlob` containing the given `(slot, blob)` pairs.
+fn cs_super_blob(blobs: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut offset = 12 + 8 * blobs.len();
+    let mut index = Vec::new();
+    let mut data = Vec::new();
+    for (slot, blob) in blobs {
+        index.extend_from_slice(&slot.to_be_bytes());
+        index.extend_from_slice(&(offset as u32).to_be_bytes());
+        data.extend_from_slice(blob);
+        data.resize((data.len() + 3) & !3, 0);
+        offset = 12 + 8 * blobs.len() + data.len();
+    }
+    let mut payload = (blobs.len() as u32).to_be_bytes().to_vec();
+    payload.extend_from_slice(&index);
+    payload.extend_from_slice(&data);
+    cs_blob(macho::CSMAGIC_EMBEDDED_SIGNATURE, &payload)
+}
+
+/// Add an `LC_CODE_SIGNATURE` command for the given signature data at file offset 0x3000.
+fn sign(builder: &mut Builder, signature: &[u8]) {
+    let command = builder.u32s(&[0x3000, signature.len() as u32]);
+    builder
+        .command(macho::LC_CODE_SIGNATURE, &command)
+        .data(0x3000, signature);
+}
+
+#[test]
+fn mapped_file_size() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.mapped_file_size(), data.len() as u64);
+
+    // The signature is past the end of `__LINKEDIT`.
+    let signature = cs_super_blob(&[(
+        macho::CSSLOT_CODEDIRECTORY,
+        cs_blob(macho::CSMAGIC_CODEDIRECTORY, &[0; 5]),
+    )]);
+    let mut builder = executable();
+    sign(&mut builder, &signature);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let linkedit = file.segments().last().unwrap();
+    assert!(linkedit.file_range().0 + linkedit.file_range().1 < 0x3000);
+    assert_eq!(file.mapped_file_size(), data.len() as u64);
+
+    // Trailing data.
+    let mut trailing = data.clone();
+    trailing.extend_from_slice(&[0; 0x10]);
+    let file = MachOFile64::<RunTimeEndian>::parse(&trailing).unwrap();
+    assert_eq!(file.mapped_file_size() + 0x10, trailing.len() as u64);
+
+    // Truncated.
+    let file = MachOFile64::<RunTimeEndian>::parse(&data[..0x3000]).unwrap();
+    assert_eq!(file.mapped_file_size(), data.len() as u64);
+}
+
+#[test]
+fn code_signature() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.is_signed());
+    assert!(file.code_signature().unwrap().is_none());
+
+    let requirements = cs_blob(macho::CSMAGIC_REQUIREMENTS, &[0, 0, 0, 0]);
+    let signature = cs_super_blob(&[
+        (
+            macho::CSSLOT_CODEDIRECTORY,
+            cs_blob(macho::CSMAGIC_CODEDIRECTORY, &[0; 5]),
+        ),
+        (macho::CSSLOT_REQUIREMENTS, requirements.clone()),
+    ]);
+    let mut builder = executable();
+    sign(&mut builder, &signature);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.is_signed());
+    let signature: MachOCodeSignature = file.code_signature().unwrap().unwrap();
+    assert_eq!(
+        signature.slots().collect::<Vec<_>>(),
+        [macho::CSSLOT_CODEDIRECTORY, macho::CSSLOT_REQUIREMENTS]
+    );
+    assert_eq!(
+        signature.blob(macho::CSSLOT_REQUIREMENTS).unwrap(),
+        Some(&requirements[..])
+    );
+    assert_eq!(signature.blob(macho::CSSLOT_ENTITLEMENTS).unwrap(), None);
+
+    // A signature that isn't a SuperBlob.
+    let mut builder = executable();
+    sign(&mut builder, &requirements);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.is_signed());
+    assert!(file.code_signature().is_err());
+}
+
+/// Encode a `CodeDirectory` blob with no hash slots.
+fn cs_code_directory(version: u32, identifier: &str, team_id: Option<&str>) -> Vec<u8> {
+    let header_size = if version >= macho::CS_SUPPORTSTEAMID {
+        52
+    } else {
+        44
+    };
+    let ident_offset = header_size;
+    let team_offset = match team_id {
+        Some(_) => ident_offset + identifier.len() as u32 + 1,
+        None => 0,
+    };
+    let mut payload = Vec::new();
+    for &val in &[
+        version,
+        0,
+        header_size,
+        ident_offset,
+        0,
+        0,
+        0,
+        0x0220_0c00,
+        0,
+    ] {
+        payload.extend_from_slice(&val.to_be_bytes());
+    }
+    if version >= macho::CS_SUPPORTSTEAMID {
+        payload.extend_from_slice(&0u32.to_be_bytes());
+        payload.extend_from_slice(&team_offset.to_be_bytes());
+    }
+    payload.extend_from_slice(identifier.as_bytes());
+    payload.push(0);
+    if let Some(team_id) = team_id {
+        payload.extend_from_slice(team_id.as_bytes());
+        payload.push(0);
+    }
+    cs_blob(macho::CSMAGIC_CODEDIRECTORY, &payload)
+}
+
+#[test]
+fn signing_identifier() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.signing_identifier().unwrap(), None);
+    assert_eq!(file.team_id().unwrap(), None);
+
+    let code_directory = cs_code_directory(0x20400, "com.example.hello", Some("ABCDE12345"));
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[(macho::CSSLOT_CODEDIRECTORY, code_directory)]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.signing_identifier().unwrap(),
+        Some("com.example.hello")
+    );
+    assert_eq!(file.team_id().unwrap(), Some("ABCDE12345"));
+
+    // Ad-hoc signature without a team identifier.
+    let code_directory = cs_code_directory(0x20400, "hello", None);
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[(macho::CSSLOT_CODEDIRECTORY, code_directory)]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.signing_identifier().unwrap(), Some("hello"));
+    assert_eq!(file.team_id().unwrap(), None);
+
+    // Old version that predates team identifiers.
+    let code_directory = cs_code_directory(0x20100, "hello", None);
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[(macho::CSSLOT_CODEDIRECTORY, code_directory)]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.signing_identifier().unwrap(), Some("hello"));
+    assert_eq!(file.team_id().unwrap(), None);
+
+    // Identifier offset outside of the blob.
+    let mut code_directory = cs_code_directory(0x20400, "hello", None);
+    code_directory[20..24].copy_from_slice(&0x1000u32.to_be_bytes());
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[(macho::CSSLOT_CODEDIRECTORY, code_directory)]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.signing_identifier().is_err());
+}
+
+#[test]
+fn code_signature_flags() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.code_signature_flags().unwrap(), None);
+
+    // Hardened runtime, as set by `codesign --options runtime`.
+    let mut code_directory = cs_code_directory(0x20400, "hello", Some("ABCDE12345"));
+    code_directory[12..16].copy_from_slice(&macho::CS_RUNTIME.to_be_bytes());
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[(macho::CSSLOT_CODEDIRECTORY, code_directory)]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.code_signature_flags().unwrap(),
+        Some(MachOCodeSignatureFlags {
+            flags: macho::CS_RUNTIME,
+            hardened_runtime: true,
+            library_validation: false,
+            restrict: false,
+            hard: false,
+            kill: false,
+        })
+    );
+
+    let flags = macho::CS_ADHOC | macho::CS_HARD | macho::CS_KILL | macho::CS_REQUIRE_LV;
+    let mut code_directory = cs_code_directory(0x20400, "hello", None);
+    code_directory[12..16].copy_from_slice(&flags.to_be_bytes());
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[(macho::CSSLOT_CODEDIRECTORY, code_directory)]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let signature_flags = file.code_signature_flags().unwrap().unwrap();
+    assert_eq!(signature_flags.flags, flags);
+    assert!(!signature_flags.hardened_runtime);
+    assert!(signature_flags.library_validation);
+    assert!(!signature_flags.restrict);
+    assert!(signature_flags.hard);
+    assert!(signature_flags.kill);
+}
+
+#[test]
+fn entitlements() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entitlements().unwrap(), None);
+
+    let plist: &[u8] = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<plist version=\"1.0\"><dict>\
+        <key>com.apple.security.get-task-allow</key><true/></dict></plist>\n";
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[
+            (
+                macho::CSSLOT_CODEDIRECTORY,
+                cs_code_directory(0x20400, "hello", None),
+            ),
+            (
+                macho::CSSLOT_ENTITLEMENTS,
+                cs_blob(macho::CSMAGIC_EMBEDDED_ENTITLEMENTS, plist),
+            ),
+        ]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entitlements().unwrap(), Some(plist));
+
+    // Signed, but without entitlements.
+    let mut builder = executable();
+    sign(
+        &mut builder,
+        &cs_super_blob(&[(
+            macho::CSSLOT_CODEDIRECTORY,
+            cs_code_directory(0x20400, "hello", None),
+        )]),
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entitlements().unwrap(), None);
+
+    // Blobs following an odd length blob are not aligned.
+    let wrapper = cs_blob(macho::CSMAGIC_BLOBWRAPPER, &[0]);
+    let code_directory = cs_code_directory(0x20400, "hello", Some("TEAM"));
+    let mut payload = Vec::new();
+    for &(slot, offset) in &[
+        (macho::CSSLOT_SIGNATURESLOT, 36),
+        (macho::CSSLOT_CODEDIRECTORY, 36 + wrapper.len()),
+        (
+            macho::CSSLOT_ENTITLEMENTS,
+            36 + wrapper.len() + code_directory.len(),
+        ),
+    ] {
+        payload.extend_from_slice(&slot.to_be_bytes());
+        payload.extend_from_slice(&(offset as u32).to_be_bytes());
+    }
+    payload.extend_from_slice(&wrapper);
+    payload.extend_from_slice(&code_directory);
+    payload.extend_from_slice(&cs_blob(macho::CSMAGIC_EMBEDDED_ENTITLEMENTS, plist));
+    let mut signature = cs_blob(macho::CSMAGIC_EMBEDDED_SIGNATURE, &[0, 0, 0, 3]);
+    signature.extend_from_slice(&payload);
+    let length = signature.len() as u32;
+    signature[4..8].copy_from_slice(&length.to_be_bytes());
+    let mut builder = executable();
+    sign(&mut builder, &signature);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let code_signature = file.code_signature().unwrap().unwrap();
+    assert_eq!(
+        code_signature.blob(macho::CSSLOT_SIGNATURESLOT).unwrap(),
+        Some(&wrapper[..])
+    );
+    assert_eq!(code_signature.identifier(), Ok(Some("hello")));
+    assert_eq!(code_signature.team_id(), Ok(Some("TEAM")));
+    assert_eq!(file.entitlements().unwrap(), Some(plist));
+}
+
+#[test]
+fn indirect_symbol_alias() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x10,
+        offset: 0x400,
+        flags: macho::S_ATTR_PURE_INSTRUCTIONS,
+        ..Default::default()
+    };
+    let strings = b"\0_target\0_alias\0";
+    let mut symbols = builder.nlist(1, macho::N_SECT | macho::N_EXT, 1, 0, 0x8);
+    symbols.extend(builder.nlist(9, macho::N_INDR | macho::N_EXT, 0, 0, 1));
+    builder
+        .segment(b"", 0, 0x10, 0x400, 0x10, 7, &[text])
+        .symtab(0x800, 2, 0x900, strings.len() as u32)
+        .data(0x400, &[0x90; 0x10])
+        .data(0x800, &symbols)
+        .data(0x900, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let alias = file.symbol_by_index(SymbolIndex(1)).unwrap();
+    assert_eq!(alias.name(), Some("_alias"));
+    assert_eq!(alias.address(), 0);
+    assert_eq!(alias.section(), object::SymbolSection::Unknown);
+    assert!(!alias.is_undefined());
+    assert_eq!(file.indirect_name(SymbolIndex(1)).unwrap(), Some("_target"));
+
+    assert_eq!(file.indirect_name(SymbolIndex(0)).unwrap(), None);
+    assert!(file.indirect_name(SymbolIndex(2)).is_err());
+}
+
+/// An export trie containing `_main`, `_puts` (a re-export) and `_resolved`
+/// (a stub and resolver).
+#[rustfmt::skip]
+const EXPORT_TRIE: &[u8] = &[
+    // 0: root
+    0, 1, b'_', 0, 5,
+    // 5: "_"
+    0, 3,
+    b'm', b'a', b'i', b'n', 0, 29,
+    b'p', b'u', b't', b's', 0, 34,
+    b'r', b'e', b's', b'o', b'l', b'v', b'e', b'd', 0, 43,
+    // 29: "_main"
+    3, 0x00, 0x80, 0x1e, 0,
+    // 34: "_puts"
+    7, 0x08, 1, b'p', b'u', b't', b's', 0, 0,
+    // 43: "_resolved"
+    5, 0x10, 0x80, 0x20, 0xa0, 0x1e, 0,
+];
+
+#[test]
+fn export() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.export("_main").unwrap(), None);
+
+    let mut builder = executable();
+    let dyld_info = builder.u32s(&[0, 0, 0, 0, 0, 0, 0, 0, 0x2100, EXPORT_TRIE.len() as u32]);
+    builder
+        .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+        .data(0x2100, EXPORT_TRIE);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    let main: MachOExport = file.export("_main").unwrap().unwrap();
+    assert_eq!(main.kind(), macho::EXPORT_SYMBOL_FLAGS_KIND_REGULAR);
+    assert!(!main.is_reexport());
+    assert_eq!(main.address(), 0xf00);
+    assert_eq!(main.resolver(), None);
+
+    let puts = file.export("_puts").unwrap().unwrap();
+    assert!(puts.is_reexport());
+    assert_eq!(puts.library_ordinal(), 1);
+    assert_eq!(puts.import_name(), b"puts");
+
+    let resolved = file.export("_resolved").unwrap().unwrap();
+    assert_eq!(resolved.address(), 0x1000);
+    assert_eq!(resolved.resolver(), Some(0xf20));
+
+    for name in &["", "_", "_ma", "_mainx", "_printf"] {
+        assert_eq!(file.export(name).unwrap(), None, "{}", name);
+    }
+
+    // The trie can also be in a `LC_DYLD_EXPORTS_TRIE` command.
+    let mut builder = executable();
+    let exports_trie = builder.u32s(&[0x2100, EXPORT_TRIE.len() as u32]);
+    builder
+        .command(macho::LC_DYLD_EXPORTS_TRIE, &exports_trie)
+        .data(0x2100, EXPORT_TRIE);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.export("_main").unwrap(), Some(main));
+}
+
+#[test]
+fn all_exports() {
+    fn exports(data: &[u8]) -> Vec<(String, u32, u64)> {
+        let file = MachOFile64::<RunTimeEndian>::parse(data).unwrap();
+        file.all_exports()
+            .unwrap()
+            .iter()
+            .map(|symbol| {
+                let export = symbol.export();
+                (
+                    symbol.name().unwrap().to_string(),
+                    export.flags(),
+                    export.address(),
+                )
+            })
+            .collect()
+    }
+
+    // A dylib with only a symbol table.
+    let mut data = executable().build();
+    data[12..16].copy_from_slice(&macho::MH_DYLIB.to_le_bytes());
+    let weak = macho::EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION;
+    assert_eq!(
+        exports(&data),
+        [
+            ("_data".to_string(), 0, 0x1000),
+            ("_main".to_string(), 0, 0xf00),
+            ("_start".to_string(), weak, 0xf00),
+        ]
+    );
+
+    // Private extern symbols are not exported.
+    data[0x2000 + 3 * 16 + 4] |= macho::N_PEXT;
+    assert_eq!(
+        exports(&data),
+        [
+            ("_main".to_string(), 0, 0xf00),
+            ("_start".to_string(), weak, 0xf00),
+        ]
+    );
+
+    // A dylib with both sources. `_main` is in both, and the trie is preferred.
+    let mut builder = executable();
+    let mut trie = EXPORT_TRIE.to_vec();
+    // Mark `_main` as a weak definition in the trie.
+    trie[30] = weak as u8;
+    let dyld_info = builder.u32s(&[0, 0, 0, 0, 0, 0, 0, 0, 0x2100, trie.len() as u32]);
+    builder
+        .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+        .data(0x2100, &trie);
+    let mut data = builder.build();
+    data[12..16].copy_from_slice(&macho::MH_DYLIB.to_le_bytes());
+    assert_eq!(
+        exports(&data),
+        [
+            ("_data".to_string(), 0, 0x1000),
+            ("_main".to_string(), weak, 0xf00),
+            ("_puts".to_string(), macho::EXPORT_SYMBOL_FLAGS_REEXPORT, 0),
+            (
+                "_resolved".to_string(),
+                macho::EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER,
+                0x1000
+            ),
+            ("_start".to_string(), weak, 0xf00),
+        ]
+    );
+
+    // A trie with a cycle is rejected.
+    let mut builder = executable();
+    let trie = [0, 1, b'_', 0, 0];
+    let dyld_info = builder.u32s(&[0, 0, 0, 0, 0, 0, 0, 0, 0x2100, trie.len() as u32]);
+    builder
+        .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+        .data(0x2100, &trie);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.all_exports().is_err());
+
+    // A chain of terminal nodes, with names that are much larger than the trie.
+    let mut builder = executable();
+    let mut trie = Vec::new();
+    for i in 1..1000u32 {
+        let child = i * 9;
+        trie.extend_from_slice(&[2, 0, 0, 1, b'a', 0]);
+        trie.extend_from_slice(&[
+            0x80 | (child & 0x7f) as u8,
+            0x80 | (child >> 7 & 0x7f) as u8,
+            (child >> 14) as u8,
+        ]);
+    }
+    trie.extend_from_slice(&[2, 0, 0, 0]);
+    let dyld_info = builder.u32s(&[0, 0, 0, 0, 0, 0, 0, 0, 0x3000, trie.len() as u32]);
+    builder
+        .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+        .data(0x3000, &trie);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.all_exports().is_err());
+}
+
+#[test]
+fn linkedit_format() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.linkedit_format(), Ok(MachOLinkeditFormat::Classic));
+
+    let mut builder = executable();
+    let dyld_info = builder.u32s(&[0; 10]);
+    builder.command(macho::LC_DYLD_INFO_ONLY, &dyld_info);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.linkedit_format(), Ok(MachOLinkeditFormat::DyldInfo));
+
+    let mut builder = executable();
+    let linkedit_data = builder.u32s(&[0x2100, 0]);
+    builder
+        .command(macho::LC_DYLD_CHAINED_FIXUPS, &linkedit_data)
+        .command(macho::LC_DYLD_EXPORTS_TRIE, &linkedit_data);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.linkedit_format(),
+        Ok(MachOLinkeditFormat::ChainedFixups)
+    );
+    assert_eq!(file.lazy_bind_at(0), Ok(None));
+}
+
+/// Append a dylib load command with the given install name.
+fn dylib(builder: &mut Builder, cmd: u32, name: &str) {
+    let mut payload = builder.u32s(&[24, 2, 0x0001_0000, 0x0001_0000]);
+    payload.extend_from_slice(name.as_bytes());
+    payload.push(0);
+    builder.command(cmd, &payload);
+}
+
+#[test]
+fn symbol_library() {
+    let mut builder = executable();
+    dylib(
+        &mut builder,
+        macho::LC_LOAD_DYLIB,
+        "/usr/lib/libSystem.B.dylib",
+    );
+    dylib(
+        &mut builder,
+        macho::LC_LOAD_WEAK_DYLIB,
+        "/usr/lib/libobjc.A.dylib",
+    );
+    let mut data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.imported_libraries().unwrap(),
+        ["/usr/lib/libSystem.B.dylib", "/usr/lib/libobjc.A.dylib"]
+    );
+    // `_printf`
+    assert_eq!(
+        file.symbol_library(SymbolIndex(4)).unwrap(),
+        Some("/usr/lib/libSystem.B.dylib")
+    );
+    // `_main` is defined.
+    assert_eq!(file.symbol_library(SymbolIndex(1)).unwrap(), None);
+    assert!(file.symbol_library(SymbolIndex(5)).is_err());
+
+    // Change the library ordinal of `_printf`.
+    let n_desc = 0x2000 + 4 * 16 + 7;
+    data[n_desc] = 2;
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.symbol_library(SymbolIndex(4)).unwrap(),
+        Some("/usr/lib/libobjc.A.dylib")
+    );
+    for &ordinal in &[
+        macho::SELF_LIBRARY_ORDINAL,
+        macho::DYNAMIC_LOOKUP_ORDINAL,
+        macho::EXECUTABLE_ORDINAL,
+    ] {
+        data[n_desc] = ordinal;
+        let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+        assert_eq!(file.symbol_library(SymbolIndex(4)).unwrap(), None);
+    }
+    data[n_desc] = 3;
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.symbol_library(SymbolIndex(4)).is_err());
+}
+
+#[test]
+fn flat_namespace() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.is_two_level());
+
+    let mut builder = executable();
+    builder.flags = macho::MH_NOUNDEFS | macho::MH_DYLDLINK | macho::MH_PIE;
+    dylib(
+        &mut builder,
+        macho::LC_LOAD_DYLIB,
+        "/usr/lib/libSystem.B.dylib",
+    );
+    let mut data = builder.build();
+    // The high byte of `n_desc` of `_printf` is not a library ordinal.
+    data[0x2000 + 4 * 16 + 7] = 5;
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.is_two_level());
+    assert_eq!(file.symbol_library(SymbolIndex(4)).unwrap(), None);
+    let imports = file.imports().unwrap();
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].name(), Ok("_printf"));
+    assert_eq!(imports[0].library(), None);
+}
+
+#[test]
+fn imports() {
+    let mut builder = executable();
+    dylib(
+        &mut builder,
+        macho::LC_LOAD_DYLIB,
+        "/usr/lib/libSystem.B.dylib",
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let imports = file.imports().unwrap();
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].name(), Ok("_printf"));
+    assert_eq!(imports[0].library(), Some("/usr/lib/libSystem.B.dylib"));
+
+    // Without a symbol table, the imports are read from the bind information.
+    let mut bind = Vec::new();
+    bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 1, 0x00]);
+    bind.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | 2);
+    bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    bind.extend_from_slice(b"_objc_msgSend\0");
+    bind.push(macho::BIND_OPCODE_DO_BIND);
+    bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    bind.extend_from_slice(b"_objc_release\0");
+    bind.push(macho::BIND_OPCODE_DO_BIND);
+    bind.push(macho::BIND_OPCODE_DONE);
+    let mut lazy_bind = Vec::new();
+    for &(offset, name) in &[
+        (0x10, &b"_printf\0"[..]),
+        (0x18, b"_puts\0"),
+        (0x20, b"_printf\0"),
+    ] {
+        lazy_bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 1, offset]);
+        lazy_bind.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | 1);
+        lazy_bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+        lazy_bind.extend_from_slice(name);
+        lazy_bind.extend_from_slice(&[macho::BIND_OPCODE_DO_BIND, macho::BIND_OPCODE_DONE]);
+    }
+    lazy_bind.push(macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 1);
+    lazy_bind.push(0x28);
+    lazy_bind.push(macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM | 0xe);
+    lazy_bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    lazy_bind.extend_from_slice(b"_flat\0");
+    lazy_bind.extend_from_slice(&[macho::BIND_OPCODE_DO_BIND, macho::BIND_OPCODE_DONE]);
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let dyld_info = builder.u32s(&[
+        0,
+        0,
+        0x1000,
+        bind.len() as u32,
+        0,
+        0,
+        0x1100,
+        lazy_bind.len() as u32,
+        0,
+        0,
+    ]);
+    builder
+        .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+        .data(0x1000, &bind)
+        .data(0x1100, &lazy_bind);
+    dylib(
+        &mut builder,
+        macho::LC_LOAD_DYLIB,
+        "/usr/lib/libSystem.B.dylib",
+    );
+    dylib(
+        &mut builder,
+        macho::LC_LOAD_DYLIB,
+        "/usr/lib/libobjc.A.dylib",
+    );
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let imports = file
+        .imports()
+        .unwrap()
+        .iter()
+        .map(|import| (import.name().unwrap(), import.library()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        imports,
+        [
+            ("_objc_msgSend", Some("/usr/lib/libobjc.A.dylib")),
+            ("_objc_release", Some("/usr/lib/libobjc.A.dylib")),
+            ("_printf", Some("/usr/lib/libSystem.B.dylib")),
+            ("_puts", Some("/usr/lib/libSystem.B.dylib")),
+            ("_flat", None),
+        ]
+    );
+}
+
+#[test]
+fn imports_bind_repeat() {
+    let build = |count: &[u8]| {
+        let mut bind = Vec::new();
+        bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB, 0x00]);
+        bind.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | 1);
+        bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+        bind.extend_from_slice(b"_a\0");
+        bind.push(macho::BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB);
+        bind.extend_from_slice(count);
+        bind.extend_from_slice(&[0x08, macho::BIND_OPCODE_DONE]);
+
+        let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_DYLIB);
+        let dyld_info = builder.u32s(&[0, 0, 0x1000, bind.len() as u32, 0, 0, 0, 0, 0, 0]);
+        let rw = macho::VM_PROT_READ | macho::VM_PROT_WRITE;
+        builder
+            .segment(b"__DATA", 0x1000, 0x100, 0, 0, rw, &[])
+            .command(macho::LC_DYLD_INFO_ONLY, &dyld_info)
+            .data(0x1000, &bind);
+        dylib(
+            &mut builder,
+            macho::LC_LOAD_DYLIB,
+            "/usr/lib/libSystem.B.dylib",
+        );
+        builder.build()
+    };
+
+    // Repeated bindings are only listed once.
+    let data = build(&[0x10]);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let imports = file.imports().unwrap();
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].name(), Ok("_a"));
+    assert_eq!(imports[0].library(), Some("/usr/lib/libSystem.B.dylib"));
+
+    // The bindings must fit in the segment.
+    let data = build(&[0x11]);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.imports().is_err());
+
+    // A huge count is an error instead of taking an unbounded time.
+    let data = build(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.imports().is_err());
+}
+
+#[test]
+fn symbol_count() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.symbol_count(), 5);
+    assert_eq!(file.symbols().count(), 5);
+
+    // Stabs are counted, but not returned by the iterator.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let strings = b"\0main.c\0_main\0";
+    let mut symbols = builder.nlist(1, macho::N_SO, 0, 0, 0);
+    symbols.extend(builder.nlist(8, macho::N_ABS | macho::N_EXT, 0, 0, 0x10));
+    builder
+        .symtab(0x200, 2, 0x220, strings.len() as u32)
+        .data(0x200, &symbols)
+        .data(0x220, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.symbol_count(), 2);
+    assert_eq!(file.symbols().count(), 1);
+}
+
+#[test]
+fn symbol_n_type() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let strings = b"\0main.c\0_abs\0_undef\0";
+    let mut symbols = builder.nlist(1, macho::N_SO, 0, 0, 0);
+    symbols.extend(builder.nlist(8, macho::N_ABS | macho::N_EXT, 0, 0, 0x10));
+    symbols.extend(builder.nlist(13, macho::N_UNDF | macho::N_EXT, 0, 0, 0));
+    builder
+        .symtab(0x200, 3, 0x230, strings.len() as u32)
+        .data(0x200, &symbols)
+        .data(0x230, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+
+    assert_eq!(file.n_type(SymbolIndex(0)), Ok(macho::N_SO));
+    let n_type = file.n_type(SymbolIndex(1)).unwrap();
+    assert_eq!(n_type & macho::N_TYPE, macho::N_ABS);
+    assert_eq!(n_type & macho::N_EXT, macho::N_EXT);
+    assert_eq!(
+        file.n_type(SymbolIndex(2)),
+        Ok(macho::N_UNDF | macho::N_EXT)
+    );
+    assert_eq!(
+        file.n_type(SymbolIndex(3)).unwrap_err().to_string(),
+        "Invalid Mach-O symbol index"
+    );
+}
+
+#[test]
+fn objc_image_info() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.objc_image_info(), Ok(None));
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let imageinfo = Sect {
+        sectname: b"__objc_imageinfo",
+        segname: b"__DATA_CONST",
+        addr: TEXT_ADDR + 0x1000,
+        size: 8,
+        offset: 0x1000,
+        align: 2,
+        ..Default::default()
+    };
+    // OptimizedByDyld | HasCategoryClassProperties, Swift ABI version 7.
+    let info = builder.u32s(&[0, 0x0000_0748]);
+    builder
+        .segment(
+            b"__DATA_CONST",
+            TEXT_ADDR + 0x1000,
+            0x1000,
+            0x1000,
+            0x1000,
+            macho::VM_PROT_READ,
+            &[imageinfo],
+        )
+        .data(0x1000, &info);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.objc_image_info(),
+        Ok(Some(MachOObjcImageInfo {
+            version: 0,
+            flags: 0x0000_0748,
+        }))
+    );
+}
+
+#[test]
+fn embedded_info_plist() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.embedded_info_plist(), Ok(None));
+
+    let plist = b"<plist version=\"1.0\"><dict><key>CFBundleIdentifier</key>\
+                  <string>com.example.tool</string></dict></plist>";
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let info_plist = Sect {
+        sectname: b"__info_plist",
+        segname: b"__TEXT",
+        addr: TEXT_ADDR + 0x800,
+        size: plist.len() as u64,
+        offset: 0x800,
+        ..Default::default()
+    };
+    builder
+        .segment(
+            b"__TEXT",
+            TEXT_ADDR,
+            0x1000,
+            0,
+            0x1000,
+            macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+            &[info_plist],
+        )
+        .data(0x800, plist);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.embedded_info_plist(), Ok(Some(&plist[..])));
+}
+
+/// Build an executable with the given pointers in `__objc_selrefs` in the given segment.
+fn objc_selrefs_executable(
+    segname: &[u8],
+    selrefs: &[u64],
+    chained_fixups: Option<&[u8]>,
+) -> Vec<u8> {
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let methname = Sect {
+        sectname: b"__objc_methname",
+        segname: b"__TEXT",
+        addr: TEXT_ADDR + 0x800,
+        size: 0x20,
+        offset: 0x800,
+        flags: macho::S_CSTRING_LITERALS,
+        ..Default::default()
+    };
+    let selrefs_sect = Sect {
+        sectname: b"__objc_selrefs",
+        segname,
+        addr: TEXT_ADDR + 0x1000,
+        size: 8 * selrefs.len() as u64,
+        offset: 0x1000,
+        align: 3,
+        flags: macho::S_LITERAL_POINTERS,
+        ..Default::default()
+    };
+    let mut pointers = Vec::new();
+    for &selref in selrefs {
+        builder.put_u64(&mut pointers, selref);
+    }
+    builder
+        .segment(b"__PAGEZERO", 0, TEXT_ADDR, 0, 0, 0, &[])
+        .segment(
+            b"__TEXT",
+            TEXT_ADDR,
+            0x1000,
+            0,
+            0x1000,
+            macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+            &[methname],
+        )
+        .segment(
+            segname,
+            TEXT_ADDR + 0x1000,
+            0x1000,
+            0x1000,
+            0x1000,
+            macho::VM_PROT_READ | macho::VM_PROT_WRITE,
+            &[selrefs_sect],
+        )
+        .data(0x800, b"init\0alloc\0setValue:forKey:\0")
+        .data(0x1000, &pointers);
+    if let Some(fixups) = chained_fixups {
+        let linkedit_data = builder.u32s(&[0x2000, fixups.len() as u32]);
+        builder
+            .command(macho::LC_DYLD_CHAINED_FIXUPS, &linkedit_data)
+            .data(0x2000, fixups);
+    }
+    builder.build()
+}
+
+#[test]
+fn objc_selrefs() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.objc_selrefs(), Ok(Vec::new()));
+
+    let selrefs = [TEXT_ADDR + 0x805, TEXT_ADDR + 0x800, TEXT_ADDR + 0x80b];
+    let data = objc_selrefs_executable(b"__DATA", &selrefs, None);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.objc_selrefs(),
+        Ok(vec!["alloc", "init", "setValue:forKey:"])
+    );
+
+    let data = objc_selrefs_executable(b"__DATA_CONST", &selrefs, None);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.objc_selrefs(),
+        Ok(vec!["alloc", "init", "setValue:forKey:"])
+    );
+
+    // The same references as `DYLD_CHAINED_PTR_64_OFFSET` rebases.
+    let builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let mut fixups = builder.u32s(&[0, 0x20, 0x48, 0x48, 0, macho::DYLD_CHAINED_IMPORT, 0, 0]);
+    // `__DATA` is the third segment.
+    fixups.extend(builder.u32s(&[3, 0, 0, 0x10]));
+    fixups.extend(builder.u32s(&[24]));
+    fixups.extend_from_slice(&0x1000u16.to_le_bytes());
+    fixups.extend_from_slice(&macho::DYLD_CHAINED_PTR_64_OFFSET.to_le_bytes());
+    builder.put_u64(&mut fixups, 0x1000);
+    fixups.extend(builder.u32s(&[0, 1]));
+    let next = 1 << 51;
+    let selrefs = [0x805 | next, 0x800 | next, 0x80b];
+    let data = objc_selrefs_executable(b"__DATA", &selrefs, Some(&fixups));
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.objc_selrefs(),
+        Ok(vec!["alloc", "init", "setValue:forKey:"])
+    );
+
+    // An unsupported pointer format is an error, even if there are no references.
+    let pointer_format = fixups.len() - 18;
+    fixups[pointer_format..][..2]
+        .copy_from_slice(&macho::DYLD_CHAINED_PTR_64_KERNEL_CACHE.to_le_bytes());
+    let data = objc_selrefs_executable(b"__DATA", &[], Some(&fixups));
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.linkedit_format(),
+        Ok(MachOLinkeditFormat::ChainedFixups)
+    );
+    assert_eq!(
+        file.objc_selrefs().unwrap_err().to_string(),
+        "Unsupported Mach-O chained pointer format"
+    );
+
+    // A reference outside of any section.
+    let data = objc_selrefs_executable(b"__DATA", &[TEXT_ADDR + 0x900], None);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.objc_selrefs().unwrap_err().to_string(),
+        "Invalid Mach-O selector reference address"
+    );
+}
+
+#[test]
+fn objc_classes() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.objc_classes(), Ok(Vec::new()));
+
+    for &is_64 in &[false, true] {
+        let (cputype, base, pointer_size) = if is_64 {
+            (macho::CPU_TYPE_X86_64, TEXT_ADDR, 8)
+        } else {
+            (macho::CPU_TYPE_X86, 0x1000, 4)
+        };
+        let mut builder = Builder::new(is_64, false, cputype, macho::MH_EXECUTE);
+        let classname = Sect {
+            sectname: b"__objc_classname",
+            segname: b"__TEXT",
+            addr: base + 0x800,
+            size: 8,
+            offset: 0x800,
+            flags: macho::S_CSTRING_LITERALS,
+            ..Default::default()
+        };
+        let classlist = Sect {
+            sectname: b"__objc_classlist",
+            segname: b"__DATA",
+            addr: base + 0x1000,
+            size: 2 * pointer_size,
+            offset: 0x1000,
+            align: 3,
+            ..Default::default()
+        };
+        // The class list, then the `class_t` and `class_ro_t` of each class.
+        let mut contents = vec![0; 0x300];
+        let mut put = |offset: usize, value: u64| {
+            if is_64 {
+                contents[offset..][..8].copy_from_slice(&value.to_le_bytes());
+            } else {
+                contents[offset..][..4].copy_from_slice(&(value as u32).to_le_bytes());
+            }
+        };
+        let name_offset = if is_64 { 24 } else { 16 };
+        for (i, &name) in [0x804, 0x800].iter().enumerate() {
+            let class = 0x100 + 0x40 * i;
+            let class_ro = 0x200 + 0x40 * i;
+            put(pointer_size as usize * i, base + 0x1000 + class as u64);
+            // The low bits of `class_t::data` are flags.
+            put(
+                class + 4 * pointer_size as usize,
+                (base + 0x1000 + class_ro as u64) | 1,
+            );
+            put(class_ro + name_offset, base + name);
+        }
+        builder
+            .segment(b"__TEXT", base, 0x1000, 0, 0x1000, 5, &[classname])
+            .segment(
+                b"__DATA",
+                base + 0x1000,
+                0x1000,
+                0x1000,
+                0x300,
+                3,
+                &[classlist],
+            )
+            .data(0x800, b"Foo\0Bar\0")
+            .data(0x1000, &contents);
+        let data = builder.build();
+        let classes = if is_64 {
+            MachOFile64::<RunTimeEndian>::parse(&data)
+                .unwrap()
+                .objc_classes()
+        } else {
+            MachOFile32::<RunTimeEndian>::parse(&data)
+                .unwrap()
+                .objc_classes()
+        };
+        assert_eq!(classes, Ok(vec!["Bar", "Foo"]), "is_64 {}", is_64);
+    }
+}
+
+#[test]
+fn compact_unwind() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.compact_unwind().unwrap().is_none());
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 0x20,
+        offset: 0x100,
+        ..Default::default()
+    };
+    let compact_unwind = Sect {
+        sectname: b"__compact_unwind",
+        segname: b"__LD",
+        addr: 0x20,
+        size: 0x40,
+        offset: 0x120,
+        align: 3,
+        reloff: 0x200,
+        nreloc: 3,
+        flags: macho::S_ATTR_DEBUG,
+        ..Default::default()
+    };
+    // _bar: a section relative function start, with no personality.
+    let mut entries = Vec::new();
+    builder.put_u64(&mut entries, 0);
+    entries.extend(builder.u32s(&[0x10, 0x0100_0000]));
+    builder.put_u64(&mut entries, 0);
+    builder.put_u64(&mut entries, 0);
+    // _foo: a symbol relative function start, with a personality and LSDA.
+    builder.put_u64(&mut entries, 0);
+    entries.extend(builder.u32s(&[0x10, 0x4100_0000]));
+    builder.put_u64(&mut entries, 0);
+    builder.put_u64(&mut entries, 0x60);
+    let mut relocs = builder.reloc(0, 1, false, 3, false, macho::X86_64_RELOC_UNSIGNED);
+    relocs.extend(builder.reloc(0x20, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED));
+    relocs.extend(builder.reloc(0x30, 1, false, 3, true, macho::X86_64_RELOC_UNSIGNED));
+    let strings = b"\0_foo\0___gxx_personality_v0\0";
+    let mut symbols = builder.nlist(1, macho::N_SECT | macho::N_EXT, 1, 0, 0x10);
+    symbols.extend(builder.nlist(6, macho::N_UNDF | macho::N_EXT, 0, 0, 0));
+    builder
+        .segment(b"", 0, 0x60, 0x100, 0x60, 7, &[text, compact_unwind])
+        .symtab(0x280, 2, 0x2a0, strings.len() as u32)
+        .data(0x120, &entries)
+        .data(0x200, &relocs)
+        .data(0x280, &symbols)
+        .data(0x2a0, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let entries = file.compact_unwind().unwrap().unwrap().collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            MachOCompactUnwindEntry {
+                function_address: 0,
+                function_length: 0x10,
+                encoding: 0x0100_0000,
+                personality: 0,
+                personality_symbol: None,
+                lsda: 0,
+            },
+            MachOCompactUnwindEntry {
+                function_address: 0x10,
+                function_length: 0x10,
+                encoding: 0x4100_0000,
+                personality: 0,
+                personality_symbol: Some(SymbolIndex(1)),
+                lsda: 0x60,
+            },
+        ]
+    );
+
+    // The section size is not a multiple of the entry size.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let compact_unwind = Sect {
+        sectname: b"__compact_unwind",
+        segname: b"__LD",
+        size: 0x18,
+        offset: 0x100,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 0x18, 0x100, 0x18, 7, &[compact_unwind])
+        .data(0x100, &[0; 0x18]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.compact_unwind().err().unwrap().to_string(),
+        "Invalid Mach-O compact unwind section size"
+    );
+}
+
+#[test]
+fn header_region() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let sizeofcmds = u32::from_le_bytes([data[20], data[21], data[22], data[23]]) as usize;
+    let region = file.header_region();
+    assert_eq!(
+        region.len(),
+        std::mem::size_of::<macho::MachHeader64<LittleEndian>>() + sizeofcmds
+    );
+    assert_eq!(region, &data[..region.len()]);
+
+    let data = Builder::new(false, true, macho::CPU_TYPE_POWERPC, macho::MH_OBJECT).build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.header_region(), &data[..28]);
+}
+
+#[test]
+fn platform() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.platform(), None);
+
+    let mut builder = executable();
+    let build_version = builder.u32s(&[macho::PLATFORM_MACOS, 0x000a_0e00, 0x000a_0f00, 0]);
+    builder.command(macho::LC_BUILD_VERSION, &build_version);
+    let data = builder.build();
+    let file = object::File::parse(&data).unwrap();
+    assert_eq!(file.platform(), Some(object::Platform::MacOS));
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let version_min = builder.u32s(&[0x000c_0000, 0x000d_0000]);
+    builder.command(macho::LC_VERSION_MIN_IPHONEOS, &version_min);
+    let data = builder.build();
+    let file = object::File::parse(&data).unwrap();
+    assert_eq!(file.platform(), Some(object::Platform::IOS));
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let build_version = builder.u32s(&[99, 0x000c_0000, 0x000d_0000, 0]);
+    builder.command(macho::LC_BUILD_VERSION, &build_version);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.platform(), Some(object::Platform::MachO(99)));
+}
+
+/// Build an arm64e dylib with a `LC_DYLD_CHAINED_FIXUPS` command.
+fn chained_fixups(imports_format: u32, imports: &[u8]) -> Vec<u8> {
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_DYLIB);
+    builder.cpusubtype = macho::CPU_SUBTYPE_ARM64E;
+    let symbols = b"\0_malloc\0_free\0_objc_msgSend\0";
+    let imports_offset = 0x20;
+    let symbols_offset = imports_offset + imports.len() as u32;
+    let mut fixups = builder.u32s(&[
+        0,
+        0x1c,
+        imports_offset,
+        symbols_offset,
+        3,
+        imports_format,
+        0,
+    ]);
+    fixups.resize(imports_offset as usize, 0);
+    fixups.extend_from_slice(imports);
+    fixups.extend_from_slice(symbols);
+    let linkedit_data = builder.u32s(&[0x1000, fixups.len() as u32]);
+    builder
+        .command(macho::LC_DYLD_CHAINED_FIXUPS, &linkedit_data)
+        .data(0x1000, &fixups);
+    builder.build()
+}
+
+#[test]
+fn chained_imports() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.chained_imports(), Ok(Vec::new()));
+
+    // `_malloc` and `_free` from ordinal 1, and `_objc_msgSend` weakly with a flat lookup.
+    let imports = [1 | 1 << 9, 1 | 9 << 9, 0xfe | 1 << 8 | 15 << 9];
+    let builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_DYLIB);
+    let check = |data: &[u8], addends: [i64; 3]| {
+        let file = MachOFile64::<RunTimeEndian>::parse(data).unwrap();
+        let imports = file.chained_imports().unwrap();
+        let imports = imports
+            .iter()
+            .map(|import| {
+                (
+                    import.name().unwrap(),
+                    import.library_ordinal(),
+                    import.is_weak_import(),
+                    import.addend(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            imports,
+            [
+                ("_malloc", 1, false, addends[0]),
+                ("_free", 1, false, addends[1]),
+                (
+                    "_objc_msgSend",
+                    i32::from(macho::BIND_SPECIAL_DYLIB_FLAT_LOOKUP),
+                    true,
+                    addends[2]
+                ),
+            ]
+        );
+    };
+
+    let data = chained_fixups(macho::DYLD_CHAINED_IMPORT, &builder.u32s(&imports));
+    check(&data, [0, 0, 0]);
+
+    let table = builder.u32s(&[imports[0], 0x10, imports[1], 0, imports[2], -8i32 as u32]);
+    let data = chained_fixups(macho::DYLD_CHAINED_IMPORT_ADDEND, &table);
+    check(&data, [0x10, 0, -8]);
+
+    let mut table = Vec::new();
+    for (&import, &addend) in imports.iter().zip(&[0x1_0000_0000u64, 0, !0]) {
+        let ordinal = u64::from(import & 0xff);
+        let ordinal = if ordinal > 0xf0 {
+            ordinal | 0xff00
+        } else {
+            ordinal
+        };
+        let import = ordinal | u64::from(import & 0x100) << 8 | u64::from(import >> 9) << 32;
+        table.extend_from_slice(&import.to_le_bytes());
+        table.extend_from_slice(&addend.to_le_bytes());
+    }
+    let data = chained_fixups(macho::DYLD_CHAINED_IMPORT_ADDEND64, &table);
+    check(&data, [0x1_0000_0000, 0, -1]);
+
+    let data = chained_fixups(4, &[]);
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.chained_imports().unwrap_err().to_string(),
+        "Unsupported Mach-O chained imports format"
+    );
+}
+
+#[test]
+fn is_simulator() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let build_version = builder.u32s(&[macho::PLATFORM_IOSSIMULATOR, 0x000d_0000, 0x000d_0000, 0]);
+    builder.command(macho::LC_BUILD_VERSION, &build_version);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.is_simulator(), Ok(true));
+
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let build_version = builder.u32s(&[macho::PLATFORM_IOS, 0x000d_0000, 0x000d_0000, 0]);
+    builder.command(macho::LC_BUILD_VERSION, &build_version);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.is_simulator(), Ok(false));
+
+    // No build version.
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.is_simulator(), Ok(false));
+}
+
+#[test]
+fn triple() {
+    let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+    let build_version = builder.u32s(&[macho::PLATFORM_IOS, 0x000d_0000, 0x000d_0000, 0]);
+    builder.command(macho::LC_BUILD_VERSION, &build_version);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let triple = file.triple();
+    assert_eq!(triple, Triple::from_str("arm64-apple-ios").unwrap());
+    assert_eq!(triple.binary_format, BinaryFormat::Macho);
+    assert_eq!(triple.to_string(), "aarch64-apple-ios");
+
+    // Mac Catalyst and simulator binaries are not device iOS binaries.
+    for &platform in &[macho::PLATFORM_IOSMAC, macho::PLATFORM_IOSSIMULATOR] {
+        let mut builder = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE);
+        let build_version = builder.u32s(&[platform, 0x000d_0000, 0x000d_0000, 0]);
+        builder.command(macho::LC_BUILD_VERSION, &build_version);
+        let data = builder.build();
+        let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+        assert_eq!(file.triple().operating_system, OperatingSystem::Darwin);
+        assert_eq!(file.triple().environment, Environment::Unknown);
+    }
+
+    let mut builder = executable();
+    let build_version = builder.u32s(&[macho::PLATFORM_MACOS, 0x000a_0e01, 0x000a_0f00, 0]);
+    builder.command(macho::LC_BUILD_VERSION, &build_version);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.triple().operating_system,
+        OperatingSystem::MacOSX {
+            major: 10,
+            minor: 14,
+            patch: 1
+        }
+    );
+
+    let mut builder = Builder::new(false, false, macho::CPU_TYPE_ARM, macho::MH_OBJECT);
+    builder.cpusubtype = macho::CPU_SUBTYPE_ARM_V7S;
+    let data = builder.build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    let triple = file.triple();
+    assert_eq!(
+        triple.architecture,
+        Architecture::Arm(ArmArchitecture::Armv7s)
+    );
+    assert_eq!(triple.operating_system, OperatingSystem::Darwin);
+}
+
+#[test]
+fn x86_64_got_relocations() {
+    // movq _var@GOTPCREL(%rip), %rax
+    // cmpq _var@GOTPCREL(%rip), %rax
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let mut relocs = builder.reloc(3, 0, true, 2, true, macho::X86_64_RELOC_GOT_LOAD);
+    relocs.extend(builder.reloc(10, 0, true, 2, true, macho::X86_64_RELOC_GOT));
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        size: 14,
+        offset: 0x100,
+        reloff: 0x110,
+        nreloc: 2,
+        ..Default::default()
+    };
+    builder
+        .segment(b"", 0, 14, 0x100, 14, 7, &[text])
+        .data(
+            0x100,
+            &[0x48, 0x8b, 0x05, 0, 0, 0, 0, 0x48, 0x3b, 0x05, 0, 0, 0, 0],
+        )
+        .data(0x110, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.section_by_name("__text").unwrap();
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].0, 3);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::GotRelative);
+    assert_eq!(
+        relocations[0].1.encoding(),
+        RelocationEncoding::X86RipRelativeMovq
+    );
+    assert_eq!(relocations[1].0, 10);
+    assert_eq!(relocations[1].1.kind(), RelocationKind::GotRelative);
+    assert_eq!(
+        relocations[1].1.encoding(),
+        RelocationEncoding::X86RipRelative
+    );
+}
+
+#[test]
+fn entry_section() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entry(), 0xf00);
+    assert_eq!(file.entry_address(), Some(TEXT_ADDR + 0xf00));
+    let section = file.entry_section().unwrap().unwrap();
+    assert_eq!(section.name(), Ok("__text"));
+    assert_eq!(section.segment_name(), Ok(Some("__TEXT")));
+
+    // The entry offset is outside of any segment.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    let entry = builder.u32s(&[0xf00, 0, 0, 0]);
+    builder.command(macho::LC_MAIN, &entry);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.entry_address(), None);
+    assert!(file.entry_section().unwrap().is_none());
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(file.entry_section().unwrap().is_none());
+}
+
+#[test]
+fn header_reserved() {
+    let mut data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.reserved(), 0);
+
+    data[28..32].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.reserved(), 0x1234_5678);
+
+    let data = Builder::new(false, false, macho::CPU_TYPE_X86, macho::MH_OBJECT).build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.reserved(), 0);
+}
+
+#[test]
+fn arm64_32() {
+    let data = Builder::new(false, false, macho::CPU_TYPE_ARM64_32, macho::MH_EXECUTE).build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.architecture(),
+        Architecture::Aarch64(Aarch64Architecture::Aarch64)
+    );
+    assert_eq!(file.pointer_size(), 4);
+    assert!(!file.is_64());
+}
+
+#[test]
+fn header_magic() {
+    let data = Builder::new(false, true, macho::CPU_TYPE_POWERPC, macho::MH_OBJECT).build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.magic(), macho::MH_MAGIC);
+
+    let data = Builder::new(false, false, macho::CPU_TYPE_X86, macho::MH_OBJECT).build();
+    let file = MachOFile32::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.magic(), macho::MH_CIGAM);
+
+    let data = Builder::new(true, true, macho::CPU_TYPE_POWERPC64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.magic(), macho::MH_MAGIC_64);
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.magic(), macho::MH_CIGAM_64);
+}
+
+#[test]
+fn strings() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.strings().collect::<Vec<_>>(),
+        ["_main", "_helper", "_data", "_printf", "_start"]
+    );
+
+    // Invalid UTF-8 is skipped.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let strings = b"\0_a\0\xff\xfe\0_b";
+    builder
+        .symtab(0x200, 0, 0x200, strings.len() as u32)
+        .data(0x200, strings);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.strings().count(), 2);
+    assert_eq!(file.strings().collect::<Vec<_>>(), ["_a", "_b"]);
+
+    let data = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT).build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.strings().count(), 0);
+}
+
+#[test]
+fn relocated_data() {
+    // .quad _target + 8
+    // callq _target
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_OBJECT);
+    let mut relocs = builder.reloc(0, 0, false, 3, true, macho::X86_64_RELOC_UNSIGNED);
+    relocs.extend(builder.reloc(9, 0, true, 2, true, macho::X86_64_RELOC_BRANCH));
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT",
+        addr: 0x100,
+        size: 13,
+        offset: 0x200,
+        reloff: 0x210,
+        nreloc: 2,
+        ..Default::default()
+    };
+    let mut code = 8u64.to_le_bytes().to_vec();
+    code.extend_from_slice(&[0xe8, 0, 0, 0, 0]);
+    builder
+        .segment(b"", 0x100, 13, 0x200, 13, 7, &[text])
+        .data(0x200, &code)
+        .data(0x210, &relocs);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let text = file.section_by_name("__text").unwrap();
+
+    let relocated = text
+        .relocated_data(|symbol| {
+            assert_eq!(symbol, SymbolIndex(0));
+            Some(0x1000)
+        })
+        .unwrap();
+    let mut expected = 0x1008u64.to_le_bytes().to_vec();
+    // 0x1000 - (0x100 + 9 + 4)
+    expected.extend_from_slice(&[0xe8]);
+    expected.extend_from_slice(&(0x1000i32 - 0x10d).to_le_bytes());
+    assert_eq!(relocated, expected);
+    // The original data is unchanged.
+    assert_eq!(text.data().unwrap(), &code[..]);
+
+    assert_eq!(
+        text.relocated_data(|_| None).unwrap_err().to_string(),
+        "Unresolved Mach-O relocation symbol"
+    );
+    assert_eq!(
+        text.relocated_data(|_| Some(0x1_0000_0000))
+            .unwrap_err()
+            .to_string(),
+        "Mach-O relocation value out of range"
+    );
+}
+
+#[test]
+fn is_pagezero() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let segments = file.segments().collect::<Vec<_>>();
+    assert_eq!(segments[0].name(), Ok(Some("__PAGEZERO")));
+    assert_eq!(segments[0].size(), 0x1_0000_0000);
+    assert!(segments[0].is_pagezero());
+    assert!(segments[1..].iter().all(|segment| !segment.is_pagezero()));
+
+    // A segment with the name, but that is readable.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder.segment(b"__PAGEZERO", 0, 0x1000, 0, 0, macho::VM_PROT_READ, &[]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert!(!file.segments().next().unwrap().is_pagezero());
+}
+
+/// Append an `LC_FILESET_ENTRY` command.
+fn fileset_entry(builder: &mut Builder, vmaddr: u64, fileoff: u64, entry_id: &str) {
+    let mut payload = builder.u32s(&[
+        vmaddr as u32,
+        (vmaddr >> 32) as u32,
+        fileoff as u32,
+        (fileoff >> 32) as u32,
+        32,
+        0,
+    ]);
+    payload.extend_from_slice(entry_id.as_bytes());
+    payload.push(0);
+    builder.command(macho::LC_FILESET_ENTRY, &payload);
+}
+
+#[test]
+fn fileset_symbols() {
+    const KC_ADDR: u64 = 0xffff_fe00_0700_0000;
+
+    // The kext, whose header is at 0x2000. All of its file offsets are
+    // relative to the start of the collection.
+    let mut kext = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_KEXT_BUNDLE);
+    let text = Sect {
+        sectname: b"__text",
+        segname: b"__TEXT_EXEC",
+        addr: KC_ADDR + 0x2800,
+        size: 0x20,
+        offset: 0x2800,
+        flags: macho::S_ATTR_PURE_INSTRUCTIONS,
+        ..Default::default()
+    };
+    let strings = b"\0_driver_start\0_driver_stop\0";
+    let mut symbols = kext.nlist(1, macho::N_SECT | macho::N_EXT, 1, 0, KC_ADDR + 0x2800);
+    symbols.extend(kext.nlist(15, macho::N_SECT | macho::N_EXT, 1, 0, KC_ADDR + 0x2810));
+    kext.segment(
+        b"__TEXT_EXEC",
+        KC_ADDR + 0x2800,
+        0x800,
+        0x2800,
+        0x800,
+        macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+        &[text],
+    )
+    .symtab(0x3000, 2, 0x3100, strings.len() as u32);
+    let kext = kext.build();
+
+    let mut collection = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_FILESET);
+    fileset_entry(
+        &mut collection,
+        KC_ADDR + 0x1000,
+        0x1000,
+        "com.apple.kernel",
+    );
+    fileset_entry(
+        &mut collection,
+        KC_ADDR + 0x2000,
+        0x2000,
+        "com.example.driver",
+    );
+    collection
+        .data(
+            0x1000,
+            &Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE).build(),
+        )
+        .data(0x2000, &kext)
+        .data(0x2800, &[0xd5; 0x20])
+        .data(0x3000, &symbols)
+        .data(0x3100, strings);
+    let data = collection.build();
+
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    let entries = file.fileset_entries().unwrap();
+    assert_eq!(
+        entries,
+        [
+            MachOFilesetEntry {
+                entry_id: b"com.apple.kernel",
+                vmaddr: KC_ADDR + 0x1000,
+                fileoff: 0x1000,
+            },
+            MachOFilesetEntry {
+                entry_id: b"com.example.driver",
+                vmaddr: KC_ADDR + 0x2000,
+                fileoff: 0x2000,
+            },
+        ]
+    );
+
+    let driver = MachOFile64::<RunTimeEndian>::parse_at(&data, entries[1].fileoff).unwrap();
+    assert_eq!(driver.header_region(), &kext[..]);
+    let symbols = driver
+        .symbols()
+        .map(|(_, symbol)| (symbol.name().unwrap(), symbol.address()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [
+            ("_driver_start", KC_ADDR + 0x2800),
+            ("_driver_stop", KC_ADDR + 0x2810),
+        ]
+    );
+    let text = driver.section_by_name("__text").unwrap();
+    assert_eq!(text.data().unwrap(), &[0xd5; 0x20][..]);
+
+    let kernel = MachOFile64::<RunTimeEndian>::parse_at(&data, entries[0].fileoff).unwrap();
+    assert_eq!(kernel.symbols().count(), 0);
+    assert!(MachOFile64::<RunTimeEndian>::parse_at(&data, 0x10_0000).is_err());
+}
+
+#[test]
+fn fat_arches() {
+    let x86_64 = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE).build();
+    let arm64 = Builder::new64(macho::CPU_TYPE_ARM64, macho::MH_EXECUTE).build();
+    let mut data = fat(&[
+        (macho::CPU_TYPE_X86_64, &x86_64),
+        (macho::CPU_TYPE_ARM64, &arm64),
+    ]);
+    // Set the cpusubtype and alignment of the arm64 slice.
+    data[8 + 20 + 4..8 + 20 + 8].copy_from_slice(&macho::CPU_SUBTYPE_ARM64E.to_be_bytes());
+    data[8 + 20 + 16..8 + 20 + 20].copy_from_slice(&14u32.to_be_bytes());
+    let fat = MachOFatFile::parse(&data).unwrap();
+    assert_eq!(
+        fat.arches().collect::<Vec<_>>(),
+        [
+            MachOFatArch {
+                cputype: macho::CPU_TYPE_X86_64,
+                cpusubtype: 0,
+                offset: 0x1000,
+                size: x86_64.len() as u64,
+                align: 12,
+            },
+            MachOFatArch {
+                cputype: macho::CPU_TYPE_ARM64,
+                cpusubtype: macho::CPU_SUBTYPE_ARM64E,
+                offset: 0x2000,
+                size: arm64.len() as u64,
+                align: 14,
+            },
+        ]
+    );
+}
+
+#[test]
+fn fat_huge_nfat_arch() {
+    let x86_64 = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE).build();
+    let mut data = fat(&[(macho::CPU_TYPE_X86_64, &x86_64)]);
+    for &nfat_arch in &[0x1000_0000u32, !0] {
+        data[4..8].copy_from_slice(&nfat_arch.to_be_bytes());
+        assert_eq!(
+            MachOFatFile::parse(&data).unwrap_err().to_string(),
+            "Invalid fat arch count"
+        );
+    }
+
+    // The count is read as big endian, so a byte swapped count of 1 is too large.
+    data[4..8].copy_from_slice(&1u32.to_le_bytes());
+    assert_eq!(
+        MachOFatFile::parse(&data).unwrap_err().to_string(),
+        "Invalid fat arch count"
+    );
+
+    data[4..8].copy_from_slice(&1u32.to_be_bytes());
+    assert_eq!(MachOFatFile::parse(&data).unwrap().arches().count(), 1);
+}
+
+/// Build a `FAT_MAGIC_64` universal binary with the given `(cputype, offset, size)` entries.
+fn fat64(arches: &[(u32, u64, u64)], len: usize) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&macho::FAT_MAGIC_64.to_be_bytes());
+    data.extend_from_slice(&(arches.len() as u32).to_be_bytes());
+    for &(cputype, offset, size) in arches {
+        data.extend_from_slice(&cputype.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&offset.to_be_bytes());
+        data.extend_from_slice(&size.to_be_bytes());
+        data.extend_from_slice(&12u32.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+    }
+    data.resize(len, 0);
+    data
+}
+
+#[test]
+fn fat_arches_64() {
+    let x86_64 = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE).build();
+    let mut data = fat64(
+        &[(macho::CPU_TYPE_X86_64, 0x1000, x86_64.len() as u64)],
+        0x1000,
+    );
+    data.extend_from_slice(&x86_64);
+    let fat = MachOFatFile::parse(&data).unwrap();
+    assert_eq!(
+        fat.arches().collect::<Vec<_>>(),
+        [MachOFatArch {
+            cputype: macho::CPU_TYPE_X86_64,
+            cpusubtype: 0,
+            offset: 0x1000,
+            size: x86_64.len() as u64,
+            align: 12,
+        }]
+    );
+    assert_eq!(fat.build_info()[0].0, Architecture::X86_64);
+
+    // A slice that is larger than 4 GiB, but which is truncated.
+    let data = fat64(&[(macho::CPU_TYPE_ARM64, 0x1000, 0x1_0000_0000)], 0x2000);
+    assert_eq!(
+        MachOFatFile::parse(&data).unwrap_err().to_string(),
+        "Invalid fat arch offset or size"
+    );
+
+    // The offset and size overflow.
+    let data = fat64(&[(macho::CPU_TYPE_ARM64, !0 - 0xfff, 0x1000)], 0x2000);
+    assert_eq!(
+        MachOFatFile::parse(&data).unwrap_err().to_string(),
+        "Invalid fat arch offset or size"
+    );
+
+    // The slice overlaps the fat header.
+    let data = fat64(&[(macho::CPU_TYPE_ARM64, 0x10, 0x100)], 0x1000);
+    assert_eq!(
+        MachOFatFile::parse(&data).unwrap_err().to_string(),
+        "Invalid fat arch offset or size"
+    );
+
+    let mut data = fat64(&[], 0x1000);
+    data[..4].copy_from_slice(&macho::FAT_CIGAM_64.to_be_bytes());
+    assert_eq!(
+        MachOFatFile::parse(&data).unwrap_err().to_string(),
+        "Unsupported little endian fat header"
+    );
+}
+
+#[test]
+fn overlapping_segments() {
+    let data = executable().build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.overlapping_segments(), None);
+    assert_eq!(file.validate(), Ok(()));
+
+    let rw = macho::VM_PROT_READ | macho::VM_PROT_WRITE;
+
+    // The file ranges overlap.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder
+        .segment(b"__DATA", 0x1000, 0x1000, 0x1000, 0x1000, rw, &[])
+        .segment(b"__DATA", 0x4000, 0x1000, 0x1800, 0x1000, rw, &[]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.overlapping_segments(),
+        Some((&b"__DATA"[..], &b"__DATA"[..]))
+    );
+    assert_eq!(
+        file.validate().unwrap_err().to_string(),
+        "Overlapping Mach-O segments"
+    );
+
+    // The address ranges overlap.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder
+        .segment(b"__DATA", 0x1000, 0x2000, 0x1000, 0x1000, rw, &[])
+        .segment(b"__DATA_DIRTY", 0x2fff, 0x1000, 0x2000, 0x1000, rw, &[]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.overlapping_segments(),
+        Some((&b"__DATA"[..], &b"__DATA_DIRTY"[..]))
+    );
+
+    // Segments that are not in address order. The names are in load command order.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder
+        .segment(b"__C", 0x3800, 0x1000, 0x3000, 0x1000, rw, &[])
+        .segment(b"__A", 0x1000, 0x1000, 0x1000, 0x1000, rw, &[])
+        .segment(b"__B", 0x3000, 0x1000, 0x2000, 0x1000, rw, &[]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(
+        file.overlapping_segments(),
+        Some((&b"__C"[..], &b"__B"[..]))
+    );
+
+    // Adjacent and empty ranges don't overlap.
+    let mut builder = Builder::new64(macho::CPU_TYPE_X86_64, macho::MH_EXECUTE);
+    builder
+        .segment(b"__DATA", 0x1000, 0x1000, 0x1000, 0x1000, rw, &[])
+        .segment(b"__DATA_DIRTY", 0x2000, 0x1000, 0x2000, 0, rw, &[])
+        .segment(b"__BSS", 0x3000, 0, 0x1800, 0, rw, &[]);
+    let data = builder.build();
+    let file = MachOFile64::<RunTimeEndian>::parse(&data).unwrap();
+    assert_eq!(file.validate(), Ok(()));
+}
@@ -0,0 +1,4 @@
+#![cfg(feature = "read")]
+
+mod macho;
+mod util;
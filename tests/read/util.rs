@@ -0,0 +1,15 @@
+use object::read::{read_sleb128, read_uleb128};
+
+#[test]
+fn leb128() {
+    let mut data = &[0xe5, 0x8e, 0x26, 0x7f, 0x80][..];
+    assert_eq!(read_uleb128(&mut data), Ok(624_485));
+    assert_eq!(data, &[0x7f, 0x80]);
+    assert_eq!(read_sleb128(&mut data), Ok(-1));
+    assert_eq!(data, &[0x80]);
+
+    // Errors don't advance the data.
+    assert!(read_uleb128(&mut data).is_err());
+    assert!(read_sleb128(&mut data).is_err());
+    assert_eq!(data, &[0x80]);
+}
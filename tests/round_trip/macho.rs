@@ -0,0 +1,139 @@
+use object::read::{Object, ObjectSection, ObjectSegment};
+use object::{read, write};
+use object::{
+    RelocationEncoding, RelocationKind, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+};
+use target_lexicon::{Architecture, BinaryFormat};
+
+#[test]
+fn unnamed_segment() {
+    let mut object = write::Object::new(BinaryFormat::Macho, Architecture::X86_64);
+
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3; 16], 16);
+    let func = object.add_symbol(write::Symbol {
+        name: b"func".to_vec(),
+        value: 0,
+        size: 16,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[0; 8], 8);
+    object
+        .add_relocation(
+            data,
+            write::Relocation {
+                offset: 0,
+                size: 64,
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                symbol: func,
+                addend: 0,
+            },
+        )
+        .unwrap();
+    let debug_info = object.add_section(
+        b"__DWARF".to_vec(),
+        b"__debug_info".to_vec(),
+        SectionKind::Debug,
+    );
+    object.append_section_data(debug_info, &[1; 4], 1);
+
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&bytes).unwrap();
+    assert_eq!(object.format(), BinaryFormat::Macho);
+
+    let segments = object.segments().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].name(), Ok(None));
+    assert_eq!(segments[0].address(), 0);
+
+    let text = object.section_by_name(".text").unwrap();
+    assert_eq!(text.name(), Ok("__text"));
+    assert_eq!(text.segment_name(), Ok(Some("__TEXT")));
+    assert_eq!(text.kind(), SectionKind::Text);
+    assert_eq!(text.address(), 0);
+
+    let data = object.section_by_name("__data").unwrap();
+    assert_eq!(data.segment_name(), Ok(Some("__DATA")));
+    assert_eq!(data.kind(), SectionKind::Data);
+    let relocations = data.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 0);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::Absolute);
+
+    let debug_info = object.section_by_name(".debug_info").unwrap();
+    assert_eq!(debug_info.name(), Ok("__debug_info"));
+    assert_eq!(debug_info.segment_name(), Ok(Some("__DWARF")));
+    assert_eq!(debug_info.kind(), SectionKind::Debug);
+    assert_eq!(debug_info.data(), Ok(&[1; 4][..]));
+    assert!(object.has_debug_symbols());
+}
+
+#[test]
+fn x86_64_got_tlv_relocations() {
+    let mut object = write::Object::new(BinaryFormat::Macho, Architecture::X86_64);
+
+    let var = object.add_symbol(write::Symbol {
+        name: b"var".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Data,
+        scope: SymbolScope::Dynamic,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::None,
+    });
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0x90; 24], 16);
+    let expected = [
+        (
+            RelocationKind::GotRelative,
+            RelocationEncoding::X86RipRelativeMovq,
+        ),
+        (
+            RelocationKind::GotRelative,
+            RelocationEncoding::X86RipRelative,
+        ),
+        (
+            RelocationKind::TlvRelative,
+            RelocationEncoding::X86RipRelativeMovq,
+        ),
+    ];
+    for (i, &(kind, encoding)) in expected.iter().enumerate() {
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset: 3 + 8 * i as u64,
+                    size: 32,
+                    kind,
+                    encoding,
+                    symbol: var,
+                    addend: -4,
+                },
+            )
+            .unwrap();
+    }
+
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&bytes).unwrap();
+    let text = object.section_by_name("__text").unwrap();
+    let mut relocations = text
+        .relocations()
+        .map(|(offset, relocation)| (offset, relocation.kind(), relocation.encoding()))
+        .collect::<Vec<_>>();
+    relocations.sort_by_key(|relocation| relocation.0);
+    assert_eq!(
+        relocations,
+        expected
+            .iter()
+            .enumerate()
+            .map(|(i, &(kind, encoding))| (3 + 8 * i as u64, kind, encoding))
+            .collect::<Vec<_>>()
+    );
+}
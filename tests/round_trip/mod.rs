@@ -11,6 +11,7 @@ use target_lexicon::{Architecture, BinaryFormat};
 mod bss;
 mod common;
 mod elf;
+mod macho;
 mod tls;
 
 #[test]